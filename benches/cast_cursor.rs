@@ -0,0 +1,112 @@
+use criterion::{criterion_group, criterion_main, Criterion};
+use intertrait::cast::*;
+use intertrait::*;
+
+trait Node: CastFrom {
+    fn children(&self) -> &[Box<dyn Node>];
+}
+
+trait Visit {
+    fn visit(&self) -> u32;
+}
+
+#[cast_to(Visit)]
+struct Group(Vec<Box<dyn Node>>);
+
+impl Node for Group {
+    fn children(&self) -> &[Box<dyn Node>] {
+        &self.0
+    }
+}
+
+impl Visit for Group {
+    fn visit(&self) -> u32 {
+        1
+    }
+}
+
+#[cast_to(Visit)]
+struct Leaf(u32);
+
+impl Node for Leaf {
+    fn children(&self) -> &[Box<dyn Node>] {
+        &[]
+    }
+}
+
+impl Visit for Leaf {
+    fn visit(&self) -> u32 {
+        self.0
+    }
+}
+
+/// Builds a tree whose leaves form large homogeneous runs.
+fn tree(depth: u32) -> Box<dyn Node> {
+    if depth == 0 {
+        Box::new(Group((0..1000).map(|i| Box::new(Leaf(i)) as _).collect()))
+    } else {
+        Box::new(Group((0..4).map(|_| tree(depth - 1)).collect()))
+    }
+}
+
+fn walk(node: &dyn Node) -> u32 {
+    node.cast::<dyn Visit>().unwrap().visit()
+        + node
+            .children()
+            .iter()
+            .map(|child| walk(&**child))
+            .sum::<u32>()
+}
+
+fn walk_with_cursor(node: &dyn Node, cursor: &mut CastCursor<dyn Visit>) -> u32 {
+    cursor.cast_ref(node).unwrap().visit()
+        + node
+            .children()
+            .iter()
+            .map(|child| walk_with_cursor(&**child, cursor))
+            .sum::<u32>()
+}
+
+/// Sums the leaves of the last group in `node`, resolving the caster on the first leaf.
+fn sum_leaves_with_handle(node: &dyn Node) -> u32 {
+    let leaves = node.children();
+    let (first, handle) = (*leaves[0]).cast_handled::<dyn Visit>().unwrap();
+    first.visit()
+        + leaves[1..]
+            .iter()
+            .map(|leaf| handle.cast_ref(&**leaf).unwrap().visit())
+            .sum::<u32>()
+}
+
+fn sum_leaves(node: &dyn Node) -> u32 {
+    node.children()
+        .iter()
+        .map(|leaf| (**leaf).cast::<dyn Visit>().unwrap().visit())
+        .sum()
+}
+
+fn homogeneous_subtree(c: &mut Criterion) {
+    let root = tree(3);
+    assert_eq!(
+        walk(&*root),
+        walk_with_cursor(&*root, &mut CastCursor::new())
+    );
+
+    let mut group = c.benchmark_group("walk 64000 leaves");
+    group.bench_function("cast", |b| b.iter(|| walk(&*root)));
+    group.bench_function("cursor", |b| {
+        b.iter(|| walk_with_cursor(&*root, &mut CastCursor::new()))
+    });
+    group.finish();
+
+    let leaves = tree(0);
+    assert_eq!(sum_leaves(&*leaves), sum_leaves_with_handle(&*leaves));
+
+    let mut group = c.benchmark_group("sum 1000 leaves");
+    group.bench_function("cast", |b| b.iter(|| sum_leaves(&*leaves)));
+    group.bench_function("handle", |b| b.iter(|| sum_leaves_with_handle(&*leaves)));
+    group.finish();
+}
+
+criterion_group!(benches, homogeneous_subtree);
+criterion_main!(benches);
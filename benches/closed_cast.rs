@@ -0,0 +1,76 @@
+//! Casts over a closed set of types with `closed_cast!` against the global registry.
+use criterion::{criterion_group, criterion_main, Criterion};
+use intertrait::cast::*;
+use intertrait::*;
+
+trait Shape: CastFrom {}
+
+trait Area {
+    fn area(&self) -> u64;
+}
+
+#[cast_to(Area)]
+struct Circle(u64);
+
+#[cast_to(Area)]
+struct Square(u64);
+
+impl Shape for Circle {}
+impl Shape for Square {}
+
+impl Area for Circle {
+    fn area(&self) -> u64 {
+        3 * self.0 * self.0
+    }
+}
+
+impl Area for Square {
+    fn area(&self) -> u64 {
+        self.0 * self.0
+    }
+}
+
+closed_cast! {
+    enum Node: Circle, Square => dyn Area
+}
+
+fn casts(c: &mut Criterion) {
+    let shapes: Vec<Box<dyn Shape>> = (0..1024)
+        .map(|i| -> Box<dyn Shape> {
+            if i % 2 == 0 {
+                Box::new(Circle(i))
+            } else {
+                Box::new(Square(i))
+            }
+        })
+        .collect();
+    let nodes: Vec<Node> = (0..1024)
+        .map(|i| {
+            if i % 2 == 0 {
+                Node::from(Circle(i))
+            } else {
+                Node::from(Square(i))
+            }
+        })
+        .collect();
+
+    c.bench_function("registry cast over 1024 shapes", |b| {
+        b.iter(|| -> u64 {
+            shapes
+                .iter()
+                .map(|shape| (**shape).cast::<dyn Area>().unwrap().area())
+                .sum()
+        })
+    });
+    c.bench_function("closed cast over 1024 shapes", |b| {
+        b.iter(|| -> u64 {
+            nodes
+                .iter()
+                .map(|node| node.closed_cast::<dyn Area>().unwrap().area())
+                .sum()
+        })
+    });
+}
+
+criterion_group!(benches, casts);
+criterion_main!(benches);
@@ -0,0 +1,56 @@
+//! Repeated `impls` queries over a set of types and traits, mostly failing ones.
+//!
+//! Run with `--features dynamic` and `--features dynamic,impls-cache` to compare. With `dynamic`,
+//! a failing query goes through the locks of the casts allowed at runtime, which the cache skips.
+use criterion::{criterion_group, criterion_main, Criterion};
+use intertrait::cast::*;
+use intertrait::*;
+
+trait Component: CastFrom {}
+
+trait Render {}
+trait Collide {}
+trait Animate {}
+trait Persist {}
+
+macro_rules! components {
+    ($($ty:ident),*) => {
+        $(
+            #[cast_to(Render)]
+            struct $ty;
+            impl Component for $ty {}
+            impl Render for $ty {}
+        )*
+
+        fn components() -> Vec<Box<dyn Component>> {
+            vec![$(Box::new($ty)),*]
+        }
+    };
+}
+
+components!(C0, C1, C2, C3, C4, C5, C6, C7, C8, C9, C10, C11, C12, C13, C14, C15);
+
+fn query(components: &[Box<dyn Component>]) -> usize {
+    components
+        .iter()
+        .map(|component| {
+            let component = &**component;
+            component.impls::<dyn Render>() as usize
+                + component.impls::<dyn Collide>() as usize
+                + component.impls::<dyn Animate>() as usize
+                + component.impls::<dyn Persist>() as usize
+        })
+        .sum()
+}
+
+fn repeated_queries(c: &mut Criterion) {
+    let components: Vec<_> = (0..64).flat_map(|_| components()).collect();
+    assert_eq!(query(&components), components.len());
+
+    c.bench_function("impls over 1024 components x 4 traits", |b| {
+        b.iter(|| query(&components))
+    });
+}
+
+criterion_group!(benches, repeated_queries);
+criterion_main!(benches);
@@ -0,0 +1,112 @@
+//! Casting trait objects behind a user-defined reference-counted pointer.
+use std::any::Any;
+use std::cell::Cell;
+use std::ops::Deref;
+use std::ptr::NonNull;
+
+use intertrait::cast::*;
+use intertrait::*;
+
+/// A minimal single-threaded reference-counted pointer, which keeps the count apart from
+/// the value so that a pointer to the value alone can be rebuilt after a cast.
+struct Counted<T: ?Sized> {
+    count: NonNull<Cell<usize>>,
+    value: NonNull<T>,
+}
+
+impl<T: ?Sized> Counted<T> {
+    fn from_box(value: Box<T>) -> Self {
+        Counted {
+            count: NonNull::from(Box::leak(Box::new(Cell::new(1)))),
+            value: NonNull::from(Box::leak(value)),
+        }
+    }
+
+    fn count(&self) -> usize {
+        unsafe { self.count.as_ref() }.get()
+    }
+}
+
+impl<T: ?Sized> Clone for Counted<T> {
+    fn clone(&self) -> Self {
+        let count = unsafe { self.count.as_ref() };
+        count.set(count.get() + 1);
+        Counted {
+            count: self.count,
+            value: self.value,
+        }
+    }
+}
+
+impl<T: ?Sized> Deref for Counted<T> {
+    type Target = T;
+
+    fn deref(&self) -> &T {
+        unsafe { self.value.as_ref() }
+    }
+}
+
+impl<T: ?Sized> Drop for Counted<T> {
+    fn drop(&mut self) {
+        let count = unsafe { self.count.as_ref() };
+        count.set(count.get() - 1);
+        if count.get() == 0 {
+            unsafe {
+                drop(Box::from_raw(self.count.as_ptr()));
+                drop(Box::from_raw(self.value.as_ptr()));
+            }
+        }
+    }
+}
+
+impl<S: ?Sized + CastFrom, T: ?Sized> CastPointer<T> for Counted<S> {
+    type Output = Counted<T>;
+
+    fn as_any(&self) -> &dyn Any {
+        (**self).ref_any()
+    }
+
+    fn downcast(self, cast: fn(&dyn Any) -> &T) -> Counted<T> {
+        // The value cast is in the same allocation, which `Box` can free through `T`.
+        let cast = Counted {
+            count: self.count,
+            value: NonNull::from(cast((*self).ref_any())),
+        };
+        // The reference held by `self` is moved into `cast`.
+        std::mem::forget(self);
+        cast
+    }
+}
+
+trait Source: CastFrom {}
+
+trait Greet {
+    fn greet(&self) -> String;
+}
+
+struct Data(&'static str);
+
+#[cast_to]
+impl Greet for Data {
+    fn greet(&self) -> String {
+        format!("Hello, {}", self.0)
+    }
+}
+
+impl Source for Data {}
+
+struct Other;
+
+impl Source for Other {}
+
+fn main() {
+    let source: Counted<dyn Source> = Counted::from_box(Box::new(Data("world")));
+    let kept = source.clone();
+    let greet = pointer_cast::<dyn Greet, _>(source).unwrap_or_else(|_| panic!("casting failed"));
+    println!("{} (shared by {})", greet.greet(), greet.count());
+    drop(kept);
+    assert_eq!(greet.count(), 1);
+
+    let other: Counted<dyn Source> = Counted::from_box(Box::new(Other));
+    assert!(pointer_cast::<dyn Greet, _>(other).is_err());
+}
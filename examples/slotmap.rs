@@ -0,0 +1,41 @@
+//! Casting components stored in a `SlotMap` and referred to by handles.
+use intertrait::cast::*;
+use intertrait::*;
+use slotmap::{DefaultKey, SlotMap};
+
+trait Component: CastFrom {}
+
+trait Render {
+    fn render(&self) -> String;
+}
+
+struct Sprite(&'static str);
+
+#[cast_to]
+impl Render for Sprite {
+    fn render(&self) -> String {
+        format!("sprite {}", self.0)
+    }
+}
+
+impl Component for Sprite {}
+
+struct Collider;
+
+impl Component for Collider {}
+
+fn main() {
+    let mut components: SlotMap<DefaultKey, Box<dyn Component>> = SlotMap::new();
+    let handles = vec![
+        components.insert(Box::new(Sprite("player"))),
+        components.insert(Box::new(Collider)),
+        components.insert(Box::new(Sprite("enemy"))),
+    ];
+
+    for handle in handles {
+        match components.get_cast::<dyn Render>(handle) {
+            Some(render) => println!("{}", render.render()),
+            None => println!("not renderable"),
+        }
+    }
+}
@@ -3,16 +3,18 @@ use std::collections::HashSet;
 use syn::bracketed;
 use syn::parse::{Parse, ParseStream, Result};
 use syn::punctuated::Punctuated;
-use syn::{Error, Ident, Path, Token, Type};
+use syn::{Attribute, Error, Ident, LitStr, Path, Token, Type, Visibility};
 
 #[derive(Hash, PartialEq, Eq)]
 pub enum Flag {
+    Send,
     Sync,
 }
 
 impl Flag {
     fn from(ident: &Ident) -> Result<Self> {
         match ident.to_string().as_str() {
+            "send" => Ok(Flag::Send),
             "sync" => Ok(Flag::Sync),
             unknown => {
                 let msg = format!("Unknown flag: {}", unknown);
@@ -22,43 +24,203 @@ impl Flag {
     }
 }
 
+/// A target of `Deref` given as `deref_to = Type` or `deref_mut_to = Type`, where the latter
+/// also allows for dereferencing mutably.
+pub struct DerefTarget {
+    pub ty: Type,
+    pub mutable: bool,
+}
+
 pub struct Targets {
+    pub krate: Option<Path>,
+    pub deref_to: Option<DerefTarget>,
+    pub label: Option<LitStr>,
+    pub registry: Option<Path>,
     pub flags: HashSet<Flag>,
     pub paths: Vec<Path>,
+    pub markers: Vec<Path>,
+}
+
+impl Targets {
+    /// Rejects a `Deref` relationship or a marker along with `registry = PATH`, which only
+    /// the global registry holds.
+    pub fn validate(self) -> Result<Self> {
+        if let Some(registry) = &self.registry {
+            if self.deref_to.is_some() || !self.markers.is_empty() {
+                let msg =
+                    "Only casters can be registered into a registry other than the global one";
+                return Err(Error::new_spanned(registry, msg));
+            }
+        }
+        Ok(self)
+    }
 }
 
 impl Parse for Targets {
     fn parse(input: ParseStream) -> Result<Self> {
+        let mut krate = None;
+        let mut deref_to = None;
+        let mut label = None;
+        let mut registry = None;
         let mut flags = HashSet::new();
         let mut paths = Vec::new();
+        let mut markers = Vec::new();
 
         if input.is_empty() {
-            return Ok(Targets { flags, paths });
+            return Ok(Targets {
+                krate,
+                deref_to,
+                label,
+                registry,
+                flags,
+                paths,
+                markers,
+            });
         }
 
-        if input.peek(syn::token::Bracket) {
-            let content;
-            bracketed!(content in input);
-            for ident in Punctuated::<Ident, Token![,]>::parse_terminated(&content)? {
-                if !flags.insert(Flag::from(&ident)?) {
-                    let msg = format!("Duplicated flag: {}", ident);
-                    return Err(Error::new_spanned(ident, msg));
+        if input.peek(Token![crate]) && input.peek2(Token![=]) {
+            krate = Some(parse_crate_path(input)?);
+            if input.is_empty() {
+                return Ok(Targets {
+                    krate,
+                    deref_to,
+                    label,
+                    registry,
+                    flags,
+                    paths,
+                    markers,
+                });
+            }
+            input.parse::<Token![,]>()?;
+        }
+
+        while input.peek(Ident) && input.peek2(Token![=]) {
+            let key: Ident = input.parse()?;
+            input.parse::<Token![=]>()?;
+            match key.to_string().as_str() {
+                "deref_to" | "deref_mut_to" if deref_to.is_none() => {
+                    deref_to = Some(DerefTarget {
+                        ty: input.parse()?,
+                        mutable: key == "deref_mut_to",
+                    })
                 }
+                "label" if label.is_none() => label = Some(input.parse()?),
+                "registry" if registry.is_none() => registry = Some(input.parse()?),
+                "deref_to" | "deref_mut_to" | "label" | "registry" => {
+                    let msg = format!("Duplicated key: {}", key);
+                    return Err(Error::new_spanned(key, msg));
+                }
+                unknown => {
+                    let msg = format!("Unknown key: {}", unknown);
+                    return Err(Error::new_spanned(key, msg));
+                }
+            }
+            if input.is_empty() {
+                return Ok(Targets {
+                    krate,
+                    deref_to,
+                    label,
+                    registry,
+                    flags,
+                    paths,
+                    markers,
+                });
             }
+            input.parse::<Token![,]>()?;
+        }
+
+        if input.peek(syn::token::Bracket) {
+            flags = parse_flags(input)?;
         }
 
         if input.is_empty() {
-            return Ok(Targets { flags, paths });
+            return Ok(Targets {
+                krate,
+                deref_to,
+                label,
+                registry,
+                flags,
+                paths,
+                markers,
+            });
         }
 
-        paths = Punctuated::<Path, Token![,]>::parse_terminated(input)?
-            .into_iter()
-            .collect();
+        while !input.is_empty() {
+            if peek_marker(input) {
+                input.parse::<Ident>()?;
+                markers.push(input.parse()?);
+            } else {
+                paths.push(input.parse()?);
+            }
+            if input.is_empty() {
+                break;
+            }
+            input.parse::<Token![,]>()?;
+        }
 
-        Ok(Targets { flags, paths })
+        Ok(Targets {
+            krate,
+            deref_to,
+            label,
+            registry,
+            flags,
+            paths,
+            markers,
+        })
     }
 }
 
+/// Tests if `marker Trait` follows, which registers a trait not forming trait objects.
+fn peek_marker(input: ParseStream) -> bool {
+    let fork = input.fork();
+    match fork.parse::<Ident>() {
+        Ok(ident) => ident == "marker" && fork.peek(Ident),
+        Err(_) => false,
+    }
+}
+
+/// Parses `[flag1, flag2, ...]`.
+fn parse_flags(input: ParseStream) -> Result<HashSet<Flag>> {
+    let mut flags = HashSet::new();
+    let content;
+    bracketed!(content in input);
+    for ident in Punctuated::<Ident, Token![,]>::parse_terminated(&content)? {
+        if !flags.insert(Flag::from(&ident)?) {
+            let msg = format!("Duplicated flag: {}", ident);
+            return Err(Error::new_spanned(ident, msg));
+        }
+    }
+    Ok(flags)
+}
+
+/// Parses `crate = path::to::intertrait`.
+fn parse_crate_path(input: ParseStream) -> Result<Path> {
+    input.parse::<Token![crate]>()?;
+    input.parse::<Token![=]>()?;
+    input.call(Path::parse_mod_style)
+}
+
+/// Removes `#[intertrait(crate = path::to::intertrait)]` from `attrs` and combines the path
+/// in it with the one given inline in the arguments of the macro.
+pub fn take_crate_path(attrs: &mut Vec<Attribute>, inline: Option<Path>) -> Result<Option<Path>> {
+    let mut krate = inline;
+    let mut result = Ok(());
+    attrs.retain(|attr| {
+        if !attr.path.is_ident("intertrait") {
+            return true;
+        }
+        match attr.parse_args_with(parse_crate_path) {
+            Ok(path) if krate.is_none() => krate = Some(path),
+            Ok(path) => {
+                result = Err(Error::new_spanned(path, "Duplicated crate path"));
+            }
+            Err(err) => result = Err(err),
+        }
+        false
+    });
+    result.map(|_| krate)
+}
+
 pub struct Casts {
     pub ty: Type,
     pub targets: Targets,
@@ -71,7 +233,139 @@ impl Parse for Casts {
 
         Ok(Casts {
             ty,
-            targets: input.parse()?,
+            targets: input.parse::<Targets>()?.validate()?,
+        })
+    }
+}
+
+/// The input of `register_all!`, which is `Type => [Trait1, Trait2, ...]` optionally with
+/// the path to `intertrait` and flags before the list of traits, i.e.
+/// `Type => crate = path::to::intertrait, [sync] [Trait1, Trait2, ...]`.
+pub struct Batch {
+    pub ty: Type,
+    pub krate: Option<Path>,
+    pub flags: HashSet<Flag>,
+    pub paths: Vec<Path>,
+}
+
+impl Parse for Batch {
+    fn parse(input: ParseStream) -> Result<Self> {
+        let ty: Type = input.parse()?;
+        input.parse::<Token![=>]>()?;
+
+        let mut krate = None;
+        if input.peek(Token![crate]) && input.peek2(Token![=]) {
+            krate = Some(parse_crate_path(input)?);
+            input.parse::<Token![,]>()?;
+        }
+
+        let mut flags = HashSet::new();
+        if input.peek(syn::token::Bracket) && input.peek2(syn::token::Bracket) {
+            flags = parse_flags(input)?;
+        }
+
+        let content;
+        bracketed!(content in input);
+        let paths = Punctuated::<Path, Token![,]>::parse_terminated(&content)?
+            .into_iter()
+            .collect();
+
+        Ok(Batch {
+            ty,
+            krate,
+            flags,
+            paths,
         })
     }
 }
+
+/// The input of `closed_cast!`, which is `enum Name: Type1, Type2 => dyn Trait1, dyn Trait2`
+/// optionally with attributes and visibility before `enum`, and the path to `intertrait`
+/// before the list of traits.
+pub struct Closed {
+    pub attrs: Vec<Attribute>,
+    pub vis: Visibility,
+    pub ident: Ident,
+    pub types: Vec<Path>,
+    pub krate: Option<Path>,
+    pub targets: Vec<Type>,
+}
+
+impl Parse for Closed {
+    fn parse(input: ParseStream) -> Result<Self> {
+        let attrs = input.call(Attribute::parse_outer)?;
+        let vis = input.parse()?;
+        input.parse::<Token![enum]>()?;
+        let ident = input.parse()?;
+        input.parse::<Token![:]>()?;
+
+        let mut types = Vec::new();
+        loop {
+            types.push(input.parse()?);
+            if input.peek(Token![=>]) {
+                break;
+            }
+            input.parse::<Token![,]>()?;
+        }
+        input.parse::<Token![=>]>()?;
+
+        let mut krate = None;
+        if input.peek(Token![crate]) && input.peek2(Token![=]) {
+            krate = Some(parse_crate_path(input)?);
+            input.parse::<Token![,]>()?;
+        }
+
+        let targets: Vec<Type> = Punctuated::<Type, Token![,]>::parse_terminated(input)?
+            .into_iter()
+            .collect();
+        if targets.is_empty() {
+            return Err(input.error("expected at least one trait"));
+        }
+
+        Ok(Closed {
+            attrs,
+            vis,
+            ident,
+            types,
+            krate,
+            targets,
+        })
+    }
+}
+
+/// The arguments of `#[cast_marker]`, which is either empty or `crate = path::to::intertrait`.
+pub struct MarkerArgs {
+    pub krate: Option<Path>,
+}
+
+impl Parse for MarkerArgs {
+    fn parse(input: ParseStream) -> Result<Self> {
+        if input.is_empty() {
+            return Ok(MarkerArgs { krate: None });
+        }
+        Ok(MarkerArgs {
+            krate: Some(parse_crate_path(input)?),
+        })
+    }
+}
+
+/// The input of `registry!`, which is `static NAME` optionally with attributes and visibility
+/// before `static`, including `#[intertrait(crate = path::to::intertrait)]`.
+pub struct RegistryDecl {
+    pub attrs: Vec<Attribute>,
+    pub vis: Visibility,
+    pub ident: Ident,
+}
+
+impl Parse for RegistryDecl {
+    fn parse(input: ParseStream) -> Result<Self> {
+        let attrs = input.call(Attribute::parse_outer)?;
+        let vis = input.parse()?;
+        input.parse::<Token![static]>()?;
+        let ident = input.parse()?;
+        if !input.is_empty() {
+            input.parse::<Token![;]>()?;
+        }
+        Ok(RegistryDecl { attrs, vis, ident })
+    }
+}
@@ -0,0 +1,94 @@
+use std::collections::HashSet;
+
+use proc_macro2::TokenStream;
+use quote::{quote, quote_spanned};
+use syn::spanned::Spanned;
+use syn::{Error, Ident, Type, TypeParamBound};
+
+use crate::args::Closed;
+use crate::gen_caster::crate_path;
+
+/// Generates an enum with a variant for each of the types, named after the last segment of
+/// its path, along with an `impl From` for each type and a `ClosedTarget` for each trait
+/// dispatching to the variants with a `match`.
+pub fn process(input: Closed) -> TokenStream {
+    let Closed {
+        attrs,
+        vis,
+        ident,
+        types,
+        krate,
+        targets,
+    } = input;
+
+    let mut seen = HashSet::new();
+    let mut variants = Vec::new();
+    for ty in &types {
+        let variant: &Ident = &ty.segments.last().unwrap().ident;
+        if !seen.insert(variant.clone()) {
+            let msg = format!("Duplicated variant: {}", variant);
+            return Error::new_spanned(ty, msg).to_compile_error();
+        }
+        variants.push(variant);
+    }
+
+    let krate = crate_path(krate);
+    let froms = types.iter().zip(&variants).map(|(ty, variant)| {
+        quote! {
+            impl ::std::convert::From<#ty> for #ident {
+                fn from(value: #ty) -> Self {
+                    #ident::#variant(value)
+                }
+            }
+        }
+    });
+    let impls = targets.iter().map(|target| {
+        // Spanned so that an error about a type not implementing the trait points at the trait.
+        let span = target.span();
+        let target = with_static_bound(target);
+        let arms_ref = variants
+            .iter()
+            .map(|variant| quote_spanned!(span => #ident::#variant(value) => Some(value),));
+        let arms_mut = arms_ref.clone();
+        quote_spanned! {span =>
+            impl #krate::cast::ClosedTarget<#target> for #ident {
+                fn target_ref(&self) -> Option<&(#target)> {
+                    match self {
+                        #(#arms_ref)*
+                    }
+                }
+
+                fn target_mut(&mut self) -> Option<&mut (#target)> {
+                    match self {
+                        #(#arms_mut)*
+                    }
+                }
+            }
+        }
+    });
+
+    quote! {
+        #(#attrs)*
+        #vis enum #ident {
+            #(#variants(#types),)*
+        }
+        #(#froms)*
+        #(#impls)*
+    }
+}
+
+/// Adds `'static` to a trait object without a lifetime bound, which would otherwise be
+/// elided to the lifetime of the reference in the return type and mismatch the trait.
+fn with_static_bound(target: &Type) -> Type {
+    let mut target = target.clone();
+    if let Type::TraitObject(object) = &mut target {
+        if !object
+            .bounds
+            .iter()
+            .any(|bound| matches!(bound, TypeParamBound::Lifetime(_)))
+        {
+            object.bounds.push(syn::parse_quote!('static));
+        }
+    }
+    target
+}
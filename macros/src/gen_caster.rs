@@ -1,19 +1,90 @@
+use std::collections::HashSet;
 use std::str::from_utf8_unchecked;
 
-use proc_macro2::TokenStream;
+use proc_macro2::{Group, TokenStream, TokenTree};
 use uuid::adapter::Simple;
 use uuid::Uuid;
 
 use quote::format_ident;
 use quote::quote;
+use quote::quote_spanned;
 use quote::ToTokens;
+use syn::spanned::Spanned;
+use syn::{Attribute, Generics, Ident, LitStr, Path, Visibility};
 
-pub fn generate_caster(ty: &impl ToTokens, trait_: &impl ToTokens, sync: bool) -> TokenStream {
+use crate::args::Flag;
+
+/// Returns the path to `intertrait` crate to be used in the generated code.
+pub fn crate_path(krate: Option<Path>) -> TokenStream {
+    match krate {
+        Some(path) => path.into_token_stream(),
+        None => quote!(::intertrait),
+    }
+}
+
+/// Generates the registration of the casters from `ty` into `trait_`, along with those into
+/// the trait objects for `trait_` with the auto traits requested by `flags`, which are
+/// registered into the registry at `registry` if given, and the global one otherwise.
+pub fn generate_caster(
+    krate: &TokenStream,
+    ty: &impl ToTokens,
+    trait_: &impl ToTokens,
+    label: Option<&LitStr>,
+    registry: Option<&Path>,
+    flags: &HashSet<Flag>,
+) -> TokenStream {
+    let sync = flags.contains(&Flag::Sync);
+    let span = trait_.span();
+    let mut bounds = vec![TokenStream::new()];
+    if flags.contains(&Flag::Send) {
+        bounds.push(quote_spanned!(span => + ::core::marker::Send));
+    }
+    if sync {
+        bounds.push(quote_spanned!(span => + ::core::marker::Send + ::core::marker::Sync));
+    }
+    bounds
+        .iter()
+        .map(|bounds| generate_variant(krate, ty, trait_, bounds, label, registry, sync))
+        .collect()
+}
+
+/// Generates the registration of a caster from `ty` into `dyn trait_` followed by `bounds`.
+fn generate_variant(
+    krate: &TokenStream,
+    ty: &impl ToTokens,
+    trait_: &impl ToTokens,
+    bounds: &TokenStream,
+    label: Option<&LitStr>,
+    registry: Option<&Path>,
+    sync: bool,
+) -> TokenStream {
     let mut fn_buf = [0u8; FN_BUF_LEN];
-    let fn_ident = format_ident!("{}", new_fn_name(&mut fn_buf));
+    let entry_ident = format_ident!("{}", new_fn_name(&mut fn_buf));
+    let caster_ident = format_ident!("{}", new_fn_name(&mut fn_buf));
+    let marker_ident = format_ident!("{}", new_fn_name(&mut fn_buf));
+    // Spanned so that an error about the trait object points at the trait.
+    let span = trait_.span();
+    let trait_ = quote_spanned!(span => dyn #trait_ #bounds);
+    let label = label.map(|label| quote!(label: Some(#label),));
+    // The link markers are checked against the global registry only.
+    let (slice, link_marker) = match registry {
+        Some(registry) => (registry_slice_path(registry).into_token_stream(), None),
+        None => {
+            let link_marker = quote_spanned! {span =>
+                #krate::__link_marker! {
+                    #[#krate::linkme::distributed_slice(#krate::LINK_MARKERS)]
+                    #[linkme(crate = #krate::linkme)]
+                    fn #marker_ident() -> #krate::LinkMarker {
+                        #krate::LinkMarker::new::<#ty, #trait_>()
+                    }
+                }
+            };
+            (quote!(#krate::CASTERS), Some(link_marker))
+        }
+    };
     let new_caster = if sync {
-        quote! {
-            ::intertrait::Caster::<dyn #trait_>::new_sync(
+        quote_spanned! {span =>
+            #krate::Caster::<#trait_>::new_sync(
                 |from| from.downcast_ref::<#ty>().unwrap(),
                 |from| from.downcast_mut::<#ty>().unwrap(),
                 |from| from.downcast::<#ty>().unwrap(),
@@ -22,8 +93,8 @@ pub fn generate_caster(ty: &impl ToTokens, trait_: &impl ToTokens, sync: bool) -
             )
         }
     } else {
-        quote! {
-            ::intertrait::Caster::<dyn #trait_>::new(
+        quote_spanned! {span =>
+            #krate::Caster::<#trait_>::new(
                 |from| from.downcast_ref::<#ty>().unwrap(),
                 |from| from.downcast_mut::<#ty>().unwrap(),
                 |from| from.downcast::<#ty>().unwrap(),
@@ -32,14 +103,183 @@ pub fn generate_caster(ty: &impl ToTokens, trait_: &impl ToTokens, sync: bool) -
         }
     };
 
-    quote! {
-        #[::linkme::distributed_slice(::intertrait::CASTERS)]
-        fn #fn_ident() -> (::std::any::TypeId, ::intertrait::BoxedCaster) {
-            (::std::any::TypeId::of::<#ty>(), Box::new(#new_caster))
+    // SAFETY: The caster only coerces the value downcast into the concrete type. The block is
+    // spanned at the call site so that the `unsafe_code` lint of the user doesn't reject it.
+    let new_entry = quote! {
+        unsafe { #krate::CasterEntry::new(::std::any::TypeId::of::<#ty>, &#caster_ident) }
+    };
+
+    quote_spanned! {span =>
+        #krate::__registration! {
+            #[#krate::linkme::distributed_slice(#slice)]
+            #[linkme(crate = #krate::linkme)]
+            #[allow(non_upper_case_globals)]
+            static #entry_ident: #krate::CasterEntry = #new_entry;
+
+            #[allow(non_upper_case_globals)]
+            static #caster_ident: #krate::StaticCaster<#trait_> = #krate::StaticCaster {
+                type_name: ::std::any::type_name::<#ty>,
+                caster: #krate::Caster::<#trait_> {
+                    #label
+                    ..#new_caster
+                },
+            };
+
+            #link_marker
         }
     }
 }
 
+/// Generates the registration of the `Deref` relationship from `ty` to `target`,
+/// which is mutable as well if `mutable` is set.
+pub fn generate_deref(
+    krate: &TokenStream,
+    ty: &impl ToTokens,
+    target: &impl ToTokens,
+    mutable: bool,
+) -> TokenStream {
+    let mut fn_buf = [0u8; FN_BUF_LEN];
+    let fn_ident = format_ident!("{}", new_fn_name(&mut fn_buf));
+    let span = target.span();
+    let deref_mut = if mutable {
+        quote_spanned! {span =>
+            Some(|from| {
+                let target: &mut #target = ::std::ops::DerefMut::deref_mut(from.downcast_mut::<#ty>().unwrap());
+                target
+            })
+        }
+    } else {
+        quote!(None)
+    };
+
+    quote_spanned! {span =>
+        #krate::__registration! {
+            #[#krate::linkme::distributed_slice(#krate::DEREFS)]
+            #[linkme(crate = #krate::linkme)]
+            fn #fn_ident() -> (::std::any::TypeId, #krate::DerefTo) {
+                let deref = #krate::DerefTo {
+                    target: ::std::any::TypeId::of::<#target>(),
+                    deref_ref: |from| {
+                        let target: &#target = ::std::ops::Deref::deref(from.downcast_ref::<#ty>().unwrap());
+                        target
+                    },
+                    deref_mut: #deref_mut,
+                };
+                (::std::any::TypeId::of::<#ty>(), deref)
+            }
+        }
+    }
+}
+
+/// Generates the registration of `ty` having the capability of `trait_`, which is keyed by
+/// the type generated for the trait by `#[cast_marker]` in place of a trait object.
+pub fn generate_marker(krate: &TokenStream, ty: &impl ToTokens, trait_: &Path) -> TokenStream {
+    let key = match marker_path(trait_) {
+        Ok(key) => key,
+        Err(err) => return err.to_compile_error(),
+    };
+    let mut fn_buf = [0u8; FN_BUF_LEN];
+    let fn_ident = format_ident!("{}", new_fn_name(&mut fn_buf));
+    let span = trait_.span();
+    let assertion = quote_spanned! {span =>
+        const _: fn() = || {
+            fn assert_impl<T: ?Sized + #trait_>() {}
+            assert_impl::<#ty>();
+        };
+    };
+
+    quote_spanned! {span =>
+        #assertion
+
+        #krate::__registration! {
+            #[#krate::linkme::distributed_slice(#krate::MARKERS)]
+            #[linkme(crate = #krate::linkme)]
+            fn #fn_ident() -> (::std::any::TypeId, ::std::any::TypeId) {
+                (::std::any::TypeId::of::<#ty>(), ::std::any::TypeId::of::<#key>())
+            }
+        }
+    }
+}
+
+/// Returns the path to the type generated by `#[cast_marker]` for `trait_`, which is named
+/// after the trait with `Marker` appended in the same module.
+pub fn marker_path(trait_: &Path) -> syn::Result<Path> {
+    let mut key = trait_.clone();
+    let last = key.segments.last_mut().unwrap();
+    if !last.arguments.is_empty() {
+        let msg = "A generic trait can't be registered as a marker";
+        return Err(syn::Error::new_spanned(trait_, msg));
+    }
+    last.ident = format_ident!("{}Marker", last.ident);
+    Ok(key)
+}
+
+/// Returns the path to the distributed slice declared by `registry!` for the registry at
+/// `registry`, which is named after the registry with `_CASTERS` appended in the same module.
+pub fn registry_slice_path(registry: &Path) -> Path {
+    let mut slice = registry.clone();
+    let last = slice.segments.last_mut().unwrap();
+    last.ident = format_ident!("{}_CASTERS", last.ident);
+    slice
+}
+
+/// Generates a static `Registry` named `ident` along with the distributed slice gathering
+/// the casters registered into it with `registry = ident`.
+pub fn generate_registry(
+    krate: &TokenStream,
+    attrs: &[Attribute],
+    vis: &Visibility,
+    ident: &Ident,
+) -> TokenStream {
+    let slice = format_ident!("{}_CASTERS", ident);
+    quote! {
+        #[doc(hidden)]
+        #[#krate::linkme::distributed_slice]
+        #[linkme(crate = #krate::linkme)]
+        #vis static #slice: [#krate::CasterEntry] = [..];
+
+        #(#attrs)*
+        #vis static #ident: #krate::Registry = #krate::Registry::from_entries(|| &#slice);
+    }
+}
+
+/// Generates an assertion that `ty` is `'static`, which is required for `TypeId::of`.
+/// Any error from it points at `ty`, along with the generic parameters in `generics`
+/// that `ty` may refer to.
+pub fn generate_static_assertion(ty: &impl ToTokens, generics: &Generics) -> TokenStream {
+    let (impl_generics, _, where_clause) = generics.split_for_impl();
+    // `Self` in the where clause refers to `ty`, which the function isn't in the scope of.
+    let where_clause = replace_self(where_clause.to_token_stream(), &ty.to_token_stream());
+    let assertion = quote_spanned! {
+        ty.span() => assert_static::<#ty>();
+    };
+    quote! {
+        const _: () = {
+            fn assert_static<T: ?Sized + 'static>() {}
+            #[allow(dead_code)]
+            fn assert #impl_generics () #where_clause {
+                #assertion
+            }
+        };
+    }
+}
+
+/// Replaces every `Self` in `tokens` with `ty`.
+fn replace_self(tokens: TokenStream, ty: &TokenStream) -> TokenStream {
+    tokens
+        .into_iter()
+        .flat_map(|token| match token {
+            TokenTree::Ident(ident) if ident == "Self" => ty.clone(),
+            TokenTree::Group(group) => {
+                let mut replaced = Group::new(group.delimiter(), replace_self(group.stream(), ty));
+                replaced.set_span(group.span());
+                TokenTree::Group(replaced).into()
+            }
+            token => token.into(),
+        })
+        .collect()
+}
+
 const FN_PREFIX: &[u8] = b"__";
 const FN_BUF_LEN: usize = FN_PREFIX.len() + Simple::LENGTH;
 
@@ -1,21 +1,35 @@
 use std::collections::HashSet;
 
-use proc_macro2::TokenStream;
+use proc_macro2::{Group, Ident, TokenStream, TokenTree};
 use quote::{quote, quote_spanned, ToTokens};
 use syn::punctuated::Punctuated;
 use syn::spanned::Spanned;
 use syn::Token;
 use syn::{
-    AngleBracketedGenericArguments, Binding, GenericArgument, ImplItem, ItemImpl, Path,
+    AngleBracketedGenericArguments, Binding, GenericArgument, ImplItem, ItemImpl, LitStr, Path,
     PathArguments,
 };
 use PathArguments::AngleBracketed;
 
-use crate::args::Flag;
-use crate::gen_caster::generate_caster;
+use crate::args::{take_crate_path, Flag};
+use crate::gen_caster::{crate_path, generate_caster, generate_static_assertion};
 
-pub fn process(flags: &HashSet<Flag>, input: ItemImpl) -> TokenStream {
+pub fn process(
+    flags: &HashSet<Flag>,
+    krate: Option<Path>,
+    label: Option<LitStr>,
+    registry: Option<Path>,
+    mut input: ItemImpl,
+) -> TokenStream {
+    let krate = match take_crate_path(&mut input.attrs, krate) {
+        Ok(krate) => crate_path(krate),
+        Err(err) => {
+            let err = err.to_compile_error();
+            return quote!(#err #input);
+        }
+    };
     let ItemImpl {
+        ref generics,
         ref self_ty,
         ref trait_,
         ref items,
@@ -31,8 +45,40 @@ pub fn process(flags: &HashSet<Flag>, input: ItemImpl) -> TokenStream {
                 bang.span() => compile_error!("#[cast_to] is not for !Trait impl");
             },
             (None, path, _) => {
-                let path = fully_bound_trait(path, items);
-                generate_caster(self_ty, &path, flags.contains(&Flag::Sync))
+                if generics.type_params().next().is_some()
+                    || generics.const_params().next().is_some()
+                {
+                    // A caster is for a single concrete type and trait, which a generic impl
+                    // doesn't determine.
+                    quote_spanned! {
+                        generics.span() => compile_error!(
+                            "#[cast_to] can't be used on a generic impl; register each \
+                             instantiation instead, e.g. castable_to!(Type<Arg> => Trait<Arg>)"
+                        );
+                    }
+                } else {
+                    // The lifetime parameters can only be 'static for the type to be 'static,
+                    // which the assertion reports if the type refers to them otherwise.
+                    let assertion = generate_static_assertion(self_ty, generics);
+                    let lifetimes: Vec<_> = generics
+                        .lifetimes()
+                        .map(|def| def.lifetime.ident.clone())
+                        .collect();
+                    let self_ty = static_lifetimes(self_ty.to_token_stream(), &lifetimes);
+                    let path = static_lifetimes(
+                        fully_bound_trait(path, items).to_token_stream(),
+                        &lifetimes,
+                    );
+                    let caster = generate_caster(
+                        &krate,
+                        &self_ty,
+                        &path,
+                        label.as_ref(),
+                        registry.as_ref(),
+                        flags,
+                    );
+                    quote!(#assertion #caster)
+                }
             }
         },
     };
@@ -43,6 +89,10 @@ pub fn process(flags: &HashSet<Flag>, input: ItemImpl) -> TokenStream {
     }
 }
 
+/// Binds the associated types of the trait at `path` to those defined in `items`.
+///
+/// Only associated types are taken from `items`, so methods, constants and macros
+/// injected by other attribute macros on the `impl` don't affect the target trait.
 fn fully_bound_trait(path: &Path, items: &[ImplItem]) -> impl ToTokens {
     let bindings = items
         .iter()
@@ -81,3 +131,28 @@ fn fully_bound_trait(path: &Path, items: &[ImplItem]) -> impl ToTokens {
     }
     path
 }
+
+/// Replaces every lifetime named in `lifetimes` in `tokens` with `'static`.
+fn static_lifetimes(tokens: TokenStream, lifetimes: &[Ident]) -> TokenStream {
+    let mut replaced: Vec<TokenTree> = Vec::new();
+    for token in tokens {
+        match token {
+            TokenTree::Ident(ident)
+                if lifetimes.contains(&ident)
+                    && matches!(replaced.last(), Some(TokenTree::Punct(punct)) if punct.as_char() == '\'') =>
+            {
+                replaced.push(TokenTree::Ident(Ident::new("static", ident.span())));
+            }
+            TokenTree::Group(group) => {
+                let mut static_group = Group::new(
+                    group.delimiter(),
+                    static_lifetimes(group.stream(), lifetimes),
+                );
+                static_group.set_span(group.span());
+                replaced.push(TokenTree::Group(static_group));
+            }
+            token => replaced.push(token),
+        }
+    }
+    replaced.into_iter().collect()
+}
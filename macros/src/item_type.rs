@@ -1,15 +1,31 @@
-use std::collections::HashSet;
-
 use proc_macro2::TokenStream;
 use syn::spanned::Spanned;
-use syn::{DeriveInput, Path};
+use syn::DeriveInput;
 
 use quote::{quote, quote_spanned};
 
-use crate::args::Flag;
-use crate::gen_caster::generate_caster;
+use crate::args::{take_crate_path, Targets};
+use crate::gen_caster::{
+    crate_path, generate_caster, generate_deref, generate_marker, generate_static_assertion,
+};
 
-pub fn process(flags: &HashSet<Flag>, paths: Vec<Path>, input: DeriveInput) -> TokenStream {
+pub fn process(targets: Targets, mut input: DeriveInput) -> TokenStream {
+    let Targets {
+        krate,
+        deref_to,
+        label,
+        registry,
+        flags,
+        paths,
+        markers,
+    } = targets;
+    let krate = match take_crate_path(&mut input.attrs, krate) {
+        Ok(krate) => crate_path(krate),
+        Err(err) => {
+            let err = err.to_compile_error();
+            return quote!(#err #input);
+        }
+    };
     let DeriveInput {
         ref ident,
         ref generics,
@@ -20,10 +36,14 @@ pub fn process(flags: &HashSet<Flag>, paths: Vec<Path>, input: DeriveInput) -> T
             generics.span() => compile_error!("#[cast_to(..)] can't be used on a generic type definition");
         }
     } else {
-        paths
-            .into_iter()
-            .flat_map(|t| generate_caster(ident, &t, flags.contains(&Flag::Sync)))
-            .collect()
+        let assertion = generate_static_assertion(ident, generics);
+        let deref =
+            deref_to.map(|target| generate_deref(&krate, ident, &target.ty, target.mutable));
+        let casters = paths.into_iter().flat_map(|t| {
+            generate_caster(&krate, ident, &t, label.as_ref(), registry.as_ref(), &flags)
+        });
+        let markers = markers.iter().map(|t| generate_marker(&krate, ident, t));
+        quote!(#assertion #deref #(#casters)* #(#markers)*)
     };
     quote! {
         #input
@@ -2,12 +2,17 @@ extern crate proc_macro;
 
 use proc_macro::TokenStream;
 
-use syn::{parse, parse_macro_input, DeriveInput, ItemImpl};
+use quote::quote;
+use syn::{parse, parse_macro_input, DeriveInput, Generics, ItemImpl, ItemTrait, Path};
 
-use args::{Casts, Flag, Targets};
-use gen_caster::generate_caster;
+use args::{take_crate_path, Batch, Casts, Closed, MarkerArgs, RegistryDecl, Targets};
+use gen_caster::{
+    crate_path, generate_caster, generate_deref, generate_marker, generate_registry,
+    generate_static_assertion, marker_path,
+};
 
 mod args;
+mod closed_cast;
 mod gen_caster;
 mod item_impl;
 mod item_type;
@@ -17,6 +22,9 @@ mod item_type;
 /// If on an `impl` item, no argument is allowed. But on a type definition, the target traits
 /// must be listed explicitly.
 ///
+/// Neither a generic `impl` nor a generic type definition is allowed, since a cast is registered
+/// for a single concrete type. Register each instantiation with [`castable_to!`] instead.
+///
 /// Add `[sync]` before the list of traits if the underlying type is `Sync + Send` and you
 /// need `std::sync::Arc`.
 ///
@@ -52,7 +60,8 @@ mod item_type;
 /// ```
 ///
 /// ## For Arc
-/// Use when the underlying type is `Sync + Send` and you want to use `Arc`.
+/// Use when the underlying type is `Sync + Send` and you want to use `Arc`. It also registers
+/// a cast into `dyn Trait + Send + Sync` to be stored in thread-safe containers.
 /// ```
 /// use intertrait::*;
 ///
@@ -61,14 +70,158 @@ mod item_type;
 /// #[derive(std::fmt::Debug)]
 /// struct Data;
 /// ```
+///
+/// ## Keeping `Send`
+/// A cast into `dyn Trait` drops the auto traits of the source, e.g. `Send` of
+/// `Box<dyn Source + Send>`. Add `[send]` for the underlying type being `Send` to register
+/// a cast into `dyn Trait + Send` as well, which is cast into as a separate target.
+/// Likewise, `[sync]` registers a cast into `dyn Trait + Send + Sync`.
+/// ```
+/// use intertrait::*;
+/// use intertrait::cast::*;
+///
+/// trait Source: CastFrom {}
+/// trait Greet {
+///     fn greet(&self) -> &'static str;
+/// }
+///
+/// #[cast_to([send] Greet)]
+/// struct Data;
+/// impl Source for Data {}
+/// impl Greet for Data {
+///     fn greet(&self) -> &'static str {
+///         "Hello"
+///     }
+/// }
+///
+/// let source: Box<dyn Source + Send> = Box::new(Data);
+/// let greet = source.cast::<dyn Greet + Send>().unwrap_or_else(|_| panic!());
+/// std::thread::spawn(move || greet.greet()).join().unwrap();
+/// ```
+///
+/// ## With a re-exported `intertrait`
+/// Use when `intertrait` is not a direct dependency but re-exported by another crate.
+/// The path can be given either inline as the first argument or in a separate
+/// `#[intertrait(crate = ...)]` attribute placed after `#[cast_to]`.
+/// ```
+/// # mod framework { pub use intertrait; }
+/// use intertrait::*;
+///
+/// #[cast_to(crate = framework::intertrait, std::fmt::Debug)]
+/// #[derive(std::fmt::Debug)]
+/// struct Data;
+///
+/// #[cast_to(std::fmt::Debug)]
+/// #[intertrait(crate = framework::intertrait)]
+/// #[derive(std::fmt::Debug)]
+/// struct Data2;
+/// ```
+///
+/// ## With a label
+/// A label can be attached to the casts for grouping and documenting them, which is
+/// reported by `intertrait::registry_snapshot`.
+/// ```
+/// use intertrait::*;
+///
+/// #[cast_to(label = "debugging", std::fmt::Debug)]
+/// #[derive(std::fmt::Debug)]
+/// struct Data;
+///
+/// let key = registry_snapshot().into_iter().next().unwrap();
+/// assert_eq!(key.label(), Some("debugging"));
+/// ```
+///
+/// ## Into a registry of your own
+/// With `registry = NAME`, the casts are registered into a [`Registry`] declared with
+/// [`registry!`] instead of the global one, which is looked up only through the registry.
+/// A `Deref` relationship or a marker can't be registered this way.
+/// ```ignore
+/// registry!(pub static PLUGINS);
+///
+/// #[cast_to(registry = PLUGINS, std::fmt::Debug)]
+/// #[derive(std::fmt::Debug)]
+/// struct Data;
+/// ```
+///
+/// ## Through `Deref`
+/// Use when a type (e.g. a newtype) dereferences to another type registered for casting.
+/// With `deref_to = Target`, a reference to the type can be cast into any trait `Target`
+/// can be cast into, unless the type itself is registered for the trait. Use `deref_mut_to`
+/// instead if the type implements `DerefMut` and mutable references need to be cast as well.
+/// The relationships are followed transitively, but only references can be cast this way.
+/// ```
+/// use intertrait::*;
+/// use intertrait::cast::*;
+///
+/// #[cast_to(std::fmt::Debug)]
+/// #[derive(std::fmt::Debug)]
+/// struct Inner;
+///
+/// #[cast_to(deref_to = Inner)]
+/// struct Outer(Inner);
+///
+/// impl std::ops::Deref for Outer {
+///     type Target = Inner;
+///
+///     fn deref(&self) -> &Inner {
+///         &self.0
+///     }
+/// }
+///
+/// let outer: &dyn std::any::Any = &Outer(Inner);
+/// assert!(outer.cast::<dyn std::fmt::Debug>().is_some());
+/// ```
+///
+/// ## As a marker
+/// A trait that can't form trait objects, e.g. one with associated functions only, can be
+/// registered as a capability with `marker` before it, if the trait is annotated with
+/// [`#[cast_marker]`][cast_marker]. It can only be tested with `impls_marker`, but not cast into.
+/// ```ignore
+/// #[cast_to(Greet, marker Spawn)]
+/// struct Data;
+/// ```
+///
+/// ## With other attribute macros
+/// Attribute macros on an item are expanded from the top, so `#[cast_to]` sees the `impl`
+/// as left by the attribute macros above it, and those below it see the `impl` as written.
+/// Either order works as long as the macros keep the trait path and the associated types,
+/// which are all that `#[cast_to]` reads from an `impl`; methods and constants added by
+/// the other macros are ignored. Place `#[cast_to]` first if a macro rewrites the trait path
+/// or the associated types, e.g.
+/// ```ignore
+/// #[cast_to]
+/// #[async_trait]
+/// impl Handler for Data {
+///     async fn handle(&self) {}
+/// }
+/// ```
+///
+/// [`castable_to!`]: ./macro.castable_to.html
+/// [cast_marker]: ./attr.cast_marker.html
+/// [`Registry`]: ./struct.Registry.html
+/// [`registry!`]: ./macro.registry.html
 #[proc_macro_attribute]
 pub fn cast_to(args: TokenStream, input: TokenStream) -> TokenStream {
-    match parse::<Targets>(args) {
-        Ok(Targets { flags, paths }) => {
-            if paths.is_empty() {
-                item_impl::process(&flags, parse_macro_input!(input as ItemImpl))
+    match parse::<Targets>(args).and_then(Targets::validate) {
+        Ok(targets) => {
+            if targets.paths.is_empty() && targets.markers.is_empty() && targets.deref_to.is_none()
+            {
+                let Targets {
+                    krate,
+                    label,
+                    registry,
+                    flags,
+                    ..
+                } = targets;
+                item_impl::process(
+                    &flags,
+                    krate,
+                    label,
+                    registry,
+                    parse_macro_input!(input as ItemImpl),
+                )
             } else {
-                item_type::process(&flags, paths, parse_macro_input!(input as DeriveInput))
+                item_type::process(targets, parse_macro_input!(input as DeriveInput))
             }
         }
         Err(err) => vec![err.to_compile_error(), input.into()]
@@ -83,6 +236,11 @@ pub fn cast_to(args: TokenStream, input: TokenStream) -> TokenStream {
 /// This macro is for registering both a concrete type and its traits to be targets for casting.
 /// Useful when the type definition and the trait implementations are in an external crate.
 ///
+/// A trait implemented for the type through a blanket `impl`, even one in another crate like
+/// `impl<T: Display> ToString for T`, can be listed as well. Only the type and the trait need
+/// to be nameable by the paths given; neither the trait nor the one the blanket `impl` is
+/// bounded by needs to be imported.
+///
 /// **Note**: this macro cannot be used in an expression or statement prior to Rust 1.45.0,
 /// due to [a previous limitation](https://github.com/rust-lang/rust/pull/68717).
 /// If you want to use it in an expression or statement, use Rust 1.45.0 or later.
@@ -129,16 +287,313 @@ pub fn cast_to(args: TokenStream, input: TokenStream) -> TokenStream {
 ///
 /// # fn main() {}
 /// ```
+///
+/// A `Deref` relationship can be registered as described for [`cast_to`] before the traits:
+/// ```ignore
+/// castable_to! { Outer => deref_to = Inner, Greet }
+/// ```
+///
+/// When `intertrait` is re-exported by another crate, give the path to it first:
+/// ```ignore
+/// castable_to! { Data => crate = framework::intertrait, [sync] std::fmt::Debug, Greet }
+/// ```
+///
+/// The casts can be registered into a registry declared with [`registry!`] as well:
+/// ```ignore
+/// castable_to! { Data => registry = PLUGINS, std::fmt::Debug, Greet }
+/// ```
+///
+/// [`cast_to`]: ./attr.cast_to.html
+/// [`registry!`]: ./macro.registry.html
 #[proc_macro]
 pub fn castable_to(input: TokenStream) -> TokenStream {
     let Casts {
         ty,
-        targets: Targets { flags, paths },
+        targets:
+            Targets {
+                krate,
+                deref_to,
+                label,
+                registry,
+                flags,
+                paths,
+                markers,
+            },
+    } = parse_macro_input!(input);
+
+    let krate = crate_path(krate);
+    let assertion = generate_static_assertion(&ty, &Generics::default());
+    let deref = deref_to.map(|target| generate_deref(&krate, &ty, &target.ty, target.mutable));
+    let casters = paths
+        .iter()
+        .map(|t| generate_caster(&krate, &ty, t, label.as_ref(), registry.as_ref(), &flags));
+    let markers = markers.iter().map(|t| generate_marker(&krate, &ty, t));
+    quote!(#assertion #deref #(#casters)* #(#markers)*).into()
+}
+
+/// Declares a batch of target traits for casting implemented by a type, which is a readable
+/// form of [`castable_to!`] for a long list of traits, e.g. all the public traits of a module.
+///
+/// An error about a trait, e.g. not being implemented by the type or not usable as a trait
+/// object, points at the trait in the list.
+///
+/// # Examples
+/// ```
+/// use intertrait::*;
+///
+/// mod traits {
+///     pub trait Greet {}
+///     pub trait Wave {}
+/// }
+///
+/// #[derive(std::fmt::Debug)]
+/// struct Data;
+/// impl traits::Greet for Data {}
+/// impl traits::Wave for Data {}
+///
+/// register_all!(Data => [traits::Greet, traits::Wave, std::fmt::Debug]);
+///
+/// # fn main() {}
+/// ```
+///
+/// Flags and the path to `intertrait` can be given before the list:
+/// ```ignore
+/// register_all!(Data => crate = framework::intertrait, [sync] [traits::Greet, traits::Wave]);
+/// ```
+///
+/// [`castable_to!`]: ./macro.castable_to.html
+#[proc_macro]
+pub fn register_all(input: TokenStream) -> TokenStream {
+    let Batch {
+        ty,
+        krate,
+        flags,
+        paths,
     } = parse_macro_input!(input);
 
-    paths
+    let krate = crate_path(krate);
+    let assertion = generate_static_assertion(&ty, &Generics::default());
+    let casters = paths
         .iter()
-        .map(|t| generate_caster(&ty, t, flags.contains(&Flag::Sync)))
-        .collect::<proc_macro2::TokenStream>()
-        .into()
+        .map(|t| generate_caster(&krate, &ty, t, None, None, &flags));
+    quote!(#assertion #(#casters)*).into()
+}
+
+/// Declares an enum over a closed set of types along with the traits implemented by all of them,
+/// which can be cast into the traits with a `match` instead of a lookup in the global registry.
+///
+/// It expands to an enum with a variant named after the last segment of the path of each type,
+/// an `impl From` for each type, and an `impl ClosedTarget` for each trait, which enables
+/// [`ClosedCast::closed_cast`] in the shape of [`CastRef::cast`]. No `CastFrom` or
+/// registration with [`#[cast_to]`][cast_to] is involved.
+///
+/// An error about a type not implementing a trait points at the trait in the list.
+///
+/// # Examples
+/// ```
+/// use intertrait::*;
+/// use intertrait::cast::*;
+///
+/// trait Draw {
+///     fn draw(&self) -> String;
+/// }
+///
+/// trait Area {
+///     fn area(&self) -> f64;
+/// }
+///
+/// struct Circle(f64);
+/// struct Square(f64);
+///
+/// impl Draw for Circle {
+///     fn draw(&self) -> String {
+///         format!("circle of radius {}", self.0)
+///     }
+/// }
+///
+/// impl Area for Circle {
+///     fn area(&self) -> f64 {
+///         3.0 * self.0 * self.0
+///     }
+/// }
+///
+/// impl Draw for Square {
+///     fn draw(&self) -> String {
+///         format!("square of side {}", self.0)
+///     }
+/// }
+///
+/// impl Area for Square {
+///     fn area(&self) -> f64 {
+///         self.0 * self.0
+///     }
+/// }
+///
+/// closed_cast! {
+///     pub enum Node: Circle, Square => dyn Draw, dyn Area
+/// }
+///
+/// let nodes = vec![Node::from(Circle(1.0)), Node::Square(Square(2.0))];
+/// let area: f64 = nodes.iter().map(|n| n.closed_cast::<dyn Area>().unwrap().area()).sum();
+/// assert_eq!(area, 7.0);
+/// assert_eq!(nodes[0].closed_cast::<dyn Draw>().unwrap().draw(), "circle of radius 1");
+/// ```
+///
+/// Attributes can be given before the enum, and the path to `intertrait` before the traits:
+/// ```ignore
+/// closed_cast! {
+///     #[derive(Debug)]
+///     enum Node: shapes::Circle, shapes::Square => crate = framework::intertrait, dyn Draw
+/// }
+/// ```
+///
+/// [cast_to]: ./attr.cast_to.html
+/// [`ClosedCast::closed_cast`]: ./cast/trait.ClosedCast.html#method.closed_cast
+/// [`CastRef::cast`]: ./cast/trait.CastRef.html#tymethod.cast
+#[proc_macro]
+pub fn closed_cast(input: TokenStream) -> TokenStream {
+    closed_cast::process(parse_macro_input!(input as Closed)).into()
+}
+
+/// Makes a trait that can't form trait objects, e.g. one with associated functions only,
+/// registrable as a capability with `#[cast_to(marker Trait)]`.
+///
+/// It generates an uninhabited type named after the trait with `Marker` appended, with
+/// the same visibility as the trait, which identifies the trait in place of a trait object.
+/// Whether a value has the capability is tested with [`CastRef::impls_marker`], but it can't
+/// be cast into the trait.
+///
+/// # Examples
+/// ```
+/// use intertrait::*;
+/// use intertrait::cast::*;
+///
+/// #[cast_marker]
+/// trait Spawn {
+///     fn spawn() -> Self;
+/// }
+///
+/// #[cast_to(marker Spawn)]
+/// struct Data;
+/// # trait Source: CastFrom {}
+/// impl Source for Data {}
+/// impl Spawn for Data {
+///     fn spawn() -> Self {
+///         Data
+///     }
+/// }
+///
+/// struct Other;
+/// impl Source for Other {}
+///
+/// let sources: [&dyn Source; 2] = [&Data, &Other];
+/// assert!(sources[0].impls_marker::<SpawnMarker>());
+/// assert!(!sources[1].impls_marker::<SpawnMarker>());
+/// ```
+///
+/// A trait with `Marker` is registered the same way with [`castable_to!`], also along with
+/// other traits to cast into:
+/// ```ignore
+/// castable_to!(Data => Greet, marker Spawn);
+/// ```
+///
+/// When `intertrait` is re-exported by another crate, give the path to it:
+/// ```ignore
+/// #[cast_marker(crate = framework::intertrait)]
+/// trait Spawn {
+///     fn spawn() -> Self;
+/// }
+/// ```
+///
+/// [`CastRef::impls_marker`]: ./cast/trait.CastRef.html#tymethod.impls_marker
+/// [`castable_to!`]: ./macro.castable_to.html
+#[proc_macro_attribute]
+pub fn cast_marker(args: TokenStream, input: TokenStream) -> TokenStream {
+    let MarkerArgs { krate } = parse_macro_input!(args);
+    let input = parse_macro_input!(input as ItemTrait);
+    let krate = crate_path(krate);
+    let marker = match marker_path(&Path::from(input.ident.clone())) {
+        Ok(marker) => marker,
+        Err(err) => return err.to_compile_error().into(),
+    };
+    if input.generics.lt_token.is_some() {
+        let err = syn::Error::new_spanned(
+            &input.generics,
+            "#[cast_marker] can't be used on a generic trait",
+        );
+        let err = err.to_compile_error();
+        return quote!(#err #input).into();
+    }
+    let vis = &input.vis;
+    let doc = format!(
+        "Identifies trait `{}` registered with `#[cast_to(marker {})]`.",
+        input.ident, input.ident
+    );
+    quote!(
+        #input
+
+        #[doc = #doc]
+        #vis enum #marker {}
+
+        impl #krate::CastMarker for #marker {}
+    )
+    .into()
+}
+
+/// Declares a static [`Registry`] isolated from the global one, into which casters are
+/// registered with `registry = NAME` given to [`cast_to`] or [`castable_to!`].
+///
+/// It also declares a hidden distributed slice named after the registry with `_CASTERS`
+/// appended, with the same visibility, which gathers the casters registered into it.
+/// As the slice is identified by its name at link time, the name of a registry must be unique
+/// across the program.
+///
+/// # Examples
+/// ```
+/// use intertrait::*;
+///
+/// trait Source: CastFrom {}
+/// trait Greet {
+///     fn greet(&self) -> &'static str;
+/// }
+///
+/// registry!(static PLUGINS);
+///
+/// #[cast_to(registry = PLUGINS)]
+/// impl Greet for Data {
+///     fn greet(&self) -> &'static str {
+///         "Hello"
+///     }
+/// }
+/// struct Data;
+/// impl Source for Data {}
+///
+/// let source: &dyn Source = &Data;
+/// assert_eq!(PLUGINS.cast_ref::<dyn Greet, _>(source).unwrap().greet(), "Hello");
+/// assert!(!cast::CastRef::impls::<dyn Greet>(source));
+/// ```
+///
+/// Attributes can be given before the registry, including the path to `intertrait`:
+/// ```ignore
+/// registry! {
+///     /// The casts of the plugins.
+///     #[intertrait(crate = framework::intertrait)]
+///     pub static PLUGINS;
+/// }
+/// ```
+///
+/// [`Registry`]: ./struct.Registry.html
+/// [`cast_to`]: ./attr.cast_to.html
+/// [`castable_to!`]: ./macro.castable_to.html
+#[proc_macro]
+pub fn registry(input: TokenStream) -> TokenStream {
+    let RegistryDecl {
+        mut attrs,
+        vis,
+        ident,
+    } = parse_macro_input!(input);
+    match take_crate_path(&mut attrs, None) {
+        Ok(krate) => generate_registry(&crate_path(krate), &attrs, &vis, &ident).into(),
+        Err(err) => err.to_compile_error().into(),
+    }
 }
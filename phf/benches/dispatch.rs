@@ -0,0 +1,108 @@
+use criterion::{black_box, criterion_group, criterion_main, Criterion};
+use intertrait::cast::*;
+use intertrait::*;
+
+mod casts {
+    include!(concat!(env!("OUT_DIR"), "/bench_casts.rs"));
+}
+
+trait Source: CastFrom {}
+
+trait Draw {
+    fn draw(&self) -> u32;
+}
+
+trait Area {
+    fn area(&self) -> u32;
+}
+
+#[cast_to(Draw)]
+struct Circle;
+
+impl Draw for Circle {
+    fn draw(&self) -> u32 {
+        1
+    }
+}
+
+impl Source for Circle {}
+
+mod shapes {
+    use intertrait::*;
+
+    #[cast_to(crate::Draw, crate::Area)]
+    pub struct Square(pub u32);
+
+    impl crate::Draw for Square {
+        fn draw(&self) -> u32 {
+            2
+        }
+    }
+
+    impl crate::Area for Square {
+        fn area(&self) -> u32 {
+            self.0 * self.0
+        }
+    }
+
+    impl crate::Source for Square {}
+}
+
+fn sources() -> Vec<Box<dyn Source>> {
+    (0..1000)
+        .map(|i| -> Box<dyn Source> {
+            if i % 2 == 0 {
+                Box::new(Circle)
+            } else {
+                Box::new(shapes::Square(i))
+            }
+        })
+        .collect()
+}
+
+fn dispatch(c: &mut Criterion) {
+    assert!(casts::mismatched_type_names().is_empty());
+    let sources = sources();
+
+    let mut group = c.benchmark_group("cast 1000 refs");
+    group.bench_function("registry", |b| {
+        b.iter(|| {
+            sources
+                .iter()
+                .filter_map(|source| (**source).cast::<dyn Draw>())
+                .map(|draw| draw.draw())
+                .sum::<u32>()
+        })
+    });
+    group.bench_function("phf", |b| {
+        b.iter(|| {
+            sources
+                .iter()
+                .filter_map(|source| casts::draw(black_box(&**source)))
+                .map(|draw| draw.draw())
+                .sum::<u32>()
+        })
+    });
+    group.bench_function("registry, sparse target", |b| {
+        b.iter(|| {
+            sources
+                .iter()
+                .filter_map(|source| (**source).cast::<dyn Area>())
+                .map(|area| area.area())
+                .sum::<u32>()
+        })
+    });
+    group.bench_function("phf, sparse target", |b| {
+        b.iter(|| {
+            sources
+                .iter()
+                .filter_map(|source| casts::area(black_box(&**source)))
+                .map(|area| area.area())
+                .sum::<u32>()
+        })
+    });
+    group.finish();
+}
+
+criterion_group!(benches, dispatch);
+criterion_main!(benches);
@@ -0,0 +1,30 @@
+// Generates the dispatchers used by the tests and the benchmarks of this crate.
+#[path = "src/lib.rs"]
+#[allow(dead_code)]
+mod generator;
+
+use std::env;
+use std::path::Path;
+
+use generator::Generator;
+
+fn main() {
+    let out_dir = env::var("OUT_DIR").unwrap();
+    let out_dir = Path::new(&out_dir);
+    for (krate, file) in &[
+        ("phf-dispatch", "test_casts.rs"),
+        ("dispatch", "bench_casts.rs"),
+    ] {
+        Generator::new(krate)
+            .target(
+                "draw",
+                "crate::Draw",
+                &["crate::Circle", "crate::shapes::Square"],
+            )
+            .target("area", "crate::Area", &["crate::shapes::Square"])
+            .write_to(out_dir.join(file))
+            .unwrap();
+    }
+    println!("cargo:rerun-if-changed=src/lib.rs");
+    println!("cargo:rerun-if-changed=build.rs");
+}
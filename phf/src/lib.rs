@@ -0,0 +1,255 @@
+//! A code generator of cast dispatchers for closed sets of casts, meant to be used
+//! in a build script.
+//!
+//! When all the concrete types castable into a trait are known at build time, casting
+//! doesn't have to go through the global registry of `intertrait`. For each target trait,
+//! [`Generator`] emits functions which dispatch on a hash of the name of the concrete type
+//! with a `match` over the hashes computed at build time, and then downcast the source
+//! to the matched type.
+//!
+//! Since `TypeId` isn't known until runtime, the hashes are computed from the names of
+//! the types as reported by [`std::any::type_name`]. The format of those names is not
+//! guaranteed to be stable, but it doesn't compromise soundness: every dispatch is
+//! verified with a downcast, so a mismatched name only makes the cast fail. The generated
+//! `mismatched_type_names` function reports such names and is worth asserting in a test.
+//!
+//! # Usage
+//! In `build.rs`:
+//! ```no_run
+//! use intertrait_phf::Generator;
+//!
+//! let out_dir = std::env::var("OUT_DIR").unwrap();
+//! Generator::new(&std::env::var("CARGO_PKG_NAME").unwrap())
+//!     .target("draw", "crate::Draw", &["crate::Circle", "crate::shapes::Square"])
+//!     .write_to(std::path::Path::new(&out_dir).join("casts.rs"))
+//!     .unwrap();
+//! ```
+//!
+//! In the crate:
+//! ```ignore
+//! mod casts {
+//!     include!(concat!(env!("OUT_DIR"), "/casts.rs"));
+//! }
+//!
+//! let source: &dyn Source = &Circle;
+//! casts::draw(source).unwrap().draw();
+//! ```
+//!
+//! Each target generates the following functions for a `name` given:
+//! * `name(&S) -> Option<&dyn Trait>`
+//! * `name_mut(&mut S) -> Option<&mut dyn Trait>`
+//! * `name_box(Box<S>) -> Result<Box<dyn Trait>, Box<S>>`
+//!
+//! where `S` is any type implementing `intertrait::CastFrom`.
+//!
+//! [`Generator`]: ./struct.Generator.html
+//! [`std::any::type_name`]: https://doc.rust-lang.org/std/any/fn.type_name.html
+use std::collections::HashMap;
+use std::fmt::Write as _;
+use std::fs;
+use std::io;
+use std::path::Path;
+
+/// Hashes a type name with 64-bit FNV-1a, which is the hash function used by
+/// the generated dispatchers.
+pub fn hash(name: &str) -> u64 {
+    name.bytes().fold(0xcbf2_9ce4_8422_2325, |hash, b| {
+        (hash ^ u64::from(b)).wrapping_mul(0x0100_0000_01b3)
+    })
+}
+
+struct Target {
+    name: String,
+    trait_: String,
+    sources: Vec<String>,
+}
+
+/// A generator of a module with cast dispatchers for closed sets of casts.
+pub struct Generator {
+    krate: String,
+    targets: Vec<Target>,
+}
+
+impl Generator {
+    /// Creates a generator for the code to be included in crate `krate`.
+    ///
+    /// Paths starting with `crate::` are resolved against `krate` to compute the names
+    /// of the types. Hyphens in `krate` are replaced with underscores as Cargo does.
+    pub fn new(krate: &str) -> Self {
+        Generator {
+            krate: krate.replace('-', "_"),
+            targets: Vec::new(),
+        }
+    }
+
+    /// Adds a target trait `trait_` into which the types in `sources` can be cast.
+    /// The generated functions are named after `name`.
+    ///
+    /// All paths must be absolute, either starting with `crate::` or a crate name.
+    pub fn target(&mut self, name: &str, trait_: &str, sources: &[&str]) -> &mut Self {
+        self.targets.push(Target {
+            name: name.to_owned(),
+            trait_: trait_.to_owned(),
+            sources: sources.iter().map(|s| (*s).to_owned()).collect(),
+        });
+        self
+    }
+
+    /// Returns the name of type `path` as reported by `std::any::type_name`.
+    fn type_name(&self, path: &str) -> String {
+        let path = path.trim_start_matches("::");
+        match path.strip_prefix("crate::") {
+            Some(rest) => format!("{}::{}", self.krate, rest),
+            None => path.to_owned(),
+        }
+    }
+
+    /// Generates the code of the module.
+    ///
+    /// # Panics
+    /// Panics if two of the type names for a target collide in their hashes.
+    pub fn generate(&self) -> String {
+        let mut code = String::new();
+        code.push_str(HEADER);
+
+        let mut names = Vec::new();
+        for target in &self.targets {
+            let mut hashes = HashMap::new();
+            let mut arms = Vec::new();
+            for source in &target.sources {
+                let type_name = self.type_name(source);
+                let h = hash(&type_name);
+                if let Some(other) = hashes.insert(h, type_name.clone()) {
+                    if other != type_name {
+                        panic!("Hash collision between {} and {}", other, type_name);
+                    }
+                    continue;
+                }
+                names.push((source.clone(), type_name));
+                arms.push((h, source.as_str()));
+            }
+            write_target(&mut code, target, &arms);
+        }
+
+        code.push_str(
+            "\n/// Returns the names computed at build time which don't match those at runtime.\n\
+             /// The casts from the types of those names always fail.\n\
+             #[allow(dead_code)]\n\
+             pub fn mismatched_type_names() -> Vec<&'static str> {\n    \
+             let mut mismatched = Vec::new();\n",
+        );
+        for (path, type_name) in names {
+            writeln!(
+                code,
+                "    if ::std::any::type_name::<{}>() != {:?} {{\n        \
+                 mismatched.push({:?});\n    }}",
+                path, type_name, type_name
+            )
+            .unwrap();
+        }
+        code.push_str("    mismatched\n}\n");
+        code
+    }
+
+    /// Generates the code of the module into the file at `path`.
+    pub fn write_to<P: AsRef<Path>>(&self, path: P) -> io::Result<()> {
+        fs::write(path, self.generate())
+    }
+}
+
+const HEADER: &str = "// Generated by intertrait-phf. Do not edit.
+
+#[allow(dead_code)]
+fn type_name_hash(name: &str) -> u64 {
+    name.bytes().fold(0xcbf2_9ce4_8422_2325, |hash, b| {
+        (hash ^ u64::from(b)).wrapping_mul(0x0100_0000_01b3)
+    })
+}
+";
+
+fn write_target(code: &mut String, target: &Target, arms: &[(u64, &str)]) {
+    let Target { name, trait_, .. } = target;
+
+    writeln!(
+        code,
+        "\n#[allow(dead_code)]\n\
+         pub fn {name}<S: ?Sized + ::intertrait::CastFrom>(source: &S) -> Option<&dyn {trait_}> {{\n    \
+         let any = source.ref_any();\n    \
+         match type_name_hash(source.type_name()) {{",
+        name = name,
+        trait_ = trait_
+    )
+    .unwrap();
+    for (h, ty) in arms {
+        writeln!(
+            code,
+            "        {:#x} => any.downcast_ref::<{}>().map(|v| v as &dyn {}),",
+            h, ty, trait_
+        )
+        .unwrap();
+    }
+    code.push_str("        _ => None,\n    }\n}\n");
+
+    writeln!(
+        code,
+        "\n#[allow(dead_code)]\n\
+         pub fn {name}_mut<S: ?Sized + ::intertrait::CastFrom>(source: &mut S) -> Option<&mut dyn {trait_}> {{\n    \
+         let h = type_name_hash(source.type_name());\n    \
+         let any = source.mut_any();\n    \
+         match h {{",
+        name = name,
+        trait_ = trait_
+    )
+    .unwrap();
+    for (h, ty) in arms {
+        writeln!(
+            code,
+            "        {:#x} => any.downcast_mut::<{}>().map(|v| v as &mut dyn {}),",
+            h, ty, trait_
+        )
+        .unwrap();
+    }
+    code.push_str("        _ => None,\n    }\n}\n");
+
+    writeln!(
+        code,
+        "\n#[allow(dead_code)]\n\
+         pub fn {name}_box<S: ?Sized + ::intertrait::CastFrom>(source: Box<S>) -> Result<Box<dyn {trait_}>, Box<S>> {{\n    \
+         match type_name_hash(source.type_name()) {{",
+        name = name,
+        trait_ = trait_
+    )
+    .unwrap();
+    for (h, ty) in arms {
+        writeln!(
+            code,
+            "        {:#x} if source.ref_any().is::<{ty}>() => {{\n            \
+             Ok(source.box_any().downcast::<{ty}>().unwrap() as Box<dyn {trait_}>)\n        }}",
+            h,
+            ty = ty,
+            trait_ = trait_
+        )
+        .unwrap();
+    }
+    code.push_str("        _ => Err(source),\n    }\n}\n");
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn crate_paths_are_resolved() {
+        let generator = Generator::new("my-app");
+        assert_eq!(generator.type_name("crate::a::B"), "my_app::a::B");
+        assert_eq!(generator.type_name("::other::B"), "other::B");
+    }
+
+    #[test]
+    fn duplicate_sources_are_merged() {
+        let code = Generator::new("app")
+            .target("draw", "crate::Draw", &["crate::A", "app::A"])
+            .generate();
+        assert_eq!(code.matches("downcast_ref::<").count(), 1);
+    }
+}
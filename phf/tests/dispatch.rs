@@ -0,0 +1,87 @@
+use intertrait::*;
+
+mod casts {
+    include!(concat!(env!("OUT_DIR"), "/test_casts.rs"));
+}
+
+trait Source: CastFrom {}
+
+trait Draw {
+    fn draw(&self) -> String;
+}
+
+trait Area {
+    fn area(&self) -> u32;
+}
+
+struct Circle;
+
+impl Draw for Circle {
+    fn draw(&self) -> String {
+        "circle".to_owned()
+    }
+}
+
+impl Source for Circle {}
+
+mod shapes {
+    pub struct Square(pub u32);
+
+    impl crate::Draw for Square {
+        fn draw(&self) -> String {
+            format!("square {}", self.0)
+        }
+    }
+
+    impl crate::Area for Square {
+        fn area(&self) -> u32 {
+            self.0 * self.0
+        }
+    }
+
+    impl crate::Source for Square {}
+}
+
+struct Triangle;
+
+impl Source for Triangle {}
+
+#[test]
+fn test_no_mismatched_type_names() {
+    assert!(casts::mismatched_type_names().is_empty());
+}
+
+#[test]
+fn test_dispatch_ref() {
+    let sources: Vec<Box<dyn Source>> = vec![
+        Box::new(Circle),
+        Box::new(shapes::Square(2)),
+        Box::new(Triangle),
+    ];
+    let drawn: Vec<String> = sources
+        .iter()
+        .filter_map(|source| casts::draw(&**source))
+        .map(|draw| draw.draw())
+        .collect();
+    assert_eq!(drawn, vec!["circle", "square 2"]);
+
+    assert!(casts::area(&*sources[0]).is_none());
+    assert_eq!(casts::area(&*sources[1]).unwrap().area(), 4);
+}
+
+#[test]
+fn test_dispatch_mut() {
+    let mut square = shapes::Square(3);
+    let source: &mut dyn Source = &mut square;
+    assert_eq!(casts::area_mut(source).unwrap().area(), 9);
+}
+
+#[test]
+fn test_dispatch_box() {
+    let source: Box<dyn Source> = Box::new(shapes::Square(3));
+    let draw = casts::draw_box(source).unwrap_or_else(|_| panic!("must be castable"));
+    assert_eq!(draw.draw(), "square 3");
+
+    let source: Box<dyn Source> = Box::new(Triangle);
+    assert!(casts::draw_box(source).is_err());
+}
@@ -9,13 +9,49 @@
 //!
 //! Since there exists single trait for each receiver type, the same `cast` method is overloaded.
 mod cast_arc;
+mod cast_arena;
 mod cast_box;
+mod cast_cache;
+mod cast_cell;
+mod cast_cursor;
+mod cast_error;
+mod cast_generic;
+mod cast_iter;
+#[cfg(feature = "parking_lot")]
+mod cast_lock;
 mod cast_mut;
+#[cfg(feature = "rayon")]
+mod cast_par;
+mod cast_pointer;
+mod cast_raw;
 mod cast_rc;
 mod cast_ref;
+mod cast_weak;
+mod caster_handle;
+mod closed_cast;
+mod dyn_cast;
+mod first_of;
 
 pub use cast_arc::*;
+pub use cast_arena::*;
 pub use cast_box::*;
+pub use cast_cache::*;
+pub use cast_cell::*;
+pub use cast_cursor::*;
+pub use cast_error::*;
+pub use cast_generic::*;
+pub use cast_iter::*;
+#[cfg(feature = "parking_lot")]
+pub use cast_lock::*;
 pub use cast_mut::*;
+#[cfg(feature = "rayon")]
+pub use cast_par::*;
+pub use cast_pointer::*;
+pub use cast_raw::*;
 pub use cast_rc::*;
 pub use cast_ref::*;
+pub use cast_weak::*;
+pub use caster_handle::*;
+pub use closed_cast::*;
+pub use dyn_cast::*;
+pub use first_of::*;
@@ -1,6 +1,7 @@
-use crate::{caster, CastFromSync};
 use std::sync::Arc;
 
+use crate::{caster, panic_not_castable, CastFromSync};
+
 /// A trait that is blanket-implemented for traits extending `CastFrom` to allow for casting
 /// of a trait object for it behind an `Rc` to a trait object for another trait
 /// implemented by the underlying value.
@@ -28,9 +29,90 @@ use std::sync::Arc;
 /// let greet = source.cast::<dyn Greet>();
 /// greet.unwrap_or_else(|_| panic!("must not happen")).greet();
 /// ```
+///
+/// ## Casting shared and owned `Arc`s
+/// `cast` on an `Arc` consumes it. To cast without giving up the `Arc`, dereference it
+/// and cast the reference instead, which also goes for `impls`.
+/// ```
+/// # use std::sync::Arc;
+/// # use intertrait::*;
+/// use intertrait::cast::*;
+///
+/// trait Component: CastFromSync {}
+/// trait System {
+///     fn run(&self) -> u32;
+/// }
+/// trait Updatable {
+///     fn update(&self) -> u32;
+/// }
+///
+/// #[cast_to([sync] System, Updatable)]
+/// struct Physics;
+/// impl Component for Physics {}
+/// impl System for Physics {
+///     fn run(&self) -> u32 { 1 }
+/// }
+/// impl Updatable for Physics {
+///     fn update(&self) -> u32 { 2 }
+/// }
+///
+/// let components: Vec<Arc<dyn Component>> = vec![Arc::new(Physics)];
+/// for component in &components {
+///     // Shared: cast a reference through `Deref`
+///     assert!((**component).impls::<dyn System>());
+///     assert_eq!((**component).cast::<dyn System>().unwrap().run(), 1);
+/// }
+/// for component in components {
+///     // Owned: cast the `Arc` itself
+///     let updatable = component.cast::<dyn Updatable>();
+///     assert_eq!(updatable.unwrap_or_else(|_| panic!()).update(), 2);
+/// }
+/// ```
+///
+/// If casting fails, the original `Arc` is returned as is, without any change in
+/// its reference counts.
 pub trait CastArc {
     /// Casts an `Arc` for this trait into that for type `T`.
     fn cast<T: ?Sized + 'static>(self: Arc<Self>) -> Result<Arc<T>, Arc<Self>>;
+
+    /// Casts an `Arc` for this trait into that for type `T`, which must succeed.
+    ///
+    /// # Panics
+    /// Panics with the names of the underlying type and `T` if the cast fails.
+    fn expect_cast<T: ?Sized + 'static>(self: Arc<Self>) -> Arc<T>;
+
+    /// Moves the value out of an `Arc` for this trait into a `Box` for type `T`, if the `Arc`
+    /// is the only one pointing to the value and the cast is registered. Otherwise, returns
+    /// the `Arc` as is.
+    ///
+    /// Unlike `cast`, it doesn't require the cast to be registered with `[sync]`. On the other
+    /// hand, it always fails for `Arc<dyn Any + Sync + Send>`, out of which a value of
+    /// an unknown type can't be moved.
+    ///
+    /// # Examples
+    /// ```
+    /// # use std::sync::Arc;
+    /// # use intertrait::*;
+    /// use intertrait::cast::*;
+    ///
+    /// # #[cast_to(Shutdown)]
+    /// # struct Plugin;
+    /// # trait Source: CastFromSync {}
+    /// # trait Shutdown {
+    /// #     fn shutdown(self: Box<Self>);
+    /// # }
+    /// # impl Shutdown for Plugin {
+    /// #     fn shutdown(self: Box<Self>) {}
+    /// # }
+    /// impl Source for Plugin {}
+    /// let plugin: Arc<dyn Source> = Arc::new(Plugin);
+    /// let shared = plugin.clone();
+    /// let plugin = plugin.try_unwrap_cast::<dyn Shutdown>().err().unwrap();
+    ///
+    /// drop(shared);
+    /// plugin.try_unwrap_cast::<dyn Shutdown>().ok().unwrap().shutdown();
+    /// ```
+    fn try_unwrap_cast<T: ?Sized + 'static>(self: Arc<Self>) -> Result<Box<T>, Arc<Self>>;
 }
 
 /// A blanket implementation of `CastArc` for traits extending `CastFrom`, `Sync`, and `Send`.
@@ -41,4 +123,117 @@ impl<S: ?Sized + CastFromSync> CastArc for S {
             None => Err(self),
         }
     }
+
+    fn expect_cast<T: ?Sized + 'static>(self: Arc<Self>) -> Arc<T> {
+        match self.cast::<T>() {
+            Ok(target) => target,
+            Err(this) => panic_not_castable::<T>((*this).type_name()),
+        }
+    }
+
+    fn try_unwrap_cast<T: ?Sized + 'static>(self: Arc<Self>) -> Result<Box<T>, Arc<Self>> {
+        let caster = match caster::<T>((*self).type_id()) {
+            Some(caster) => caster,
+            None => return Err(self),
+        };
+        // Kept to give back the `Arc` if the value can't be moved out, e.g. as another `Arc`
+        // points to it.
+        let weak = Arc::downgrade(&self);
+        match self.unwrap_any() {
+            Ok(any) => Ok((caster.cast_box)(any)),
+            Err(_any) => Err(weak
+                .upgrade()
+                .expect("unwrap_any dropped the value it failed to move out")),
+        }
+    }
+}
+
+/// A trait that is implemented for `Arc`s of traits extending `CastFromSync` to allow for
+/// casting of a shared `Arc` into another `Arc` for a trait implemented by the underlying value.
+///
+/// Unlike `CastArc::cast`, it doesn't consume the `Arc`, which is cloned only if the cast
+/// succeeds.
+///
+/// # Examples
+/// ```
+/// # use std::sync::Arc;
+/// # use intertrait::*;
+/// use intertrait::cast::*;
+///
+/// # #[cast_to([sync] Greet)]
+/// # struct Data;
+/// # trait Source: CastFromSync {}
+/// # trait Greet {
+/// #     fn greet(&self);
+/// # }
+/// # trait Unknown {}
+/// # impl Greet for Data {
+/// #    fn greet(&self) {
+/// #        println!("Hello");
+/// #    }
+/// # }
+/// impl Source for Data {}
+/// let source: Arc<dyn Source> = Arc::new(Data);
+/// assert!(source.cast_cloned::<dyn Unknown>().is_none());
+/// assert_eq!(Arc::strong_count(&source), 1);
+///
+/// let greet = source.cast_cloned::<dyn Greet>().unwrap();
+/// greet.greet();
+/// assert_eq!(Arc::strong_count(&source), 2);
+/// ```
+pub trait CastArcRef {
+    /// Casts a clone of this `Arc` into an `Arc` for type `T`, which is cloned only if
+    /// the cast succeeds.
+    fn cast_cloned<T: ?Sized + 'static>(&self) -> Option<Arc<T>>;
+}
+
+impl<S: ?Sized + CastFromSync> CastArcRef for Arc<S> {
+    fn cast_cloned<T: ?Sized + 'static>(&self) -> Option<Arc<T>> {
+        let caster = caster::<T>((**self).type_id())?;
+        Some((caster.cast_arc)(self.clone().arc_any()))
+    }
+}
+
+/// Tests if two `Arc`s point to the same allocation, even if they are trait objects for
+/// different traits, e.g. those cast from the same `Arc`.
+///
+/// `Arc::ptr_eq` can't compare them since their types differ, and comparing the fat pointers
+/// of trait objects would take their vtables into account as well. Instead, the pointers
+/// are compared as thin pointers to the data, which are the same for all the trait objects
+/// cast from an `Arc` as a cast never moves the value.
+///
+/// # Examples
+/// ```
+/// # use std::sync::Arc;
+/// # use intertrait::*;
+/// use intertrait::cast::*;
+///
+/// trait Node: CastFromSync {}
+/// trait Named {
+///     fn name(&self) -> &'static str;
+/// }
+///
+/// #[cast_to([sync] Named)]
+/// struct Leaf;
+/// impl Node for Leaf {}
+/// impl Named for Leaf {
+///     fn name(&self) -> &'static str {
+///         "leaf"
+///     }
+/// }
+///
+/// let node: Arc<dyn Node> = Arc::new(Leaf);
+/// let named = node.clone().cast::<dyn Named>().unwrap_or_else(|_| panic!());
+/// assert!(cast_ptr_eq(&node, &named));
+///
+/// let other: Arc<dyn Node> = Arc::new(Leaf);
+/// assert!(!cast_ptr_eq(&other, &named));
+/// ```
+///
+/// # Caveats
+/// Since only the addresses are compared, which trait the values are viewed through doesn't
+/// matter. Therefore, the result tells the identity of the allocations, not that the two
+/// trait objects behave the same.
+pub fn cast_ptr_eq<A: ?Sized, B: ?Sized>(a: &Arc<A>, b: &Arc<B>) -> bool {
+    std::ptr::eq(Arc::as_ptr(a) as *const (), Arc::as_ptr(b) as *const ())
 }
@@ -0,0 +1,56 @@
+use std::ops::Index;
+
+use crate::cast::CastRef;
+use crate::CastFrom;
+
+/// A trait that is blanket-implemented for handle-based storages (arenas, slot maps, etc.)
+/// holding `Box`es of trait objects for a sub-trait of `CastFrom`. It allows for fetching
+/// an element by its handle and casting it to a trait object for another trait
+/// implemented by the underlying value in one step.
+///
+/// Any storage implementing [`Index<H, Output = Box<dyn Source>>`] can be used,
+/// which includes `Vec`, `slotmap::SlotMap` and `generational_arena::Arena`.
+///
+/// # Panics
+/// Follows the behavior of the storage's `Index` implementation for an invalid handle,
+/// which usually panics.
+///
+/// # Examples
+/// ```
+/// # use intertrait::*;
+/// use intertrait::cast::*;
+///
+/// # #[cast_to(Greet)]
+/// # struct Data;
+/// # trait Source: CastFrom {}
+/// # trait Greet {
+/// #     fn greet(&self);
+/// # }
+/// # impl Greet for Data {
+/// #    fn greet(&self) {
+/// #        println!("Hello");
+/// #    }
+/// # }
+/// impl Source for Data {}
+/// let arena: Vec<Box<dyn Source>> = vec![Box::new(Data)];
+/// let greet = arena.get_cast::<dyn Greet>(0);
+/// greet.unwrap().greet();
+/// ```
+///
+/// [`Index<H, Output = Box<dyn Source>>`]: https://doc.rust-lang.org/std/ops/trait.Index.html
+pub trait CastArena<H> {
+    /// Fetches the element for `handle` and casts it into a reference to type `T`.
+    fn get_cast<T: ?Sized + 'static>(&self, handle: H) -> Option<&T>;
+}
+
+/// A blanket implementation of `CastArena` for storages indexed by `H` and holding `Box`es
+/// of trait objects for traits extending `CastFrom`.
+impl<A, H, S> CastArena<H> for A
+where
+    A: ?Sized + Index<H, Output = Box<S>>,
+    S: ?Sized + CastFrom,
+{
+    fn get_cast<T: ?Sized + 'static>(&self, handle: H) -> Option<&T> {
+        (*self[handle]).cast::<T>()
+    }
+}
@@ -1,4 +1,10 @@
-use crate::{caster, CastFrom};
+use std::any::{Any, TypeId};
+use std::pin::Pin;
+use std::rc::Rc;
+use std::sync::Arc;
+
+use crate::cast::CastError;
+use crate::{caster, downcast_box, panic_not_castable, pin_caster, CastFrom};
 
 /// A trait that is blanket-implemented for traits extending `CastFrom` to allow for casting
 /// of a trait object for it behind a `Box` to a trait object for another trait
@@ -29,14 +35,321 @@ use crate::{caster, CastFrom};
 pub trait CastBox {
     /// Casts a box to this trait into that of type `T`. If fails, returns the receiver.
     fn cast<T: ?Sized + 'static>(self: Box<Self>) -> Result<Box<T>, Box<Self>>;
+
+    /// Casts a box to this trait into that of type `T`, which must succeed.
+    ///
+    /// # Panics
+    /// Panics with the names of the underlying type and `T` if the cast fails.
+    fn expect_cast<T: ?Sized + 'static>(self: Box<Self>) -> Box<T>;
+
+    /// Casts a `Box` of this trait into that of type `T`. If fails, returns an error telling
+    /// the concrete type behind and `T`, which gives back the `Box` with `into_source`.
+    fn try_cast<T: ?Sized + 'static>(self: Box<Self>) -> Result<Box<T>, CastError<Box<Self>>>;
+
+    /// Casts a pinned box to this trait into that of type `T`. If fails, returns the receiver.
+    ///
+    /// The value stays pinned in the same allocation, which is unpinned only internally to be
+    /// cast and then pinned again. This relies on the `cast_box` of the [`Caster<T>`] for
+    /// the underlying type returning the allocation as is, which is promised only by those
+    /// referred to by a [`CasterEntry`], e.g. the ones generated by the macros. So the casts
+    /// registered otherwise, e.g. with `dynamic::register_caster` or a fallback, fail.
+    ///
+    /// # Examples
+    /// ```
+    /// # use std::marker::PhantomPinned;
+    /// # use intertrait::*;
+    /// use intertrait::cast::*;
+    ///
+    /// # #[cast_to(Greet)]
+    /// # struct Data(PhantomPinned);
+    /// # trait Source: CastFrom {}
+    /// # trait Greet {
+    /// #     fn greet(&self);
+    /// # }
+    /// # impl Greet for Data {
+    /// #    fn greet(&self) {
+    /// #        println!("Hello");
+    /// #    }
+    /// # }
+    /// impl Source for Data {}
+    /// let source: std::pin::Pin<Box<dyn Source>> = Box::pin(Data(PhantomPinned));
+    /// let greet = source.cast_pin::<dyn Greet>();
+    /// greet.unwrap_or_else(|_| panic!("casting failed")).greet();
+    /// ```
+    ///
+    /// [`Caster<T>`]: ../struct.Caster.html
+    /// [`CasterEntry`]: ../struct.CasterEntry.html
+    fn cast_pin<T: ?Sized + 'static>(self: Pin<Box<Self>>) -> Result<Pin<Box<T>>, Pin<Box<Self>>>;
 }
 
 /// A blanket implementation of `CastBox` for traits extending `CastFrom`.
 impl<S: ?Sized + CastFrom> CastBox for S {
     fn cast<T: ?Sized + 'static>(self: Box<Self>) -> Result<Box<T>, Box<Self>> {
+        if (*self).type_id() == TypeId::of::<T>() {
+            return Ok(downcast_box::<T>(self.box_any()).ok().unwrap());
+        }
         match caster::<T>((*self).type_id()) {
             Some(caster) => Ok((caster.cast_box)(self.box_any())),
             None => Err(self),
         }
     }
+
+    fn expect_cast<T: ?Sized + 'static>(self: Box<Self>) -> Box<T> {
+        match self.cast::<T>() {
+            Ok(target) => target,
+            Err(this) => panic_not_castable::<T>((*this).type_name()),
+        }
+    }
+
+    fn try_cast<T: ?Sized + 'static>(self: Box<Self>) -> Result<Box<T>, CastError<Box<Self>>> {
+        self.cast::<T>()
+            .map_err(|this| CastError::new::<T, _>(&*this).with_source(this))
+    }
+
+    fn cast_pin<T: ?Sized + 'static>(self: Pin<Box<Self>>) -> Result<Pin<Box<T>>, Pin<Box<Self>>> {
+        let type_id = (*self).type_id();
+        if type_id == TypeId::of::<T>() {
+            // SAFETY: `box_any` of `CastFrom`, which is sealed, returns the allocation as is,
+            // so the value is pinned again without being moved.
+            let this = unsafe { Pin::into_inner_unchecked(self) };
+            let target = downcast_box::<T>(this.box_any()).ok().unwrap();
+            return Ok(unsafe { Pin::new_unchecked(target) });
+        }
+        let caster = match pin_caster::<T>(type_id) {
+            Some(caster) => caster,
+            None => return Err(self),
+        };
+        // SAFETY: `box_any` of `CastFrom`, which is sealed, returns the allocation as is. So
+        // does the caster, which is referred to by a `CasterEntry`, whose creator promises it.
+        // So the value is pinned again without being moved.
+        let this = unsafe { Pin::into_inner_unchecked(self) };
+        Ok(unsafe { Pin::new_unchecked((caster.cast_box)(this.box_any())) })
+    }
+}
+
+/// Casts `any` into a `Box` of type `T`. If fails, returns `any`.
+///
+/// It is the same as `CastBox::cast` on `Box<dyn Any>`, but reads better in a chain of
+/// attempts starting with a downcast into a concrete type, which returns the `Box` on failure.
+///
+/// # Examples
+/// ```
+/// # use std::any::Any;
+/// # use intertrait::*;
+/// use intertrait::cast::*;
+///
+/// # #[cast_to(Greet)]
+/// # struct Data;
+/// # trait Greet {
+/// #     fn greet(&self);
+/// # }
+/// # impl Greet for Data {
+/// #    fn greet(&self) {
+/// #        println!("Hello");
+/// #    }
+/// # }
+/// let any: Box<dyn Any> = Box::new(Data);
+/// let greet = any
+///     .downcast::<String>()
+///     .map(|_| unreachable!())
+///     .or_else(box_any_to::<dyn Greet>);
+/// greet.unwrap_or_else(|_| panic!("casting failed")).greet();
+/// ```
+pub fn box_any_to<T: ?Sized + 'static>(any: Box<dyn Any>) -> Result<Box<T>, Box<dyn Any>> {
+    any.cast::<T>()
+}
+
+/// Casts a boxed trait object into an `Rc` of type `T`. If fails, returns `source`.
+///
+/// The value is moved into a new allocation for the `Rc`, as `Rc::from` on a `Box` does.
+///
+/// # Examples
+/// ```
+/// # use std::rc::Rc;
+/// # use intertrait::*;
+/// use intertrait::cast::*;
+///
+/// # #[cast_to(Greet)]
+/// # struct Data;
+/// # trait Source: CastFrom {}
+/// # trait Greet {
+/// #     fn greet(&self);
+/// # }
+/// # impl Greet for Data {
+/// #    fn greet(&self) {
+/// #        println!("Hello");
+/// #    }
+/// # }
+/// impl Source for Data {}
+/// let source: Box<dyn Source> = Box::new(Data);
+/// let greet: Rc<dyn Greet> = box_to_rc::<dyn Greet, _>(source).ok().unwrap();
+/// greet.greet();
+/// ```
+pub fn box_to_rc<T: ?Sized + 'static, S: ?Sized + CastFrom>(
+    source: Box<S>,
+) -> Result<Rc<T>, Box<S>> {
+    source.cast::<T>().map(Rc::from)
+}
+
+/// Casts a boxed trait object into an `Arc` of type `T`. If fails, returns `source`.
+///
+/// The value is moved into a new allocation for the `Arc`, as `Arc::from` on a `Box` does.
+/// Unlike `CastArc::cast`, it doesn't require the cast to be registered with `[sync]`.
+///
+/// # Examples
+/// ```
+/// # use std::sync::Arc;
+/// # use intertrait::*;
+/// use intertrait::cast::*;
+///
+/// # #[cast_to(Greet)]
+/// # struct Data;
+/// # trait Source: CastFrom {}
+/// # trait Greet {
+/// #     fn greet(&self);
+/// # }
+/// # impl Greet for Data {
+/// #    fn greet(&self) {
+/// #        println!("Hello");
+/// #    }
+/// # }
+/// impl Source for Data {}
+/// let source: Box<dyn Source> = Box::new(Data);
+/// let greet: Arc<dyn Greet> = box_to_arc::<dyn Greet, _>(source).ok().unwrap();
+/// greet.greet();
+/// ```
+pub fn box_to_arc<T: ?Sized + 'static, S: ?Sized + CastFrom>(
+    source: Box<S>,
+) -> Result<Arc<T>, Box<S>> {
+    source.cast::<T>().map(Arc::from)
+}
+
+/// Casts a boxed trait object into `T` carrying `Send`, typically `dyn Trait + Send`, which
+/// succeeds only if the cast is registered with `[send]` or `[sync]`. If fails, returns `source`.
+///
+/// Whether the concrete type is `Send` isn't recorded otherwise, so a cast registered with
+/// a plain `#[cast_to]` fails even if the type is `Send`. The flags, which don't compile
+/// unless the type is `Send`, let code accepting `Box<dyn Trait>` promote it safely to be
+/// sent to another thread when the value behind qualifies.
+///
+/// # Examples
+/// ```
+/// # use std::thread;
+/// # use intertrait::*;
+/// use intertrait::cast::*;
+///
+/// # struct Data;
+/// # trait Greet: CastFrom {
+/// #     fn greet(&self);
+/// # }
+/// #[cast_to([send])]
+/// impl Greet for Data {
+///    fn greet(&self) {
+///        println!("Hello");
+///    }
+/// }
+/// let greet: Box<dyn Greet> = Box::new(Data);
+/// let greet = try_add_send::<dyn Greet + Send, _>(greet).ok().unwrap();
+/// thread::spawn(move || greet.greet()).join().unwrap();
+/// ```
+pub fn try_add_send<T, S>(source: Box<S>) -> Result<Box<T>, Box<S>>
+where
+    T: ?Sized + Send + 'static,
+    S: ?Sized + CastFrom,
+{
+    source.cast::<T>()
+}
+
+/// Casts a boxed trait object into `T` carrying `Send` and `Sync`, typically
+/// `dyn Trait + Send + Sync`, which succeeds only if the cast is registered with `[sync]`.
+/// If fails, returns `source`.
+///
+/// A cast registered with a plain `#[cast_to]` or `[send]` fails even if the concrete type
+/// is `Send` and `Sync`. See [`try_add_send`] for more.
+///
+/// [`try_add_send`]: ./fn.try_add_send.html
+pub fn try_add_sync<T, S>(source: Box<S>) -> Result<Box<T>, Box<S>>
+where
+    T: ?Sized + Send + Sync + 'static,
+    S: ?Sized + CastFrom,
+{
+    source.cast::<T>()
+}
+
+/// Partitions boxed trait objects in `items` into those cast into type `T` and the others,
+/// keeping their order.
+///
+/// # Examples
+/// ```
+/// # use intertrait::*;
+/// use intertrait::cast::*;
+///
+/// # #[cast_to(Greet)]
+/// # struct Data;
+/// # struct Other;
+/// # trait Source: CastFrom {}
+/// # trait Greet {
+/// #     fn greet(&self);
+/// # }
+/// # impl Greet for Data {
+/// #    fn greet(&self) {
+/// #        println!("Hello");
+/// #    }
+/// # }
+/// impl Source for Data {}
+/// impl Source for Other {}
+/// let plugins: Vec<Box<dyn Source>> = vec![Box::new(Data), Box::new(Other), Box::new(Data)];
+/// let (greets, others) = cast_vec::<dyn Greet, _>(plugins);
+/// assert_eq!((greets.len(), others.len()), (2, 1));
+/// ```
+pub fn cast_vec<T, S>(items: Vec<Box<S>>) -> (Vec<Box<T>>, Vec<Box<S>>)
+where
+    T: ?Sized + 'static,
+    S: ?Sized + CastFrom,
+{
+    let mut targets = Vec::new();
+    let mut others = Vec::new();
+    for item in items {
+        match item.cast::<T>() {
+            Ok(target) => targets.push(target),
+            Err(item) => others.push(item),
+        }
+    }
+    (targets, others)
+}
+
+/// Removes boxed trait objects which can be cast into type `T` from `items`, and returns them
+/// cast in their order. The others are retained in `items` in their order.
+///
+/// # Examples
+/// ```
+/// # use intertrait::*;
+/// use intertrait::cast::*;
+///
+/// # #[cast_to(Greet)]
+/// # struct Data;
+/// # struct Other;
+/// # trait Source: CastFrom {}
+/// # trait Greet {
+/// #     fn greet(&self);
+/// # }
+/// # impl Greet for Data {
+/// #    fn greet(&self) {
+/// #        println!("Hello");
+/// #    }
+/// # }
+/// impl Source for Data {}
+/// impl Source for Other {}
+/// let mut plugins: Vec<Box<dyn Source>> = vec![Box::new(Data), Box::new(Other)];
+/// let greets = retain_cast::<dyn Greet, _>(&mut plugins);
+/// assert_eq!((greets.len(), plugins.len()), (1, 1));
+/// ```
+pub fn retain_cast<T, S>(items: &mut Vec<Box<S>>) -> Vec<Box<T>>
+where
+    T: ?Sized + 'static,
+    S: ?Sized + CastFrom,
+{
+    let (targets, others) = cast_vec(std::mem::take(items));
+    *items = others;
+    targets
 }
@@ -0,0 +1,203 @@
+use std::any::{Any, TypeId};
+use std::cell::{Cell, RefCell};
+use std::ops::{Deref, DerefMut};
+
+use crate::cast::{CastMut, CastRef, CasterHandle};
+use crate::{caster, generation, CastFrom, Caster};
+
+/// A cache of the caster into trait `T` last resolved, which is what [`cached_cast!`] keeps
+/// for each call site. A cast of a value of the same concrete type as the last one skips
+/// looking up the registry.
+///
+/// It isn't `Sync`, so is meant to be kept in a thread-local or a field of a value used by
+/// a single thread. With `dynamic` feature, the cached caster is dropped once the casts
+/// allowed at runtime change.
+///
+/// [`cached_cast!`]: ../macro.cached_cast.html
+pub struct CastCache<T: ?Sized + 'static> {
+    cached: Cell<Option<(usize, CasterHandle<T>)>>,
+}
+
+impl<T: ?Sized + 'static> CastCache<T> {
+    /// Creates an empty cache.
+    pub const fn new() -> Self {
+        CastCache {
+            cached: Cell::new(None),
+        }
+    }
+
+    /// Returns the caster cached if it's still valid.
+    fn handle(&self) -> Option<CasterHandle<T>> {
+        match self.cached.get() {
+            Some((generation, handle)) if generation == crate::generation() => Some(handle),
+            _ => None,
+        }
+    }
+
+    /// Casts a reference to a trait object for `S` into that of type `T`, with the caster
+    /// cached if it's for the concrete type of `source`.
+    pub fn cast_ref<'a, S: ?Sized + CastFrom>(&self, source: &'a S) -> Option<&'a T> {
+        if let Some(target) = self.handle().and_then(|handle| handle.cast_ref(source)) {
+            return Some(target);
+        }
+        let generation = generation();
+        match source.cast_handled::<T>() {
+            Some((target, handle)) => {
+                self.cached.set(Some((generation, handle)));
+                Some(target)
+            }
+            // Not through a caster, e.g. a downcast into the concrete type itself.
+            None => source.cast::<T>(),
+        }
+    }
+
+    /// Casts a mutable reference to a trait object for `S` into that of type `T`, with
+    /// the caster cached if it's for the concrete type of `source`.
+    pub fn cast_mut<'a, S: ?Sized + CastFrom>(&self, source: &'a mut S) -> Option<&'a mut T> {
+        let type_id = (*source).ref_any().type_id();
+        match self.handle() {
+            Some(handle) if handle.type_id() == type_id => handle.cast_mut(source),
+            _ => {
+                let generation = generation();
+                if let Some((_, handle)) = (*source).cast_handled::<T>() {
+                    self.cached.set(Some((generation, handle)));
+                    return handle.cast_mut(source);
+                }
+                source.cast::<T>()
+            }
+        }
+    }
+}
+
+impl<T: ?Sized + 'static> Default for CastCache<T> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// A pointer to a trait object, e.g. `Box<dyn Source>` or `Arc<dyn Source>`, which caches
+/// the casters it has resolved so far, so that a long-lived value viewed repeatedly through
+/// the same traits looks up the registry only once for each of them.
+///
+/// It dereferences to the trait object, and its own `cast` shadows those of the `cast` traits.
+/// Like [`CastCache`], it isn't `Sync` and drops the cached casters once the casts allowed at
+/// runtime change with `dynamic` feature.
+///
+/// # Examples
+/// ```
+/// # use intertrait::*;
+/// use intertrait::cast::*;
+///
+/// # #[cast_to(Greet)]
+/// # struct Data;
+/// # trait Source: CastFrom {}
+/// # trait Greet {
+/// #     fn greet(&self);
+/// # }
+/// # impl Greet for Data {
+/// #    fn greet(&self) {
+/// #        println!("Hello");
+/// #    }
+/// # }
+/// impl Source for Data {}
+/// let source = CachedCast::new(Box::new(Data) as Box<dyn Source>);
+/// for _ in 0..100 {
+///     // The registry is looked up only for the first time.
+///     source.cast::<dyn Greet>().unwrap().greet();
+/// }
+/// ```
+///
+/// [`CastCache`]: ./struct.CastCache.html
+pub struct CachedCast<P> {
+    pointer: P,
+    type_id: TypeId,
+    cache: RefCell<Casters>,
+}
+
+/// The casters cached by `CachedCast`, each of which is a `Caster<T>` keyed by `TypeId` of `T`.
+#[derive(Default)]
+struct Casters {
+    generation: usize,
+    casters: Vec<(TypeId, Box<dyn Any>)>,
+}
+
+impl<P> CachedCast<P>
+where
+    P: Deref,
+    P::Target: CastFrom,
+{
+    /// Wraps `pointer` with no caster cached.
+    pub fn new(pointer: P) -> Self {
+        let type_id = (*pointer).ref_any().type_id();
+        CachedCast {
+            pointer,
+            type_id,
+            cache: RefCell::default(),
+        }
+    }
+
+    /// Unwraps the pointer, dropping the cached casters.
+    pub fn into_inner(this: Self) -> P {
+        this.pointer
+    }
+
+    /// Returns the caster into `T` for the concrete type, which is cached once resolved.
+    fn caster<T: ?Sized + 'static>(&self) -> Option<Caster<T>> {
+        let trait_id = TypeId::of::<T>();
+        let generation = generation();
+        {
+            let cache = self.cache.borrow();
+            if cache.generation == generation {
+                let cached = cache.casters.iter().find(|(id, _)| *id == trait_id);
+                if let Some((_, caster)) = cached {
+                    return caster.downcast_ref::<Caster<T>>().copied();
+                }
+            }
+        }
+        let caster = caster::<T>(self.type_id)?;
+        let mut cache = self.cache.borrow_mut();
+        if cache.generation != generation {
+            cache.casters.clear();
+            cache.generation = generation;
+        }
+        cache.casters.push((trait_id, Box::new(caster)));
+        Some(caster)
+    }
+
+    /// Casts a reference to the trait object into that of type `T`.
+    pub fn cast<T: ?Sized + 'static>(&self) -> Option<&T> {
+        match self.caster::<T>() {
+            Some(caster) => Some((caster.cast_ref)((*self.pointer).ref_any())),
+            // Not through a caster, e.g. a downcast into the concrete type itself.
+            None => (*self.pointer).cast::<T>(),
+        }
+    }
+}
+
+impl<P> CachedCast<P>
+where
+    P: DerefMut,
+    P::Target: CastFrom,
+{
+    /// Casts a mutable reference to the trait object into that of type `T`.
+    pub fn cast_mut<T: ?Sized + 'static>(&mut self) -> Option<&mut T> {
+        match self.caster::<T>() {
+            Some(caster) => Some((caster.cast_mut)((*self.pointer).mut_any())),
+            None => CastMut::cast::<T>(&mut *self.pointer),
+        }
+    }
+}
+
+impl<P: Deref> Deref for CachedCast<P> {
+    type Target = P::Target;
+
+    fn deref(&self) -> &Self::Target {
+        &self.pointer
+    }
+}
+
+impl<P: DerefMut> DerefMut for CachedCast<P> {
+    fn deref_mut(&mut self) -> &mut Self::Target {
+        &mut self.pointer
+    }
+}
@@ -0,0 +1,69 @@
+use std::cell::{Ref, RefMut};
+
+use crate::cast::{CastMut, CastRef};
+use crate::CastFrom;
+
+/// Casts a borrow of a `RefCell` holding a trait object into that of type `T`, which keeps
+/// the `RefCell` borrowed until the result is dropped. If fails, returns `borrow`.
+///
+/// # Examples
+/// ```
+/// # use std::cell::RefCell;
+/// # use std::rc::Rc;
+/// # use intertrait::*;
+/// use intertrait::cast::*;
+///
+/// # #[cast_to(Clickable)]
+/// # struct Button;
+/// # trait Widget: CastFrom {}
+/// # trait Clickable {
+/// #     fn label(&self) -> &'static str;
+/// # }
+/// # impl Clickable for Button {
+/// #     fn label(&self) -> &'static str {
+/// #         "OK"
+/// #     }
+/// # }
+/// impl Widget for Button {}
+/// let widget: Rc<RefCell<dyn Widget>> = Rc::new(RefCell::new(Button));
+/// let clickable = cast_cell_ref::<_, dyn Clickable>(widget.borrow());
+/// assert_eq!(clickable.ok().unwrap().label(), "OK");
+/// ```
+pub fn cast_cell_ref<'b, S: ?Sized + CastFrom, T: ?Sized + 'static>(
+    borrow: Ref<'b, S>,
+) -> Result<Ref<'b, T>, Ref<'b, S>> {
+    Ref::filter_map(borrow, |source| CastRef::cast::<T>(source))
+}
+
+/// Casts a mutable borrow of a `RefCell` holding a trait object into that of type `T`, which
+/// keeps the `RefCell` borrowed until the result is dropped. If fails, returns `borrow`.
+///
+/// # Examples
+/// ```
+/// # use std::cell::RefCell;
+/// # use std::rc::Rc;
+/// # use intertrait::*;
+/// use intertrait::cast::*;
+///
+/// # #[cast_to(Toggle)]
+/// # struct Checkbox(bool);
+/// # trait Widget: CastFrom {}
+/// # trait Toggle {
+/// #     fn toggle(&mut self) -> bool;
+/// # }
+/// # impl Toggle for Checkbox {
+/// #     fn toggle(&mut self) -> bool {
+/// #         self.0 = !self.0;
+/// #         self.0
+/// #     }
+/// # }
+/// impl Widget for Checkbox {}
+/// let widget: Rc<RefCell<dyn Widget>> = Rc::new(RefCell::new(Checkbox(false)));
+/// let mut toggle = cast_cell_mut::<_, dyn Toggle>(widget.borrow_mut()).ok().unwrap();
+/// assert!(toggle.toggle());
+/// ```
+pub fn cast_cell_mut<'b, S: ?Sized + CastFrom, T: ?Sized + 'static>(
+    borrow: RefMut<'b, S>,
+) -> Result<RefMut<'b, T>, RefMut<'b, S>> {
+    RefMut::filter_map(borrow, |source| CastMut::cast::<T>(source))
+}
@@ -0,0 +1,82 @@
+use std::any::TypeId;
+
+use crate::{caster, generation, CastFrom, Caster};
+
+/// A cursor for casting a sequence of trait objects into trait `T`, which reuses
+/// the caster resolved for the previous object as long as the concrete types are the same.
+///
+/// It fits a traversal of a graph of trait objects (e.g. a scene graph) where consecutive
+/// nodes are mostly of the same concrete type, skipping the lookup of the registry for them.
+/// The caster is resolved again once the casts allowed at runtime change.
+///
+/// # Examples
+/// ```
+/// # use intertrait::*;
+/// use intertrait::cast::*;
+///
+/// # #[cast_to(Greet)]
+/// # struct Data;
+/// # trait Source: CastFrom {}
+/// # trait Greet {
+/// #     fn greet(&self);
+/// # }
+/// # impl Greet for Data {
+/// #    fn greet(&self) {
+/// #        println!("Hello");
+/// #    }
+/// # }
+/// impl Source for Data {}
+/// let nodes: Vec<Box<dyn Source>> = (0..100).map(|_| Box::new(Data) as _).collect();
+/// let mut cursor = CastCursor::<dyn Greet>::new();
+/// for node in &nodes {
+///     cursor.cast_ref(&**node).unwrap().greet();
+/// }
+/// ```
+pub struct CastCursor<T: ?Sized + 'static> {
+    cached: Option<(usize, TypeId, Option<Caster<T>>)>,
+}
+
+impl<T: ?Sized + 'static> CastCursor<T> {
+    /// Creates a cursor with nothing resolved yet.
+    pub fn new() -> Self {
+        CastCursor { cached: None }
+    }
+
+    /// Returns the caster for the concrete type of `type_id`, resolving it only if
+    /// the concrete type differs from the previous one or the casts have changed since.
+    fn caster(&mut self, type_id: TypeId) -> Option<Caster<T>> {
+        let generation = generation();
+        match self.cached {
+            Some((cached_generation, cached_id, caster))
+                if cached_generation == generation && cached_id == type_id =>
+            {
+                caster
+            }
+            _ => {
+                let caster = caster::<T>(type_id);
+                self.cached = Some((generation, type_id, caster));
+                caster
+            }
+        }
+    }
+
+    /// Casts a reference to a trait object for `S` into that of type `T`.
+    pub fn cast_ref<'a, S: ?Sized + CastFrom>(&mut self, source: &'a S) -> Option<&'a T> {
+        let any = source.ref_any();
+        let caster = self.caster(any.type_id())?;
+        Some((caster.cast_ref)(any))
+    }
+
+    /// Casts a mutable reference to a trait object for `S` into that of type `T`.
+    pub fn cast_mut<'a, S: ?Sized + CastFrom>(&mut self, source: &'a mut S) -> Option<&'a mut T> {
+        let any = source.mut_any();
+        let caster = self.caster((*any).type_id())?;
+        Some((caster.cast_mut)(any))
+    }
+}
+
+impl<T: ?Sized + 'static> Default for CastCursor<T> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
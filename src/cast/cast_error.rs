@@ -0,0 +1,109 @@
+use std::any::TypeId;
+use std::error::Error;
+use std::fmt;
+
+use crate::CastFrom;
+
+/// An error telling that a trait object can't be cast into a type, which is returned by
+/// the `try_cast` methods along with the source of the cast as `S` if it's consumed.
+///
+/// It carries `TypeId` and the name of the concrete type behind the source along with
+/// the name of the target, which `Option` returned by `cast` doesn't.
+///
+/// # Examples
+/// ```
+/// # use intertrait::*;
+/// use intertrait::cast::*;
+///
+/// # trait Source: CastFrom {}
+/// # trait Greet {}
+/// struct Data;
+/// impl Source for Data {}
+/// let source: Box<dyn Source> = Box::new(Data);
+/// let err = source.try_cast::<dyn Greet>().err().unwrap();
+/// assert_eq!(err.source_type_name(), std::any::type_name::<Data>());
+/// assert_eq!(err.target_name(), std::any::type_name::<dyn Greet>());
+/// let source: Box<dyn Source> = err.into_source();
+/// ```
+pub struct CastError<S = ()> {
+    source: S,
+    type_id: TypeId,
+    type_name: &'static str,
+    target_name: &'static str,
+}
+
+impl CastError {
+    /// Creates an error of casting `from` into `T`.
+    pub(crate) fn new<T: ?Sized, F: ?Sized + CastFrom>(from: &F) -> Self {
+        CastError {
+            source: (),
+            type_id: (*from).type_id(),
+            type_name: from.type_name(),
+            target_name: std::any::type_name::<T>(),
+        }
+    }
+
+    /// Attaches `source` to this error.
+    pub(crate) fn with_source<S>(self, source: S) -> CastError<S> {
+        CastError {
+            source,
+            type_id: self.type_id,
+            type_name: self.type_name,
+            target_name: self.target_name,
+        }
+    }
+}
+
+impl<S> CastError<S> {
+    /// Returns `TypeId` of the concrete type behind the source.
+    pub fn source_type_id(&self) -> TypeId {
+        self.type_id
+    }
+
+    /// Returns the name of the concrete type behind the source.
+    pub fn source_type_name(&self) -> &'static str {
+        self.type_name
+    }
+
+    /// Returns the name of the type the source couldn't be cast into.
+    pub fn target_name(&self) -> &'static str {
+        self.target_name
+    }
+
+    /// Gives back the source of the cast.
+    pub fn into_source(self) -> S {
+        self.source
+    }
+
+    /// Drops the source of the cast, which makes the error `Send` and `Sync`.
+    pub fn without_source(self) -> CastError {
+        CastError {
+            source: (),
+            type_id: self.type_id,
+            type_name: self.type_name,
+            target_name: self.target_name,
+        }
+    }
+}
+
+impl<S> fmt::Debug for CastError<S> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("CastError")
+            .field("type_id", &self.type_id)
+            .field("type_name", &self.type_name)
+            .field("target_name", &self.target_name)
+            .finish()
+    }
+}
+
+impl<S> fmt::Display for CastError<S> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "{} is not castable to {}",
+            self.type_name, self.target_name
+        )
+    }
+}
+
+impl<S> Error for CastError<S> {}
@@ -0,0 +1,104 @@
+use std::rc::Rc;
+use std::sync::Arc;
+
+use crate::cast::{CastArc, CastBox, CastMut, CastRc, CastRef};
+use crate::{CastFrom, CastFromSync};
+
+/// A trait implemented by all the kinds of references and pointers to traits extending
+/// `CastFrom` that can be cast into `T`, which are `&`, `&mut`, `Box`, `Rc` and `Arc`.
+///
+/// It lets code generic over the kind of pointers cast whatever it is given, which would
+/// otherwise need a separate path for each of `CastRef`, `CastMut`, `CastBox`, `CastRc`
+/// and `CastArc`.
+///
+/// # Examples
+/// ```
+/// # use std::ops::Deref;
+/// # use std::rc::Rc;
+/// # use intertrait::*;
+/// use intertrait::cast::*;
+///
+/// # trait Source: CastFrom {}
+/// trait Greet {
+///     fn greet(&self) -> &'static str;
+/// }
+///
+/// #[cast_to(Greet)]
+/// struct Data;
+/// impl Source for Data {}
+/// impl Greet for Data {
+///     fn greet(&self) -> &'static str {
+///         "Hello"
+///     }
+/// }
+///
+/// fn greet<P>(source: P) -> Option<&'static str>
+/// where
+///     P: Cast<dyn Greet>,
+///     P::Output: Deref<Target = dyn Greet>,
+/// {
+///     source.cast_into().ok().map(|greet| greet.greet())
+/// }
+///
+/// let source: &dyn Source = &Data;
+/// assert_eq!(greet(source), Some("Hello"));
+/// let source: Box<dyn Source> = Box::new(Data);
+/// assert_eq!(greet(source), Some("Hello"));
+/// let source: Rc<dyn Source> = Rc::new(Data);
+/// assert_eq!(greet(source), Some("Hello"));
+/// ```
+pub trait Cast<T: ?Sized>: Sized {
+    /// The same kind of reference or pointer to `T`.
+    type Output;
+
+    /// Casts this reference or pointer into [`Output`]. If fails, returns `self`.
+    ///
+    /// [`Output`]: #associatedtype.Output
+    fn cast_into(self) -> Result<Self::Output, Self>;
+}
+
+impl<'a, S: ?Sized + CastFrom, T: ?Sized + 'static> Cast<T> for &'a S {
+    type Output = &'a T;
+
+    fn cast_into(self) -> Result<&'a T, &'a S> {
+        CastRef::cast::<T>(self).ok_or(self)
+    }
+}
+
+impl<'a, S: ?Sized + CastFrom, T: ?Sized + 'static> Cast<T> for &'a mut S {
+    type Output = &'a mut T;
+
+    fn cast_into(self) -> Result<&'a mut T, &'a mut S> {
+        let source: *mut S = self;
+        // SAFETY: `source` is reborrowed only once on each path, either for the result of
+        // the cast or for giving it back, which the borrow checker can't tell apart yet.
+        match CastMut::cast::<T>(unsafe { &mut *source }) {
+            Some(target) => Ok(target),
+            None => Err(unsafe { &mut *source }),
+        }
+    }
+}
+
+impl<S: ?Sized + CastFrom, T: ?Sized + 'static> Cast<T> for Box<S> {
+    type Output = Box<T>;
+
+    fn cast_into(self) -> Result<Box<T>, Box<S>> {
+        CastBox::cast::<T>(self)
+    }
+}
+
+impl<S: ?Sized + CastFrom, T: ?Sized + 'static> Cast<T> for Rc<S> {
+    type Output = Rc<T>;
+
+    fn cast_into(self) -> Result<Rc<T>, Rc<S>> {
+        CastRc::cast::<T>(self)
+    }
+}
+
+impl<S: ?Sized + CastFromSync, T: ?Sized + 'static> Cast<T> for Arc<S> {
+    type Output = Arc<T>;
+
+    fn cast_into(self) -> Result<Arc<T>, Arc<S>> {
+        CastArc::cast::<T>(self)
+    }
+}
@@ -0,0 +1,65 @@
+use std::iter::FilterMap;
+
+use crate::cast::Cast;
+
+/// The iterator returned by [`CastIterator::filter_cast`].
+///
+/// [`CastIterator::filter_cast`]: ./trait.CastIterator.html#method.filter_cast
+pub type FilterCast<I, T> =
+    FilterMap<I, fn(<I as Iterator>::Item) -> Option<<<I as Iterator>::Item as Cast<T>>::Output>>;
+
+/// An extension of iterators over references and pointers to traits extending `CastFrom`,
+/// i.e. those implementing [`Cast`], to cast the items in a chain of adapters.
+///
+/// Note that an iterator over `&Box<dyn Source>`, e.g. from `iter` of `Vec<Box<dyn Source>>`,
+/// casts the `Box`es rather than the trait objects in them, as a `Box` is also `CastFrom`.
+/// Map the items into `&dyn Source` beforehand as below.
+///
+/// # Examples
+/// ```
+/// # use intertrait::*;
+/// use intertrait::cast::*;
+///
+/// # #[cast_to(Greet)]
+/// # struct Data;
+/// # struct Other;
+/// # trait Source: CastFrom {}
+/// # trait Greet {
+/// #     fn greet(&self) -> usize;
+/// # }
+/// # impl Greet for Data {
+/// #    fn greet(&self) -> usize {
+/// #        1
+/// #    }
+/// # }
+/// impl Source for Data {}
+/// impl Source for Other {}
+/// let sources: Vec<Box<dyn Source>> = vec![Box::new(Data), Box::new(Other), Box::new(Data)];
+/// let greets: usize = sources
+///     .iter()
+///     .map(|source| &**source)
+///     .filter_cast::<dyn Greet>()
+///     .map(|greet| greet.greet())
+///     .sum();
+/// assert_eq!(greets, 2);
+///
+/// let greets: Vec<Box<dyn Greet>> = sources.into_iter().filter_cast::<dyn Greet>().collect();
+/// assert_eq!(greets.len(), 2);
+/// ```
+///
+/// [`Cast`]: ./trait.Cast.html
+pub trait CastIterator: Iterator + Sized {
+    /// Casts each of the items into type `T`, skipping those that can't be cast.
+    fn filter_cast<T: ?Sized + 'static>(self) -> FilterCast<Self, T>
+    where
+        Self::Item: Cast<T>,
+    {
+        self.filter_map(cast_item::<T, Self::Item>)
+    }
+}
+
+fn cast_item<T: ?Sized, I: Cast<T>>(item: I) -> Option<I::Output> {
+    item.cast_into().ok()
+}
+
+impl<I: Iterator> CastIterator for I {}
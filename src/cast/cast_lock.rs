@@ -0,0 +1,59 @@
+use parking_lot::{
+    MappedMutexGuard, MappedRwLockReadGuard, MappedRwLockWriteGuard, MutexGuard, RwLockReadGuard,
+    RwLockWriteGuard,
+};
+
+use crate::cast::{CastMut, CastRef};
+use crate::CastFrom;
+
+/// Casts the guard of a `parking_lot::Mutex` holding a trait object into a guard of type `T`,
+/// which keeps the mutex locked until the result is dropped. If fails, returns `guard`.
+/// It is enabled with `parking_lot` feature.
+///
+/// # Examples
+/// ```
+/// # use std::sync::Arc;
+/// # use intertrait::*;
+/// use intertrait::cast::*;
+/// use parking_lot::Mutex;
+///
+/// # #[cast_to(Count)]
+/// # struct Counter(u32);
+/// # trait Source: CastFrom + Send {}
+/// # trait Count {
+/// #     fn increment(&mut self) -> u32;
+/// # }
+/// # impl Count for Counter {
+/// #     fn increment(&mut self) -> u32 {
+/// #         self.0 += 1;
+/// #         self.0
+/// #     }
+/// # }
+/// impl Source for Counter {}
+/// let source: Arc<Mutex<dyn Source>> = Arc::new(Mutex::new(Counter(0)));
+/// let mut count = cast_mutex_guard::<_, dyn Count>(source.lock()).ok().unwrap();
+/// assert_eq!(count.increment(), 1);
+/// ```
+pub fn cast_mutex_guard<'a, S: ?Sized + CastFrom, T: ?Sized + 'static>(
+    guard: MutexGuard<'a, S>,
+) -> Result<MappedMutexGuard<'a, T>, MutexGuard<'a, S>> {
+    MutexGuard::try_map(guard, |source| CastMut::cast::<T>(source))
+}
+
+/// Casts the read guard of a `parking_lot::RwLock` holding a trait object into a read guard
+/// of type `T`, which keeps the lock held until the result is dropped. If fails, returns
+/// `guard`. It is enabled with `parking_lot` feature.
+pub fn cast_read_guard<'a, S: ?Sized + CastFrom, T: ?Sized + 'static>(
+    guard: RwLockReadGuard<'a, S>,
+) -> Result<MappedRwLockReadGuard<'a, T>, RwLockReadGuard<'a, S>> {
+    RwLockReadGuard::try_map(guard, |source| CastRef::cast::<T>(source))
+}
+
+/// Casts the write guard of a `parking_lot::RwLock` holding a trait object into a write guard
+/// of type `T`, which keeps the lock held until the result is dropped. If fails, returns
+/// `guard`. It is enabled with `parking_lot` feature.
+pub fn cast_write_guard<'a, S: ?Sized + CastFrom, T: ?Sized + 'static>(
+    guard: RwLockWriteGuard<'a, S>,
+) -> Result<MappedRwLockWriteGuard<'a, T>, RwLockWriteGuard<'a, S>> {
+    RwLockWriteGuard::try_map(guard, |source| CastMut::cast::<T>(source))
+}
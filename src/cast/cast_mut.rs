@@ -1,4 +1,8 @@
-use crate::{caster, CastFrom};
+use std::any::TypeId;
+use std::pin::Pin;
+
+use crate::cast::CastError;
+use crate::{caster, deref, downcast_mut, panic_not_castable, pin_caster, CastFrom};
 
 /// A trait that is blanket-implemented for traits extending `CastFrom` to allow for casting
 /// of a trait object for it behind an mutable reference to a trait object for another trait
@@ -29,13 +33,100 @@ use crate::{caster, CastFrom};
 pub trait CastMut {
     /// Casts a mutable reference to this trait into that of type `T`.
     fn cast<T: ?Sized + 'static>(&mut self) -> Option<&mut T>;
+
+    /// Casts a mutable reference to this trait into that of type `T`, which must succeed.
+    ///
+    /// # Panics
+    /// Panics with the names of the underlying type and `T` if the cast fails.
+    fn expect_cast<T: ?Sized + 'static>(&mut self) -> &mut T;
+
+    /// Casts a mutable reference to this trait into that of type `T`. If fails, returns
+    /// an error telling the concrete type behind and `T`.
+    fn try_cast<T: ?Sized + 'static>(&mut self) -> Result<&mut T, CastError>;
+
+    /// Casts a mutable reference to this trait into that of type `T`, and calls `f` with it,
+    /// which keeps the borrow within `f`. Returns what `f` returns, or `None` if the cast fails.
+    fn with_cast<T: ?Sized + 'static, R>(&mut self, f: impl FnOnce(&mut T) -> R) -> Option<R>;
+
+    /// Casts a pinned mutable reference to this trait into that of type `T`.
+    ///
+    /// The value stays pinned as the cast only changes the trait through which it is viewed.
+    /// This relies on the `cast_mut` of the [`Caster<T>`] for the underlying type returning
+    /// the value given as is without moving it, which is promised only by those referred to
+    /// by a [`CasterEntry`], e.g. the ones generated by the macros. So the casts registered
+    /// otherwise, e.g. with `dynamic::register_caster` or a fallback, fail. Unlike `cast`, it
+    /// doesn't cast through `Deref` registered with `#[cast_to(deref_to = Target)]`, since
+    /// the target isn't pinned by pinning the value.
+    ///
+    /// # Examples
+    /// ```
+    /// # use std::marker::PhantomPinned;
+    /// # use std::pin::Pin;
+    /// # use intertrait::*;
+    /// use intertrait::cast::*;
+    ///
+    /// # #[cast_to(Greet)]
+    /// # struct Data(PhantomPinned);
+    /// # trait Source: CastFrom {}
+    /// # trait Greet {
+    /// #     fn greet(&self);
+    /// # }
+    /// # impl Greet for Data {
+    /// #    fn greet(&self) {
+    /// #        println!("Hello");
+    /// #    }
+    /// # }
+    /// impl Source for Data {}
+    /// let mut source: Pin<Box<dyn Source>> = Box::pin(Data(PhantomPinned));
+    /// let greet: Pin<&mut dyn Greet> = source.as_mut().cast_pin::<dyn Greet>().unwrap();
+    /// greet.greet();
+    /// ```
+    ///
+    /// [`Caster<T>`]: ../struct.Caster.html
+    /// [`CasterEntry`]: ../struct.CasterEntry.html
+    fn cast_pin<T: ?Sized + 'static>(self: Pin<&mut Self>) -> Option<Pin<&mut T>>;
 }
 
 /// A blanket implementation of `CastMut` for traits extending `CastFrom`.
 impl<S: ?Sized + CastFrom> CastMut for S {
     fn cast<T: ?Sized + 'static>(&mut self) -> Option<&mut T> {
         let any = self.mut_any();
-        let caster = caster::<T>((*any).type_id())?;
-        (caster.cast_mut)(any).into()
+        if (*any).type_id() == TypeId::of::<T>() {
+            return downcast_mut::<T>(any);
+        }
+        match caster::<T>((*any).type_id()) {
+            Some(caster) => Some((caster.cast_mut)(any)),
+            None => deref::cast_mut(any),
+        }
+    }
+
+    fn expect_cast<T: ?Sized + 'static>(&mut self) -> &mut T {
+        let type_name = (*self).type_name();
+        match self.cast::<T>() {
+            Some(target) => target,
+            None => panic_not_castable::<T>(type_name),
+        }
+    }
+
+    fn try_cast<T: ?Sized + 'static>(&mut self) -> Result<&mut T, CastError> {
+        let err = CastError::new::<T, _>(self);
+        self.cast::<T>().ok_or(err)
+    }
+
+    fn with_cast<T: ?Sized + 'static, R>(&mut self, f: impl FnOnce(&mut T) -> R) -> Option<R> {
+        self.cast::<T>().map(f)
+    }
+
+    fn cast_pin<T: ?Sized + 'static>(self: Pin<&mut Self>) -> Option<Pin<&mut T>> {
+        // SAFETY: The value is never moved, but only viewed through another type, which is
+        // pinned again. `mut_any` of `CastFrom`, which is sealed, returns the value as is.
+        // So does the caster, which is referred to by a `CasterEntry`, whose creator promises
+        // it.
+        let any = unsafe { self.get_unchecked_mut() }.mut_any();
+        if (*any).type_id() == TypeId::of::<T>() {
+            return downcast_mut::<T>(any).map(|target| unsafe { Pin::new_unchecked(target) });
+        }
+        let caster = pin_caster::<T>((*any).type_id())?;
+        Some(unsafe { Pin::new_unchecked((caster.cast_mut)(any)) })
     }
 }
@@ -0,0 +1,45 @@
+use rayon::prelude::*;
+
+use crate::cast::CastRef;
+use crate::CastFrom;
+
+/// Casts each of boxed trait objects in `items` into a reference to type `T` in parallel,
+/// skipping those that can't be cast. It is enabled with `rayon` feature.
+///
+/// The lookup of the casters is safe to be done concurrently, so a heterogeneous collection
+/// can be cast and processed across threads. The trait objects must be `Sync` to be shared,
+/// and `T` must be `Sync` for the references to be sent between threads.
+///
+/// # Examples
+/// ```
+/// # use intertrait::*;
+/// use intertrait::cast::*;
+/// use rayon::prelude::*;
+///
+/// # #[cast_to(Greet)]
+/// # struct Data;
+/// # struct Other;
+/// # trait Source: CastFrom {}
+/// # trait Greet: Sync {
+/// #     fn greet(&self) -> usize;
+/// # }
+/// # impl Greet for Data {
+/// #    fn greet(&self) -> usize {
+/// #        1
+/// #    }
+/// # }
+/// impl Source for Data {}
+/// impl Source for Other {}
+/// let sources: Vec<Box<dyn Source + Sync>> = vec![Box::new(Data), Box::new(Other)];
+/// let greets: usize = par_cast_refs::<_, dyn Greet>(&sources)
+///     .map(|greet| greet.greet())
+///     .sum();
+/// assert_eq!(greets, 1);
+/// ```
+pub fn par_cast_refs<'a, S, T>(items: &'a [Box<S>]) -> impl ParallelIterator<Item = &'a T> + 'a
+where
+    S: ?Sized + CastFrom + Sync,
+    T: ?Sized + Sync + 'static,
+{
+    items.par_iter().filter_map(|item| (**item).cast::<T>())
+}
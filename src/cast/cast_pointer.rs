@@ -0,0 +1,88 @@
+use std::any::Any;
+
+use crate::caster;
+
+/// A trait to be implemented by a user-defined smart pointer (e.g. a reference-counted one
+/// from another crate) to allow for casting of a trait object behind it into another trait
+/// object with [`pointer_cast`].
+///
+/// `T` is the target of the cast, and [`Output`] is the same kind of pointer to `T`.
+///
+/// # Examples
+/// A pointer built on top of `Rc`:
+/// ```
+/// use std::any::Any;
+/// use std::rc::Rc;
+/// # use intertrait::*;
+/// use intertrait::cast::*;
+///
+/// # #[cast_to(Greet)]
+/// # struct Data;
+/// # trait Source: CastFrom {}
+/// # trait Greet {
+/// #     fn greet(&self);
+/// # }
+/// # impl Greet for Data {
+/// #    fn greet(&self) {
+/// #        println!("Hello");
+/// #    }
+/// # }
+/// impl Source for Data {}
+///
+/// struct Shared<T: ?Sized>(Rc<T>);
+///
+/// impl<S: ?Sized + CastFrom, T: ?Sized> CastPointer<T> for Shared<S> {
+///     type Output = Shared<T>;
+///
+///     fn as_any(&self) -> &dyn Any {
+///         (*self.0).ref_any()
+///     }
+///
+///     fn downcast(self, cast: fn(&dyn Any) -> &T) -> Shared<T> {
+///         let raw = Rc::into_raw(self.0);
+///         // Safe as `cast` returns a pointer into the same allocation as `raw`.
+///         unsafe {
+///             let target: *const T = cast((*raw).ref_any());
+///             Shared(Rc::from_raw(target))
+///         }
+///     }
+/// }
+///
+/// let source: Shared<dyn Source> = Shared(Rc::new(Data));
+/// let greet = pointer_cast::<dyn Greet, _>(source);
+/// greet.unwrap_or_else(|_| panic!("casting failed")).0.greet();
+/// ```
+///
+/// [`pointer_cast`]: ./fn.pointer_cast.html
+/// [`Output`]: #associatedtype.Output
+pub trait CastPointer<T: ?Sized>: Sized {
+    /// The pointer to `T` into which this pointer is cast.
+    type Output;
+
+    /// Returns the value behind this pointer as `Any`, which is backed by its concrete type
+    /// rather than the pointer itself.
+    fn as_any(&self) -> &dyn Any;
+
+    /// Converts this pointer into one to `T`, given `cast` which casts the value returned
+    /// by `as_any` into `T`.
+    ///
+    /// The reference returned by `cast` points to the same value as this pointer does,
+    /// which an implementation usually turns into a raw pointer to rebuild the pointer from
+    /// along with the ownership (e.g. reference count) this pointer holds.
+    fn downcast(self, cast: fn(&dyn Any) -> &T) -> Self::Output;
+}
+
+/// Casts `pointer` into a pointer to `T` through its implementation of [`CastPointer`].
+/// If the cast fails, returns the `pointer` as is.
+///
+/// [`CastPointer`]: ./trait.CastPointer.html
+pub fn pointer_cast<T, P>(pointer: P) -> Result<P::Output, P>
+where
+    T: ?Sized + 'static,
+    P: CastPointer<T>,
+{
+    match caster::<T>(pointer.as_any().type_id()) {
+        Some(caster) => Ok(pointer.downcast(caster.cast_ref)),
+        None => Err(pointer),
+    }
+}
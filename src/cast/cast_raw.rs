@@ -0,0 +1,77 @@
+use std::any::{Any, TypeId};
+
+use crate::{caster, downcast_ptr};
+
+/// Casts a raw pointer to a trait object for `Any` into a raw pointer to `T`, looking up
+/// the caster with `type_id` of the concrete type of the value behind it.
+///
+/// It's meant for FFI layers holding raw pointers to trait objects, which can cast them
+/// without reconstructing references of some lifetime on their side. `T` may also be
+/// the concrete type itself. Returns `None` if no cast into `T` is registered.
+///
+/// # Safety
+/// - `ptr` must be non-null, properly aligned and point to a live value, which isn't
+///   mutably borrowed elsewhere during the call.
+/// - `type_id` must be `TypeId` of the concrete type of the value, which is what
+///   `Any::type_id` returns for it.
+///
+/// The returned pointer points to the same value as `ptr`, and is valid as long as `ptr` is.
+///
+/// # Examples
+/// ```
+/// use std::any::{Any, TypeId};
+/// # use intertrait::*;
+/// use intertrait::cast::*;
+///
+/// # #[cast_to(Greet)]
+/// # struct Data;
+/// # trait Greet {
+/// #     fn greet(&self) -> &'static str;
+/// # }
+/// # impl Greet for Data {
+/// #    fn greet(&self) -> &'static str {
+/// #        "hello"
+/// #    }
+/// # }
+/// let data = Box::into_raw(Box::new(Data) as Box<dyn Any>);
+/// unsafe {
+///     let greet = cast_ptr::<dyn Greet>(data, TypeId::of::<Data>()).unwrap();
+///     assert_eq!((*greet).greet(), "hello");
+///     drop(Box::from_raw(data));
+/// }
+/// ```
+pub unsafe fn cast_ptr<T: ?Sized + 'static>(
+    ptr: *const dyn Any,
+    type_id: TypeId,
+) -> Option<*const T> {
+    if let Some(target) = downcast_ptr::<T>(type_id, ptr as *const ()) {
+        return Some(target);
+    }
+    let caster = caster::<T>(type_id)?;
+    // The caller guarantees `ptr` may be borrowed for the duration of the call.
+    Some((caster.cast_ref)(&*ptr))
+}
+
+/// Casts a raw pointer to a trait object for `Any` into a mutable raw pointer to `T`.
+/// It's the same as [`cast_ptr`] except for the mutability.
+///
+/// # Safety
+/// - `ptr` must be non-null, properly aligned and point to a live value, which isn't
+///   borrowed elsewhere during the call.
+/// - `type_id` must be `TypeId` of the concrete type of the value, which is what
+///   `Any::type_id` returns for it.
+///
+/// The returned pointer points to the same value as `ptr`, and is valid as long as `ptr` is.
+///
+/// [`cast_ptr`]: ./fn.cast_ptr.html
+pub unsafe fn cast_ptr_mut<T: ?Sized + 'static>(
+    ptr: *mut dyn Any,
+    type_id: TypeId,
+) -> Option<*mut T> {
+    if let Some(target) = downcast_ptr::<T>(type_id, ptr as *const ()) {
+        return Some(target as *mut T);
+    }
+    let caster = caster::<T>(type_id)?;
+    // The caller guarantees `ptr` may be borrowed mutably for the duration of the call.
+    Some((caster.cast_mut)(&mut *ptr))
+}
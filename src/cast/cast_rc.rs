@@ -1,4 +1,4 @@
-use crate::{caster, CastFrom};
+use crate::{caster, panic_not_castable, CastFrom};
 use std::rc::Rc;
 
 /// A trait that is blanket-implemented for traits extending `CastFrom` to allow for casting
@@ -31,6 +31,12 @@ use std::rc::Rc;
 pub trait CastRc {
     /// Casts an `Rc` for this trait into that for type `T`.
     fn cast<T: ?Sized + 'static>(self: Rc<Self>) -> Result<Rc<T>, Rc<Self>>;
+
+    /// Casts an `Rc` for this trait into that for type `T`, which must succeed.
+    ///
+    /// # Panics
+    /// Panics with the names of the underlying type and `T` if the cast fails.
+    fn expect_cast<T: ?Sized + 'static>(self: Rc<Self>) -> Rc<T>;
 }
 
 /// A blanket implementation of `CastRc` for traits extending `CastFrom`.
@@ -41,4 +47,11 @@ impl<S: ?Sized + CastFrom> CastRc for S {
             None => Err(self),
         }
     }
+
+    fn expect_cast<T: ?Sized + 'static>(self: Rc<Self>) -> Rc<T> {
+        match self.cast::<T>() {
+            Ok(target) => target,
+            Err(this) => panic_not_castable::<T>((*this).type_name()),
+        }
+    }
 }
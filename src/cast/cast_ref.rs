@@ -1,6 +1,15 @@
 use std::any::TypeId;
+use std::option;
+use std::ptr::NonNull;
 
-use crate::{caster, CastFrom, Caster, CASTER_MAP};
+#[cfg(feature = "dyn-clone")]
+use dyn_clone::DynClone;
+
+use crate::cast::{CastError, CasterHandle, FirstOf};
+use crate::{
+    caster, deref, downcast_ref, generation, impls, marker, panic_not_castable, CastFrom,
+    CastMarker,
+};
 
 /// A trait that is blanket-implemented for traits extending `CastFrom` to allow for casting
 /// of a trait object for it behind an immutable reference to a trait object for another trait
@@ -52,23 +61,503 @@ use crate::{caster, CastFrom, Caster, CASTER_MAP};
 /// assert!(source.impls::<dyn Greet>());
 /// assert!(!source.impls::<dyn std::fmt::Debug>());
 /// ```
+///
+/// ## Downcasting into a concrete type
+/// `T` can also be the concrete type of the underlying value, which needs no registration.
+/// It goes for casting mutable references and `Box`es as well.
+/// ```
+/// # use intertrait::*;
+/// use intertrait::cast::*;
+///
+/// # trait Source: CastFrom {}
+/// struct Data(u32);
+/// struct Other;
+/// impl Source for Data {}
+/// let source: &dyn Source = &Data(1);
+/// assert_eq!(source.cast::<Data>().unwrap().0, 1);
+/// assert!(source.cast::<Other>().is_none());
+/// ```
+///
+/// ## Mixing with non-`'static` trait objects
+/// The target is named in its `'static` form, as it keys the registry by `TypeId`. But the
+/// underlying value is always `'static`, so the result coerces into `&'a (dyn Trait + 'a)`
+/// to be used along with trait objects for borrowed data.
+/// ```
+/// # use intertrait::*;
+/// use intertrait::cast::*;
+///
+/// # trait Source: CastFrom {}
+/// trait Greet {
+///     fn greet(&self) -> String;
+/// }
+///
+/// #[cast_to(Greet)]
+/// struct Data;
+/// impl Source for Data {}
+/// impl Greet for Data {
+///     fn greet(&self) -> String {
+///         "Hello".to_string()
+///     }
+/// }
+///
+/// struct Borrowed<'a>(&'a str);
+/// impl Greet for Borrowed<'_> {
+///     fn greet(&self) -> String {
+///         self.0.to_string()
+///     }
+/// }
+///
+/// let name = String::from("World");
+/// let borrowed = Borrowed(&name);
+/// let source: &dyn Source = &Data;
+/// let greets: Vec<&dyn Greet> = vec![source.cast::<dyn Greet>().unwrap(), &borrowed];
+/// assert_eq!(greets[0].greet() + " " + &greets[1].greet(), "Hello World");
+/// ```
 pub trait CastRef {
     /// Casts a reference to this trait into that of type `T`.
     fn cast<T: ?Sized + 'static>(&self) -> Option<&T>;
 
+    /// Casts a reference to this trait into that of type `T`. If fails, returns an error
+    /// telling the concrete type behind and `T`.
+    fn try_cast<T: ?Sized + 'static>(&self) -> Result<&T, CastError>;
+
     /// Tests if this trait object can be cast into `T`.
     fn impls<T: ?Sized + 'static>(&self) -> bool;
+
+    /// Tests if the underlying type is registered with `#[cast_to(marker Trait)]` to have
+    /// the capability of a trait that can't form trait objects, identified by `M` generated
+    /// for the trait by `#[cast_marker]`.
+    ///
+    /// # Examples
+    /// ```
+    /// # use intertrait::*;
+    /// use intertrait::cast::*;
+    ///
+    /// #[cast_marker]
+    /// trait Spawn {
+    ///     fn spawn() -> Self;
+    /// }
+    ///
+    /// #[cast_to(marker Spawn)]
+    /// struct Data;
+    /// # trait Source: CastFrom {}
+    /// impl Source for Data {}
+    /// impl Spawn for Data {
+    ///     fn spawn() -> Self {
+    ///         Data
+    ///     }
+    /// }
+    ///
+    /// let source: &dyn Source = &Data;
+    /// assert!(source.impls_marker::<SpawnMarker>());
+    /// ```
+    fn impls_marker<M: CastMarker>(&self) -> bool;
+
+    /// Tests if this trait object can be cast into every type in `L`, which is a tuple of
+    /// references to the types, e.g. `(&dyn A, &dyn B)`, as a tuple can't hold unsized types.
+    ///
+    /// It lets all the capabilities required be checked up front before borrowing any of them.
+    ///
+    /// # Examples
+    /// ```
+    /// # use std::fmt::{Debug, Display};
+    /// # use intertrait::*;
+    /// use intertrait::cast::*;
+    ///
+    /// # trait Source: CastFrom {}
+    /// #[cast_to(Debug, Display)]
+    /// #[derive(Debug)]
+    /// struct Data;
+    /// # impl Display for Data {
+    /// #     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+    /// #         write!(f, "Data")
+    /// #     }
+    /// # }
+    /// impl Source for Data {}
+    /// let source: &dyn Source = &Data;
+    /// assert!(source.impls_all::<(&dyn Debug, &dyn Display)>());
+    /// assert!(!source.impls_all::<(&dyn Debug, &dyn std::error::Error)>());
+    /// ```
+    fn impls_all<L: CastTargets>(&self) -> bool;
+
+    /// Casts this trait object into the first of the types in `L` it can be cast into, which is
+    /// a tuple of references to the types, e.g. `(&dyn A, &dyn B)`. Returns which one it is
+    /// along with the reference cast into it.
+    ///
+    /// # Examples
+    /// Preferring one capability and falling back to another:
+    /// ```
+    /// # use intertrait::*;
+    /// use intertrait::cast::*;
+    ///
+    /// # trait Source: CastFrom {}
+    /// trait GpuDraw {
+    ///     fn draw_gpu(&self) -> &'static str;
+    /// }
+    /// trait CpuDraw {
+    ///     fn draw_cpu(&self) -> &'static str;
+    /// }
+    ///
+    /// #[cast_to(CpuDraw)]
+    /// struct Canvas;
+    /// impl CpuDraw for Canvas {
+    ///     fn draw_cpu(&self) -> &'static str {
+    ///         "cpu"
+    ///     }
+    /// }
+    /// impl Source for Canvas {}
+    ///
+    /// let source: &dyn Source = &Canvas;
+    /// let drawn = match source.first_of::<(&dyn GpuDraw, &dyn CpuDraw)>() {
+    ///     Some(OneOf2::First(gpu)) => gpu.draw_gpu(),
+    ///     Some(OneOf2::Second(cpu)) => cpu.draw_cpu(),
+    ///     None => "nothing",
+    /// };
+    /// assert_eq!(drawn, "cpu");
+    /// ```
+    fn first_of<'a, L: FirstOf<'a>>(&'a self) -> Option<L::Output>;
+
+    /// Casts a reference to this trait into that of type `T`, and clones the underlying value
+    /// into a `Box` of `T`. It is enabled with `dyn-clone` feature.
+    ///
+    /// `T` must extend `DynClone`, which is implemented by all the `Clone` types.
+    ///
+    /// # Examples
+    /// ```
+    /// # use intertrait::*;
+    /// use dyn_clone::DynClone;
+    /// use intertrait::cast::*;
+    ///
+    /// # trait Source: CastFrom {}
+    /// trait Shape: DynClone {
+    ///     fn area(&self) -> u32;
+    /// }
+    ///
+    /// #[cast_to(Shape)]
+    /// #[derive(Clone)]
+    /// struct Square(u32);
+    /// impl Source for Square {}
+    /// impl Shape for Square {
+    ///     fn area(&self) -> u32 {
+    ///         self.0 * self.0
+    ///     }
+    /// }
+    ///
+    /// let template: &dyn Source = &Square(2);
+    /// let shape: Box<dyn Shape> = template.clone_to::<dyn Shape>().unwrap();
+    /// assert_eq!(shape.area(), 4);
+    /// ```
+    #[cfg(feature = "dyn-clone")]
+    fn clone_to<T: ?Sized + DynClone + 'static>(&self) -> Option<Box<T>>;
+
+    /// Casts a reference to this trait into that of type `T`, which must succeed.
+    ///
+    /// # Panics
+    /// Panics with the names of the underlying type and `T` if the cast fails.
+    fn expect_cast<T: ?Sized + 'static>(&self) -> &T;
+
+    /// Casts a reference to this trait into a non-null pointer to type `T`, which can be held
+    /// where a reference can't, e.g. across an FFI boundary.
+    ///
+    /// Creating the pointer is safe, but dereferencing it is only sound under the contracts
+    /// of a shared reference the caller must uphold:
+    /// * The pointer must not be dereferenced after the referent is dropped or moved.
+    /// * The referent must not be mutated through the pointer or otherwise while a reference
+    ///   reconstructed from the pointer is alive, except through interior mutability.
+    ///
+    /// # Examples
+    /// ```
+    /// # use intertrait::*;
+    /// use intertrait::cast::*;
+    ///
+    /// # #[cast_to(Greet)]
+    /// # struct Data;
+    /// # trait Source: CastFrom {}
+    /// # trait Greet {
+    /// #     fn greet(&self);
+    /// # }
+    /// # impl Greet for Data {
+    /// #    fn greet(&self) {
+    /// #        println!("Hello");
+    /// #    }
+    /// # }
+    /// impl Source for Data {}
+    /// let data = Data;
+    /// let source: &dyn Source = &data;
+    /// let ptr = source.cast_non_null::<dyn Greet>().unwrap();
+    /// // Safe as `data` is alive and not mutated.
+    /// unsafe { ptr.as_ref() }.greet();
+    /// ```
+    fn cast_non_null<T: ?Sized + 'static>(&self) -> Option<NonNull<T>>;
+
+    /// Casts a reference to this trait into that of type `T`, and calls `f` with it, which
+    /// keeps the borrow within `f`. Returns what `f` returns, or `None` if the cast fails.
+    ///
+    /// # Examples
+    /// ```
+    /// # use intertrait::*;
+    /// use intertrait::cast::*;
+    ///
+    /// # #[cast_to(Greet)]
+    /// # struct Data;
+    /// # trait Source: CastFrom {}
+    /// # trait Greet {
+    /// #     fn greet(&self) -> &'static str;
+    /// # }
+    /// # impl Greet for Data {
+    /// #    fn greet(&self) -> &'static str {
+    /// #        "Hello"
+    /// #    }
+    /// # }
+    /// impl Source for Data {}
+    /// let source: &dyn Source = &Data;
+    /// let len = source.with_cast::<dyn Greet, _>(|greet| greet.greet().len());
+    /// assert_eq!(len, Some(5));
+    /// ```
+    fn with_cast<T: ?Sized + 'static, R>(&self, f: impl FnOnce(&T) -> R) -> Option<R>;
+
+    /// Casts a reference to this trait into that of type `T`, yielding it if succeeded.
+    /// It reads naturally in a `flat_map` over a collection of trait objects.
+    ///
+    /// # Examples
+    /// ```
+    /// # use intertrait::*;
+    /// use intertrait::cast::*;
+    ///
+    /// # #[cast_to(Greet)]
+    /// # struct Data;
+    /// # struct Other;
+    /// # trait Source: CastFrom {}
+    /// # trait Greet {
+    /// #     fn greet(&self);
+    /// # }
+    /// # impl Greet for Data {
+    /// #    fn greet(&self) {
+    /// #        println!("Hello");
+    /// #    }
+    /// # }
+    /// impl Source for Data {}
+    /// impl Source for Other {}
+    /// let sources: Vec<Box<dyn Source>> = vec![Box::new(Data), Box::new(Other)];
+    /// let greets = sources.iter().flat_map(|source| (**source).cast_iter::<dyn Greet>());
+    /// assert_eq!(greets.count(), 1);
+    /// ```
+    fn cast_iter<T: ?Sized + 'static>(&self) -> option::IntoIter<&T>;
+
+    /// Casts a reference to this trait into that of type `T` along with a [`CasterHandle`]
+    /// reusable for casting more values of the same concrete type without the lookup.
+    ///
+    /// Unlike `cast`, it doesn't fall back to casting through `Deref`, which no handle
+    /// can be resolved for. The handle stops casting once the cast is no longer allowed
+    /// at runtime.
+    ///
+    /// # Examples
+    /// ```
+    /// # use intertrait::*;
+    /// use intertrait::cast::*;
+    ///
+    /// # #[cast_to(Greet)]
+    /// # struct Data;
+    /// # trait Source: CastFrom {}
+    /// # trait Greet {
+    /// #     fn greet(&self);
+    /// # }
+    /// # impl Greet for Data {
+    /// #    fn greet(&self) {
+    /// #        println!("Hello");
+    /// #    }
+    /// # }
+    /// impl Source for Data {}
+    /// let sources: Vec<Box<dyn Source>> = (0..100).map(|_| Box::new(Data) as _).collect();
+    /// let (greet, handle) = (*sources[0]).cast_handled::<dyn Greet>().unwrap();
+    /// greet.greet();
+    /// for source in &sources[1..] {
+    ///     handle.cast_ref(&**source).unwrap().greet();
+    /// }
+    /// ```
+    ///
+    /// [`CasterHandle`]: ./struct.CasterHandle.html
+    fn cast_handled<T: ?Sized + 'static>(&self) -> Option<(&T, CasterHandle<T>)>;
 }
 
 /// A blanket implementation of `CastRef` for traits extending `CastFrom`.
 impl<S: ?Sized + CastFrom> CastRef for S {
     fn cast<T: ?Sized + 'static>(&self) -> Option<&T> {
         let any = self.ref_any();
-        let caster = caster::<T>(any.type_id())?;
-        (caster.cast_ref)(any).into()
+        if let Some(target) = downcast_ref::<T>(any) {
+            return Some(target);
+        }
+        match caster::<T>(any.type_id()) {
+            Some(caster) => Some((caster.cast_ref)(any)),
+            None => deref::cast_ref(any),
+        }
+    }
+
+    fn try_cast<T: ?Sized + 'static>(&self) -> Result<&T, CastError> {
+        self.cast::<T>().ok_or_else(|| CastError::new::<T, _>(self))
     }
 
     fn impls<T: ?Sized + 'static>(&self) -> bool {
-        CASTER_MAP.contains_key(&(self.type_id(), TypeId::of::<Caster<T>>()))
+        impls::<T>(self.type_id())
+    }
+
+    fn impls_marker<M: CastMarker>(&self) -> bool {
+        marker::contains(self.type_id(), TypeId::of::<M>())
+    }
+
+    fn impls_all<L: CastTargets>(&self) -> bool {
+        L::impls_all(self.type_id())
+    }
+
+    fn first_of<'a, L: FirstOf<'a>>(&'a self) -> Option<L::Output> {
+        L::first_of(self.ref_any())
+    }
+
+    fn expect_cast<T: ?Sized + 'static>(&self) -> &T {
+        match self.cast::<T>() {
+            Some(target) => target,
+            None => panic_not_castable::<T>(self.type_name()),
+        }
+    }
+
+    fn cast_non_null<T: ?Sized + 'static>(&self) -> Option<NonNull<T>> {
+        self.cast::<T>().map(NonNull::from)
+    }
+
+    fn with_cast<T: ?Sized + 'static, R>(&self, f: impl FnOnce(&T) -> R) -> Option<R> {
+        self.cast::<T>().map(f)
+    }
+
+    fn cast_iter<T: ?Sized + 'static>(&self) -> option::IntoIter<&T> {
+        self.cast::<T>().into_iter()
+    }
+
+    #[cfg(feature = "dyn-clone")]
+    fn clone_to<T: ?Sized + DynClone + 'static>(&self) -> Option<Box<T>> {
+        self.cast::<T>().map(dyn_clone::clone_box)
+    }
+
+    fn cast_handled<T: ?Sized + 'static>(&self) -> Option<(&T, CasterHandle<T>)> {
+        let any = self.ref_any();
+        let type_id = any.type_id();
+        let generation = generation();
+        let caster = caster::<T>(type_id)?;
+        Some((
+            (caster.cast_ref)(any),
+            CasterHandle::new(type_id, caster, generation),
+        ))
+    }
+}
+
+/// A tuple of references to the types into which [`CastRef::impls_all`] tests casts,
+/// e.g. `(&dyn A, &dyn B)`, which is implemented for tuples of up to 8 elements.
+///
+/// [`CastRef::impls_all`]: ./trait.CastRef.html#tymethod.impls_all
+pub trait CastTargets {
+    /// Tests if a value of a concrete type of `type_id` can be cast into every type.
+    fn impls_all(type_id: TypeId) -> bool;
+}
+
+macro_rules! impl_cast_targets {
+    ($($T:ident),+) => {
+        impl<'a, $($T: ?Sized + 'static),+> CastTargets for ($(&'a $T,)+) {
+            fn impls_all(type_id: TypeId) -> bool {
+                $(impls::<$T>(type_id))&&+
+            }
+        }
+    };
+}
+
+impl_cast_targets!(A);
+impl_cast_targets!(A, B);
+impl_cast_targets!(A, B, C);
+impl_cast_targets!(A, B, C, D);
+impl_cast_targets!(A, B, C, D, E);
+impl_cast_targets!(A, B, C, D, E, F);
+impl_cast_targets!(A, B, C, D, E, F, G);
+impl_cast_targets!(A, B, C, D, E, F, G, H);
+
+/// Casts each of borrowed trait objects in `items` into a reference to type `T`,
+/// skipping those that can't be cast. The references yielded keep the lifetime `'a`
+/// of the borrowed objects.
+///
+/// # Examples
+/// ```
+/// # use intertrait::*;
+/// use intertrait::cast::*;
+///
+/// # #[cast_to(Greet)]
+/// # struct Data;
+/// # struct Other;
+/// # trait Source: CastFrom {}
+/// # trait Greet {
+/// #     fn greet(&self);
+/// # }
+/// # impl Greet for Data {
+/// #    fn greet(&self) {
+/// #        println!("Hello");
+/// #    }
+/// # }
+/// impl Source for Data {}
+/// impl Source for Other {}
+/// let (data, other) = (Data, Other);
+/// let sources: Vec<&dyn Source> = vec![&data, &other, &data];
+/// let greets: Vec<&dyn Greet> = cast_borrowed_refs(&sources).collect();
+/// assert_eq!(greets.len(), 2);
+/// ```
+pub fn cast_borrowed_refs<'a, S, T>(items: &'a [&'a S]) -> impl Iterator<Item = &'a T> + 'a
+where
+    S: ?Sized + CastFrom,
+    T: ?Sized + 'static,
+{
+    items.iter().filter_map(|item| (**item).cast::<T>())
+}
+
+/// Casts all of borrowed trait objects in `items` of a single concrete type into references
+/// to type `T`, looking up the registry only once for the first of them.
+///
+/// Returns `None` if they can't be cast, including when any of them is of a concrete type
+/// other than that of the first, which is checked by comparing `TypeId`s instead of looking up
+/// the registry again.
+///
+/// # Examples
+/// ```
+/// # use intertrait::*;
+/// use intertrait::cast::*;
+///
+/// # #[cast_to(Greet)]
+/// # struct Data;
+/// # struct Other;
+/// # trait Source: CastFrom {}
+/// # trait Greet {
+/// #     fn greet(&self);
+/// # }
+/// # impl Greet for Data {
+/// #    fn greet(&self) {
+/// #        println!("Hello");
+/// #    }
+/// # }
+/// impl Source for Data {}
+/// impl Source for Other {}
+/// let (data, other) = (Data, Other);
+/// let greets: Vec<&dyn Greet> = cast_slice(&[&data as &dyn Source, &data]).unwrap();
+/// assert_eq!(greets.len(), 2);
+/// assert!(cast_slice::<_, dyn Greet>(&[&data as &dyn Source, &other]).is_none());
+/// ```
+pub fn cast_slice<'a, S, T>(items: &[&'a S]) -> Option<Vec<&'a T>>
+where
+    S: ?Sized + CastFrom,
+    T: ?Sized + 'static,
+{
+    let (first, rest) = match items.split_first() {
+        Some(split) => split,
+        None => return Some(Vec::new()),
+    };
+    let (target, handle) = (**first).cast_handled::<T>()?;
+    let mut targets = Vec::with_capacity(items.len());
+    targets.push(target);
+    for item in rest {
+        targets.push(handle.cast_ref(*item)?);
     }
+    Some(targets)
 }
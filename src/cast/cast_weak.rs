@@ -0,0 +1,99 @@
+use std::rc::{self, Rc};
+use std::sync::{self, Arc};
+
+use crate::cast::{CastArc, CastRc};
+use crate::{CastFrom, CastFromSync};
+
+/// A trait that is implemented for `rc::Weak`s of traits extending `CastFrom` to allow for
+/// casting of a weak reference to a trait object into that for another trait implemented
+/// by the underlying value.
+///
+/// The method is named `cast_weak` rather than `cast` since a `Weak` is itself `CastFrom`,
+/// for which `cast` would be ambiguous with `CastRef::cast`.
+///
+/// # Examples
+/// ```
+/// # use std::rc::Rc;
+/// # use intertrait::*;
+/// use intertrait::cast::*;
+///
+/// # #[cast_to(Observer)]
+/// # struct Data;
+/// # trait Source: CastFrom {}
+/// # trait Observer {
+/// #     fn notify(&self);
+/// # }
+/// # impl Observer for Data {
+/// #    fn notify(&self) {
+/// #        println!("Notified");
+/// #    }
+/// # }
+/// impl Source for Data {}
+/// let source: Rc<dyn Source> = Rc::new(Data);
+/// let observer = Rc::downgrade(&source).cast_weak::<dyn Observer>().unwrap();
+/// observer.upgrade().unwrap().notify();
+///
+/// drop(source);
+/// assert!(observer.upgrade().is_none());
+/// ```
+pub trait CastWeak {
+    /// Casts a `Weak` for this trait into that for type `T`, which points to the same
+    /// allocation.
+    ///
+    /// It upgrades the `Weak` temporarily to look up the cast. Returns `None` if the value
+    /// has already been dropped or can't be cast into `T`.
+    fn cast_weak<T: ?Sized + 'static>(&self) -> Option<rc::Weak<T>>;
+}
+
+impl<S: ?Sized + CastFrom> CastWeak for rc::Weak<S> {
+    fn cast_weak<T: ?Sized + 'static>(&self) -> Option<rc::Weak<T>> {
+        let target = self.upgrade()?.cast::<T>().ok()?;
+        Some(Rc::downgrade(&target))
+    }
+}
+
+/// A trait that is implemented for `sync::Weak`s of traits extending `CastFromSync` to allow
+/// for casting of a weak reference to a trait object into that for another trait implemented
+/// by the underlying value.
+///
+/// It is the counterpart of [`CastWeak`] for `Arc`s.
+///
+/// # Examples
+/// ```
+/// # use std::sync::Arc;
+/// # use intertrait::*;
+/// use intertrait::cast::*;
+///
+/// # #[cast_to([sync] Observer)]
+/// # struct Data;
+/// # trait Source: CastFromSync {}
+/// # trait Observer {
+/// #     fn notify(&self);
+/// # }
+/// # impl Observer for Data {
+/// #    fn notify(&self) {
+/// #        println!("Notified");
+/// #    }
+/// # }
+/// impl Source for Data {}
+/// let source: Arc<dyn Source> = Arc::new(Data);
+/// let observer = Arc::downgrade(&source).cast_weak::<dyn Observer>().unwrap();
+/// observer.upgrade().unwrap().notify();
+/// ```
+///
+/// [`CastWeak`]: ./trait.CastWeak.html
+pub trait CastWeakSync {
+    /// Casts a `Weak` for this trait into that for type `T`, which points to the same
+    /// allocation.
+    ///
+    /// It upgrades the `Weak` temporarily to look up the cast. Returns `None` if the value
+    /// has already been dropped or can't be cast into `T`.
+    fn cast_weak<T: ?Sized + 'static>(&self) -> Option<sync::Weak<T>>;
+}
+
+impl<S: ?Sized + CastFromSync> CastWeakSync for sync::Weak<S> {
+    fn cast_weak<T: ?Sized + 'static>(&self) -> Option<sync::Weak<T>> {
+        let target = self.upgrade()?.cast::<T>().ok()?;
+        Some(Arc::downgrade(&target))
+    }
+}
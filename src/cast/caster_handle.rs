@@ -0,0 +1,120 @@
+use std::any::TypeId;
+
+use crate::{caster, generation, CastFrom, Caster};
+
+/// A caster into trait `T` resolved for a concrete type, which is returned by
+/// [`CastRef::cast_handled`] to cast more values of the same concrete type without
+/// looking up the registry.
+///
+/// A value of any other concrete type is rejected rather than cast. Once the casts allowed
+/// at runtime change, e.g. with `set_cast_denylist` or `unregister_caster`, the caster is
+/// resolved again, so a cast no longer allowed is rejected as well.
+///
+/// [`CastRef::cast_handled`]: ./trait.CastRef.html#tymethod.cast_handled
+pub struct CasterHandle<T: ?Sized + 'static> {
+    type_id: TypeId,
+    caster: Caster<T>,
+    generation: usize,
+}
+
+impl<T: ?Sized + 'static> CasterHandle<T> {
+    /// Creates a handle of `caster` resolved at `generation` of the casts.
+    pub(crate) fn new(type_id: TypeId, caster: Caster<T>, generation: usize) -> Self {
+        CasterHandle {
+            type_id,
+            caster,
+            generation,
+        }
+    }
+
+    /// Returns the caster resolved, which is looked up again if the casts have changed since.
+    fn caster(&self) -> Option<Caster<T>> {
+        if self.generation == generation() {
+            Some(self.caster)
+        } else {
+            caster::<T>(self.type_id)
+        }
+    }
+
+    /// Returns `TypeId` of the concrete type this handle casts.
+    pub fn type_id(&self) -> TypeId {
+        self.type_id
+    }
+
+    /// Casts a reference to a trait object for `S` into that of type `T`
+    /// if its concrete type is the one this handle was resolved for.
+    pub fn cast_ref<'a, S: ?Sized + CastFrom>(&self, source: &'a S) -> Option<&'a T> {
+        let any = source.ref_any();
+        if any.type_id() != self.type_id {
+            return None;
+        }
+        self.caster().map(|caster| (caster.cast_ref)(any))
+    }
+
+    /// Casts a mutable reference to a trait object for `S` into that of type `T`
+    /// if its concrete type is the one this handle was resolved for.
+    pub fn cast_mut<'a, S: ?Sized + CastFrom>(&self, source: &'a mut S) -> Option<&'a mut T> {
+        let any = source.mut_any();
+        if (*any).type_id() != self.type_id {
+            return None;
+        }
+        self.caster().map(move |caster| (caster.cast_mut)(any))
+    }
+
+    /// Casts a `Box` of a trait object for `S` into that of type `T` if its concrete type
+    /// is the one this handle was resolved for. If fails, returns `source`.
+    pub fn cast_box<S: ?Sized + CastFrom>(&self, source: Box<S>) -> Result<Box<T>, Box<S>> {
+        if (*source).ref_any().type_id() != self.type_id {
+            return Err(source);
+        }
+        match self.caster() {
+            Some(caster) => Ok((caster.cast_box)(source.box_any())),
+            None => Err(source),
+        }
+    }
+}
+
+/// Resolves a [`CasterHandle`] casting values of the same concrete type as `source` into
+/// type `T`, which casts them without looking up the registry again.
+///
+/// Returns `None` if no caster into `T` is registered for the concrete type, including
+/// when `T` is the concrete type itself or a cast into `T` goes through `Deref`.
+///
+/// # Examples
+/// ```
+/// # use intertrait::*;
+/// use intertrait::cast::*;
+///
+/// # #[cast_to(Greet)]
+/// # struct Data;
+/// # trait Source: CastFrom {}
+/// # trait Greet {
+/// #     fn greet(&self);
+/// # }
+/// # impl Greet for Data {
+/// #    fn greet(&self) {
+/// #        println!("Hello");
+/// #    }
+/// # }
+/// impl Source for Data {}
+/// let sources: Vec<Box<dyn Source>> = (0..100).map(|_| Box::new(Data) as _).collect();
+/// let handle = caster_of::<dyn Greet, _>(&*sources[0]).unwrap();
+/// for source in sources {
+///     handle.cast_box(source).ok().unwrap().greet();
+/// }
+/// ```
+///
+/// [`CasterHandle`]: ./struct.CasterHandle.html
+pub fn caster_of<T: ?Sized + 'static, S: ?Sized + CastFrom>(source: &S) -> Option<CasterHandle<T>> {
+    let type_id = source.ref_any().type_id();
+    let generation = generation();
+    caster::<T>(type_id).map(|caster| CasterHandle::new(type_id, caster, generation))
+}
+
+impl<T: ?Sized + 'static> Clone for CasterHandle<T> {
+    fn clone(&self) -> Self {
+        *self
+    }
+}
+
+impl<T: ?Sized + 'static> Copy for CasterHandle<T> {}
@@ -0,0 +1,67 @@
+/// A trait implemented by `closed_cast!` for an enum of a closed set of types and a trait `T`
+/// implemented by all of them, which casts the enum into `T` with a `match`.
+///
+/// Use [`ClosedCast`] instead of calling the methods of this trait directly.
+///
+/// [`ClosedCast`]: ./trait.ClosedCast.html
+pub trait ClosedTarget<T: ?Sized> {
+    /// Casts a reference to this enum into that of type `T`.
+    fn target_ref(&self) -> Option<&T>;
+
+    /// Casts a mutable reference to this enum into that of type `T`.
+    fn target_mut(&mut self) -> Option<&mut T>;
+}
+
+/// A trait that is blanket-implemented to allow for casting of an enum generated by
+/// `closed_cast!` into the traits listed in it.
+///
+/// Unlike the traits for casting trait objects, the cast doesn't involve `Any` or the global
+/// registry, but is resolved with a `match` over the variants of the enum.
+///
+/// # Examples
+/// ```
+/// use intertrait::*;
+/// use intertrait::cast::*;
+///
+/// # trait Draw {
+/// #     fn draw(&self) -> String;
+/// # }
+/// struct Circle;
+/// struct Square;
+/// # impl Draw for Circle {
+/// #     fn draw(&self) -> String {
+/// #         "circle".to_owned()
+/// #     }
+/// # }
+/// # impl Draw for Square {
+/// #     fn draw(&self) -> String {
+/// #         "square".to_owned()
+/// #     }
+/// # }
+///
+/// closed_cast! {
+///     enum Node: Circle, Square => dyn Draw
+/// }
+///
+/// let node = Node::from(Square);
+/// assert_eq!(node.closed_cast::<dyn Draw>().unwrap().draw(), "square");
+/// ```
+pub trait ClosedCast {
+    /// Casts a reference to this enum into that of type `T`.
+    fn closed_cast<T: ?Sized>(&self) -> Option<&T>
+    where
+        Self: ClosedTarget<T>,
+    {
+        self.target_ref()
+    }
+
+    /// Casts a mutable reference to this enum into that of type `T`.
+    fn closed_cast_mut<T: ?Sized>(&mut self) -> Option<&mut T>
+    where
+        Self: ClosedTarget<T>,
+    {
+        self.target_mut()
+    }
+}
+
+impl<S: ?Sized> ClosedCast for S {}
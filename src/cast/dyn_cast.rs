@@ -0,0 +1,125 @@
+use std::any::{Any, TypeId};
+
+use crate::cast::{CastMut, CastRef};
+use crate::{impls_key, CastFrom, CastKey};
+
+/// An object-safe companion of the `cast` traits, which lets a framework store values as
+/// `Box<dyn DynCast>` and query them with `TypeId`s of the target traits only known at runtime.
+///
+/// It's implemented for all the `Sized` types implementing `CastFrom`. Since `dyn DynCast`
+/// extends `CastFrom`, the methods of the `cast` traits are available on it as well.
+///
+/// As a `Box` or a reference of `dyn DynCast` implements it too, dereference it down to
+/// `dyn DynCast` before calling the methods, e.g. `(**component).impls_type_id(id)`.
+///
+/// # Examples
+/// ```
+/// use std::any::TypeId;
+/// # use intertrait::*;
+/// use intertrait::cast::*;
+///
+/// # #[cast_to(Greet)]
+/// # struct Data;
+/// # trait Greet {
+/// #     fn greet(&self) -> &'static str;
+/// # }
+/// # impl Greet for Data {
+/// #    fn greet(&self) -> &'static str {
+/// #        "hello"
+/// #    }
+/// # }
+/// let components: Vec<Box<dyn DynCast>> = vec![Box::new(Data), Box::new(1u8)];
+/// let wanted = TypeId::of::<dyn Greet>();
+/// let found: Vec<_> = components
+///     .iter()
+///     .filter_map(|component| (**component).cast_erased(wanted))
+///     .collect();
+/// assert_eq!(found.len(), 1);
+/// assert_eq!(found[0].downcast::<dyn Greet>().unwrap().greet(), "hello");
+/// ```
+pub trait DynCast: CastFrom {
+    /// Tests if this can be cast into the type of `trait_id`.
+    fn impls_type_id(&self, trait_id: TypeId) -> bool;
+
+    /// Casts a reference to this into that of the type of `trait_id` with its type erased,
+    /// which is recovered later with [`ErasedRef::downcast`].
+    ///
+    /// [`ErasedRef::downcast`]: ./struct.ErasedRef.html#method.downcast
+    fn cast_erased(&self, trait_id: TypeId) -> Option<ErasedRef<'_>>;
+
+    /// Casts a mutable reference to this into that of the type of `trait_id` with its type
+    /// erased, which is recovered later with [`ErasedMut::downcast`].
+    ///
+    /// [`ErasedMut::downcast`]: ./struct.ErasedMut.html#method.downcast
+    fn cast_erased_mut(&mut self, trait_id: TypeId) -> Option<ErasedMut<'_>>;
+}
+
+impl<S: CastFrom> DynCast for S {
+    fn impls_type_id(&self, trait_id: TypeId) -> bool {
+        impls_key(CastKey::erased(TypeId::of::<S>(), trait_id))
+    }
+
+    fn cast_erased(&self, trait_id: TypeId) -> Option<ErasedRef<'_>> {
+        if !self.impls_type_id(trait_id) {
+            return None;
+        }
+        Some(ErasedRef {
+            any: self,
+            trait_id,
+        })
+    }
+
+    fn cast_erased_mut(&mut self, trait_id: TypeId) -> Option<ErasedMut<'_>> {
+        if !(*self).impls_type_id(trait_id) {
+            return None;
+        }
+        Some(ErasedMut {
+            any: self,
+            trait_id,
+        })
+    }
+}
+
+/// A reference known to be castable into the type of `TypeId` it carries.
+#[derive(Clone, Copy)]
+pub struct ErasedRef<'a> {
+    any: &'a dyn Any,
+    trait_id: TypeId,
+}
+
+impl<'a> ErasedRef<'a> {
+    /// Returns `TypeId` of the type the reference is cast into.
+    pub fn trait_id(&self) -> TypeId {
+        self.trait_id
+    }
+
+    /// Recovers the reference to `T`, which must be the type the reference is cast into.
+    pub fn downcast<T: ?Sized + 'static>(self) -> Option<&'a T> {
+        if TypeId::of::<T>() != self.trait_id {
+            return None;
+        }
+        self.any.cast::<T>()
+    }
+}
+
+/// A mutable reference known to be castable into the type of `TypeId` it carries.
+pub struct ErasedMut<'a> {
+    any: &'a mut dyn Any,
+    trait_id: TypeId,
+}
+
+impl<'a> ErasedMut<'a> {
+    /// Returns `TypeId` of the type the reference is cast into.
+    pub fn trait_id(&self) -> TypeId {
+        self.trait_id
+    }
+
+    /// Recovers the mutable reference to `T`, which must be the type the reference is
+    /// cast into. If fails, returns the erased reference as is.
+    pub fn downcast<T: ?Sized + 'static>(self) -> Result<&'a mut T, Self> {
+        if TypeId::of::<T>() != self.trait_id || !(*self.any).impls::<T>() {
+            return Err(self);
+        }
+        Ok(self.any.cast::<T>().unwrap())
+    }
+}
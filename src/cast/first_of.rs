@@ -0,0 +1,58 @@
+use std::any::Any;
+
+use crate::cast::CastRef;
+
+/// A tuple of references to the types, e.g. `(&dyn A, &dyn B)`, which [`CastRef::first_of`]
+/// tries casting into in order. It's implemented for tuples of 2 to 4 elements.
+///
+/// [`CastRef::first_of`]: ./trait.CastRef.html#tymethod.first_of
+pub trait FirstOf<'a> {
+    /// Which of the types the cast succeeded into along with the reference to it,
+    /// which is one of [`OneOf2`], [`OneOf3`] and [`OneOf4`].
+    ///
+    /// [`OneOf2`]: ./enum.OneOf2.html
+    /// [`OneOf3`]: ./enum.OneOf3.html
+    /// [`OneOf4`]: ./enum.OneOf4.html
+    type Output;
+
+    /// Casts `any` into the first of the types it can be cast into.
+    fn first_of(any: &'a dyn Any) -> Option<Self::Output>;
+}
+
+macro_rules! one_of {
+    ($(#[$attr:meta])* $OneOf:ident { $($Variant:ident($T:ident)),+ }) => {
+        $(#[$attr])*
+        #[derive(Clone, Copy, Debug, PartialEq, Eq)]
+        pub enum $OneOf<$($T),+> {
+            $($Variant($T)),+
+        }
+
+        impl<'a, 'r, $($T: ?Sized + 'static),+> FirstOf<'a> for ($(&'r $T,)+) {
+            type Output = $OneOf<$(&'a $T),+>;
+
+            fn first_of(any: &'a dyn Any) -> Option<Self::Output> {
+                $(
+                    if let Some(target) = any.cast::<$T>() {
+                        return Some($OneOf::$Variant(target));
+                    }
+                )+
+                None
+            }
+        }
+    };
+}
+
+one_of! {
+    /// The first of two types a cast succeeded into.
+    OneOf2 { First(A), Second(B) }
+}
+
+one_of! {
+    /// The first of three types a cast succeeded into.
+    OneOf3 { First(A), Second(B), Third(C) }
+}
+
+one_of! {
+    /// The first of four types a cast succeeded into.
+    OneOf4 { First(A), Second(B), Third(C), Fourth(D) }
+}
@@ -0,0 +1,92 @@
+//! Casting through the `Deref` relationships registered with `#[cast_to(deref_to = Target)]`.
+//!
+//! When no caster is registered for a concrete type and a trait, and the type is registered
+//! to dereference to a target, the cast is retried with the casters of the target on
+//! the dereferenced value. This repeats along a chain of registered relationships up to
+//! [`MAX_DEPTH`] steps. Only references can be cast this way, since a `Box`, `Rc` or `Arc`
+//! of a type can't be turned into one of its target.
+use std::any::{Any, TypeId};
+
+#[cfg(not(feature = "disabled"))]
+use crate::registry::Map;
+use crate::{caster, contains_caster, CastKey, DerefTo};
+#[cfg(not(feature = "disabled"))]
+use crate::{Lazy, DEREFS};
+
+/// The maximum number of `Deref` relationships followed for a cast, which also stops
+/// a cycle of relationships registered by mistake.
+const MAX_DEPTH: usize = 8;
+
+#[cfg(not(feature = "disabled"))]
+static DEREF_MAP: Lazy<Map<TypeId, DerefTo>> =
+    Lazy::new(|| DEREFS.iter().map(|f| f()).collect::<Map<TypeId, DerefTo>>());
+
+/// Builds the map of the `Deref` relationships eagerly.
+#[cfg(not(feature = "disabled"))]
+pub(crate) fn init() {
+    Lazy::force(&DEREF_MAP);
+}
+
+/// Returns the `Deref` relationship registered for a type of `type_id`.
+#[cfg(not(feature = "disabled"))]
+fn deref_to(type_id: TypeId) -> Option<DerefTo> {
+    DEREF_MAP.get(&type_id).copied()
+}
+
+/// Returns no `Deref` relationship as casting is disabled.
+#[cfg(feature = "disabled")]
+fn deref_to(_: TypeId) -> Option<DerefTo> {
+    None
+}
+
+/// Dereferences `any` along the registered relationships to the first value having
+/// a caster for the cast of `key`, whose concrete type is disregarded.
+fn deref_ref(mut any: &dyn Any, key: CastKey) -> Option<&dyn Any> {
+    for _ in 0..MAX_DEPTH {
+        any = (deref_to(any.type_id())?.deref_ref)(any);
+        if contains_caster(key.with_type_id(any.type_id())) {
+            return Some(any);
+        }
+    }
+    None
+}
+
+/// Mutably dereferences `any` along the registered relationships to the first value having
+/// a caster for the cast of `key`, whose concrete type is disregarded.
+fn deref_mut(mut any: &mut dyn Any, key: CastKey) -> Option<&mut dyn Any> {
+    for _ in 0..MAX_DEPTH {
+        any = deref_to((*any).type_id())?.deref_mut?(any);
+        if contains_caster(key.with_type_id((*any).type_id())) {
+            return Some(any);
+        }
+    }
+    None
+}
+
+/// Casts `any` into `T` with the casters of the types it dereferences to.
+pub fn cast_ref<T: ?Sized + 'static>(any: &dyn Any) -> Option<&T> {
+    let target = deref_ref(any, CastKey::new::<T>(any.type_id()))?;
+    caster::<T>(target.type_id()).map(|caster| (caster.cast_ref)(target))
+}
+
+/// Casts `any` into `T` with the casters of the types it mutably dereferences to.
+pub fn cast_mut<T: ?Sized + 'static>(any: &mut dyn Any) -> Option<&mut T> {
+    let key = CastKey::new::<T>((*any).type_id());
+    let target = deref_mut(any, key)?;
+    caster::<T>((*target).type_id()).map(move |caster| (caster.cast_mut)(target))
+}
+
+/// Tests if the cast of `key` is possible through the types it dereferences to.
+pub fn contains(key: CastKey) -> bool {
+    let mut type_id = key.type_id;
+    for _ in 0..MAX_DEPTH {
+        match deref_to(type_id) {
+            Some(deref) => type_id = deref.target,
+            None => return false,
+        }
+        if contains_caster(key.with_type_id(type_id)) {
+            return true;
+        }
+    }
+    false
+}
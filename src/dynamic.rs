@@ -0,0 +1,519 @@
+//! `dynamic` module allows for extending the registry of casters at runtime,
+//! which is enabled with `dynamic` feature.
+//!
+//! Casters registered with the macros of this crate are gathered into a distributed slice
+//! at link time. But a dynamically loaded library (e.g. a plugin) has its own copy of the slice,
+//! which the host can't see at compile time. The plugin can expose its slice through
+//! an exported function, and the host can merge it with [`merge_slice`] after loading it.
+//!
+//! ```ignore
+//! // In the plugin
+//! #[no_mangle]
+//! pub fn casters() -> &'static [CasterEntry] {
+//!     &intertrait::CASTERS
+//! }
+//!
+//! // In the host, after loading the plugin
+//! intertrait::dynamic::merge_slice(casters());
+//! ```
+//!
+//! Use [`try_merge_slice`] instead to refuse a slice registering the casts already merged,
+//! or [`merge_slice_revocable`] to unregister the casts again before unloading the library.
+//! With `plugin` feature, [`plugin`] module does both sides of this with a well-known name.
+//!
+//! Each entry of the slice is tagged with the version of its format, and a slice containing
+//! an entry built against an incompatible version of this crate is refused as a whole rather
+//! than being cast through. See [`CasterEntry`] for what is guaranteed across versions.
+//!
+//! Casters can also be registered one by one with [`register_caster`]. This is useful
+//! when a cast should be allowed only if a condition known at runtime holds (e.g. a license
+//! check). In that case, omit `#[cast_to]` for the trait, which always registers the cast
+//! at link time, and register it conditionally at startup instead:
+//!
+//! ```
+//! # use intertrait::*;
+//! # use intertrait::cast::*;
+//! use intertrait::dynamic::{register_caster, register_if};
+//!
+//! # trait Source: CastFrom {}
+//! # trait Greet {
+//! #     fn greet(&self);
+//! # }
+//! struct Data;
+//! impl Source for Data {}
+//! // No #[cast_to] here
+//! impl Greet for Data {
+//!     fn greet(&self) {
+//!         println!("Hello");
+//!     }
+//! }
+//!
+//! # let licensed = true;
+//! register_if(licensed, || {
+//!     register_caster::<Data, dyn Greet>(Caster::new(
+//!         |from| from.downcast_ref::<Data>().unwrap(),
+//!         |from| from.downcast_mut::<Data>().unwrap(),
+//!         |from| from.downcast::<Data>().unwrap(),
+//!         |from| from.downcast::<Data>().unwrap(),
+//!     ))
+//! });
+//!
+//! let source: &dyn Source = &Data;
+//! assert_eq!(source.impls::<dyn Greet>(), licensed);
+//! ```
+//!
+//! Casters registered at link time always take precedence over those registered at runtime.
+//! Those registered at runtime can be removed again with [`unregister_caster`].
+//!
+//! As the last resort, a resolver installed with [`set_cast_fallback`] is consulted
+//! when no caster is registered for a cast.
+//!
+//! On the other hand, casts can be forbidden regardless of registration with
+//! [`set_cast_denylist`].
+//!
+//! [`merge_slice`]: ./fn.merge_slice.html
+//! [`try_merge_slice`]: ./fn.try_merge_slice.html
+//! [`merge_slice_revocable`]: ./fn.merge_slice_revocable.html
+//! [`plugin`]: ../plugin/index.html
+//! [`CasterEntry`]: ../struct.CasterEntry.html
+//! [`register_caster`]: ./fn.register_caster.html
+//! [`unregister_caster`]: ./fn.unregister_caster.html
+//! [`set_cast_fallback`]: ./fn.set_cast_fallback.html
+//! [`set_cast_denylist`]: ./fn.set_cast_denylist.html
+use std::any::{Any, TypeId};
+use std::collections::HashSet;
+use std::error::Error;
+use std::fmt;
+use std::sync::atomic::{AtomicBool, AtomicUsize, Ordering};
+use std::sync::RwLock;
+
+use crate::registry::{ConflictPolicy, Registry, StableKey, VersionMismatch};
+use crate::{CastKey, Caster, CasterEntry, Lazy};
+
+/// A `Registry` holding the casters registered at runtime.
+static DYNAMIC_CASTER_MAP: Lazy<RwLock<Registry>> = Lazy::new(Default::default);
+
+/// A counter incremented whenever the casts allowed at runtime change, which invalidates
+/// the results cached before.
+static GENERATION: AtomicUsize = AtomicUsize::new(0);
+
+#[cfg_attr(not(feature = "impls-cache"), allow(dead_code))]
+pub(crate) fn generation() -> usize {
+    GENERATION.load(Ordering::Acquire)
+}
+
+fn bump_generation() {
+    GENERATION.fetch_add(1, Ordering::AcqRel);
+}
+
+/// Merges the casters referred to by the entries in `slice` into the registry.
+///
+/// The `slice` is usually a copy of [`CASTERS`] in a dynamically loaded library.
+/// If a caster for the same pair of a concrete type and a trait has already been merged,
+/// it is replaced with the new one.
+///
+/// # Panics
+/// Panics if any of the entries is of another version than [`CASTER_ABI_VERSION`],
+/// in which case nothing is merged.
+///
+/// [`CASTERS`]: ../static.CASTERS.html
+/// [`CASTER_ABI_VERSION`]: ../constant.CASTER_ABI_VERSION.html
+pub fn merge_slice(slice: &[CasterEntry]) {
+    if let Err(err) = merge(slice, ConflictPolicy::Override) {
+        panic!("{}", err);
+    }
+}
+
+/// Merges the casters referred to by the entries in `slice` into the registry, only if none of
+/// the casts has already been merged. Otherwise, merges nothing and returns the keys of
+/// the casts merged already.
+///
+/// Nothing is merged either if any of the entries is of another version than
+/// [`CASTER_ABI_VERSION`].
+///
+/// It is meant for assembling the registry from several libraries (e.g. plugins) which
+/// aren't supposed to register the same casts.
+///
+/// # Examples
+/// ```ignore
+/// match try_merge_slice(plugin.casters()) {
+///     Ok(()) => {}
+///     Err(MergeError::Conflicts(conflicts)) => {
+///         for key in conflicts {
+///             eprintln!("{} => {} is already registered", key.type_name(), key.trait_name());
+///         }
+///     }
+///     Err(err) => eprintln!("{}", err),
+/// }
+/// ```
+///
+/// [`CASTER_ABI_VERSION`]: ../constant.CASTER_ABI_VERSION.html
+pub fn try_merge_slice(slice: &[CasterEntry]) -> Result<(), MergeError> {
+    merge(slice, ConflictPolicy::Error).map(|_| ())
+}
+
+/// Merges the casters referred to by the entries in `slice` into the registry as
+/// [`try_merge_slice`] does, returning a [`RegistrationToken`] owning them.
+///
+/// Dropping or revoking the token unregisters the casters, after which the library
+/// providing them (e.g. a plugin) can be unloaded and loaded again.
+///
+/// [`try_merge_slice`]: ./fn.try_merge_slice.html
+/// [`RegistrationToken`]: ./struct.RegistrationToken.html
+pub fn merge_slice_revocable(slice: &[CasterEntry]) -> Result<RegistrationToken, MergeError> {
+    let keys = merge(slice, ConflictPolicy::Error)?;
+    Ok(RegistrationToken { keys })
+}
+
+/// A token owning the casters merged with [`merge_slice_revocable`], which unregisters them
+/// when dropped or revoked.
+///
+/// The casters for the casts merged with the token are unregistered, even if they have been
+/// replaced since. Call [`keep`] to keep the casters registered for good instead.
+///
+/// [`merge_slice_revocable`]: ./fn.merge_slice_revocable.html
+/// [`keep`]: #method.keep
+#[must_use = "dropping the token unregisters the casters merged with it"]
+#[derive(Debug)]
+pub struct RegistrationToken {
+    keys: Vec<(TypeId, TypeId)>,
+}
+
+impl RegistrationToken {
+    /// Unregisters the casters merged with the token, which is the same as dropping it.
+    pub fn revoke(self) {}
+
+    /// Consumes the token, keeping the casters registered for good.
+    pub fn keep(mut self) {
+        self.keys.clear();
+    }
+}
+
+impl Drop for RegistrationToken {
+    fn drop(&mut self) {
+        if self.keys.is_empty() {
+            return;
+        }
+        let mut registry = DYNAMIC_CASTER_MAP.write().unwrap();
+        for key in &self.keys {
+            registry.remove(key);
+        }
+        bump_generation();
+    }
+}
+
+/// The reason why [`try_merge_slice`] merged nothing.
+///
+/// [`try_merge_slice`]: ./fn.try_merge_slice.html
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum MergeError {
+    /// Some of the casts in the slice have already been merged.
+    Conflicts(Vec<StableKey>),
+
+    /// An entry in the slice is built against an incompatible version of this crate.
+    VersionMismatch(VersionMismatch),
+}
+
+impl fmt::Display for MergeError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            MergeError::Conflicts(keys) => {
+                write!(f, "{} of the casts are already registered", keys.len())?;
+                for (i, key) in keys.iter().enumerate() {
+                    let sep = if i == 0 { ": " } else { ", " };
+                    write!(f, "{}{} => {}", sep, key.type_name(), key.trait_name())?;
+                }
+                Ok(())
+            }
+            MergeError::VersionMismatch(err) => err.fmt(f),
+        }
+    }
+}
+
+impl Error for MergeError {
+    fn source(&self) -> Option<&(dyn Error + 'static)> {
+        match self {
+            MergeError::Conflicts(_) => None,
+            MergeError::VersionMismatch(err) => Some(err),
+        }
+    }
+}
+
+/// Merges the casters in `slice`, returning the pairs of `TypeId`s of the casts merged.
+fn merge(
+    slice: &[CasterEntry],
+    policy: ConflictPolicy,
+) -> Result<Vec<(TypeId, TypeId)>, MergeError> {
+    let other = Registry::from_slice(slice).map_err(MergeError::VersionMismatch)?;
+    DYNAMIC_CASTER_MAP
+        .write()
+        .unwrap()
+        .extend_from(&other, policy)
+        .map_err(MergeError::Conflicts)?;
+    bump_generation();
+    Ok(other.entries().map(|(id, _)| id).collect())
+}
+
+/// Registers `caster` for casting from a concrete type `S` to a trait `T`.
+///
+/// If a caster for the same pair of `S` and `T` has already been registered at runtime,
+/// it is replaced with the new one.
+pub fn register_caster<S: 'static, T: ?Sized + 'static>(caster: Caster<T>) {
+    let caster = Caster {
+        type_name: std::any::type_name::<S>(),
+        ..caster
+    };
+    DYNAMIC_CASTER_MAP
+        .write()
+        .unwrap()
+        .insert(TypeId::of::<S>(), Box::new(caster));
+    bump_generation();
+}
+
+/// Unregisters the caster registered at runtime for casting from a concrete type `S` to
+/// a trait `T`, returning whether there was one.
+///
+/// The casters registered at link time, which seed the registry, can't be unregistered.
+/// Forbid such a cast with [`set_cast_denylist`] instead.
+///
+/// [`set_cast_denylist`]: ./fn.set_cast_denylist.html
+pub fn unregister_caster<S: 'static, T: ?Sized + 'static>() -> bool {
+    let removed = DYNAMIC_CASTER_MAP
+        .write()
+        .unwrap()
+        .remove(&(TypeId::of::<S>(), TypeId::of::<T>()));
+    if removed {
+        bump_generation();
+    }
+    removed
+}
+
+/// Registers the casters from a concrete type into traits at runtime, which is a shorthand
+/// for [`register_caster`] building the casters as `#[cast_to]` does.
+///
+/// - `register!(Type => dyn Trait1, dyn Trait2)` registers the casts into the traits.
+/// - `register!(Type => [sync] dyn Trait1, dyn Trait2)` registers the casts for `Arc` as well,
+///   which requires `Type` to be `Sync + Send`.
+///
+/// It's a macro rather than a generic function, since no coercion from `Type` into `dyn Trait`
+/// can be written generically over the trait on stable Rust.
+///
+/// # Examples
+/// ```
+/// # use intertrait::*;
+/// # use intertrait::cast::*;
+/// # trait Source: CastFrom {}
+/// # trait Greet {
+/// #     fn greet(&self);
+/// # }
+/// struct Data;
+/// impl Source for Data {}
+/// impl Greet for Data {
+///     fn greet(&self) {
+///         println!("Hello");
+///     }
+/// }
+///
+/// register!(Data => dyn Greet);
+///
+/// let source: &dyn Source = &Data;
+/// source.cast::<dyn Greet>().unwrap().greet();
+/// ```
+///
+/// [`register_caster`]: ./dynamic/fn.register_caster.html
+#[macro_export]
+macro_rules! register {
+    ($ty:ty => [sync] $($target:ty),+ $(,)?) => {
+        $(
+            $crate::dynamic::register_caster::<$ty, $target>($crate::Caster::new_sync(
+                |from| from.downcast_ref::<$ty>().unwrap(),
+                |from| from.downcast_mut::<$ty>().unwrap(),
+                |from| from.downcast::<$ty>().unwrap(),
+                |from| from.downcast::<$ty>().unwrap(),
+                |from| from.downcast::<$ty>().unwrap(),
+            ));
+        )+
+    };
+    ($ty:ty => $($target:ty),+ $(,)?) => {
+        $(
+            $crate::dynamic::register_caster::<$ty, $target>($crate::Caster::new(
+                |from| from.downcast_ref::<$ty>().unwrap(),
+                |from| from.downcast_mut::<$ty>().unwrap(),
+                |from| from.downcast::<$ty>().unwrap(),
+                |from| from.downcast::<$ty>().unwrap(),
+            ));
+        )+
+    };
+}
+
+/// Calls `register` only if `condition` holds, which is meant for registering casters
+/// depending on a runtime check.
+pub fn register_if<F: FnOnce()>(condition: bool, register: F) {
+    if condition {
+        register()
+    }
+}
+
+pub use crate::owned::CastFallback;
+
+static CAST_FALLBACK: Lazy<RwLock<Option<CastFallback>>> = Lazy::new(Default::default);
+
+/// Installs `fallback` to be consulted when both of the registries built at link time
+/// and runtime have no caster for a cast. It replaces the one previously installed.
+///
+/// # Examples
+/// ```
+/// # use std::any::{Any, TypeId};
+/// # use intertrait::*;
+/// # use intertrait::cast::*;
+/// # use once_cell::sync::Lazy;
+/// use intertrait::dynamic::set_cast_fallback;
+///
+/// # trait Source: CastFrom {}
+/// # trait Greet {
+/// #     fn greet(&self);
+/// # }
+/// struct Data;
+/// impl Source for Data {}
+/// impl Greet for Data {
+///     fn greet(&self) {
+///         println!("Hello");
+///     }
+/// }
+///
+/// static GREET: Lazy<Caster<dyn Greet>> = Lazy::new(|| {
+///     Caster::new(
+///         |from| from.downcast_ref::<Data>().unwrap(),
+///         |from| from.downcast_mut::<Data>().unwrap(),
+///         |from| from.downcast::<Data>().unwrap(),
+///         |from| from.downcast::<Data>().unwrap(),
+///     )
+/// });
+///
+/// fn fallback(type_id: TypeId, trait_id: TypeId) -> Option<&'static (dyn Any + Send + Sync)> {
+///     if type_id == TypeId::of::<Data>() && trait_id == TypeId::of::<dyn Greet>() {
+///         Some(&*GREET)
+///     } else {
+///         None
+///     }
+/// }
+///
+/// set_cast_fallback(fallback);
+/// let source: &dyn Source = &Data;
+/// source.cast::<dyn Greet>().unwrap().greet();
+/// ```
+pub fn set_cast_fallback(fallback: CastFallback) {
+    *CAST_FALLBACK.write().unwrap() = Some(fallback);
+    bump_generation();
+}
+
+/// Uninstalls the fallback installed with [`set_cast_fallback`] if any.
+///
+/// [`set_cast_fallback`]: ./fn.set_cast_fallback.html
+pub fn clear_cast_fallback() {
+    *CAST_FALLBACK.write().unwrap() = None;
+    bump_generation();
+}
+
+static CAST_DENYLIST: Lazy<RwLock<HashSet<(TypeId, TypeId)>>> = Lazy::new(Default::default);
+
+/// Whether `CAST_DENYLIST` is non-empty, which saves locking it for every cast.
+static HAS_CAST_DENYLIST: AtomicBool = AtomicBool::new(false);
+
+/// Forbids the casts given as pairs of `TypeId`s of a concrete type and a target trait object,
+/// replacing the ones previously forbidden. An empty `pairs` allows for all the casts again.
+///
+/// A forbidden cast fails and isn't reported by `impls` or [`impls_of`], even if a caster
+/// is registered for it. Note that this is a policy layer rather than a security boundary:
+/// the value is still accessible through `Any` and its concrete type, and through the casts
+/// into other traits.
+///
+/// # Examples
+/// ```
+/// # use std::any::TypeId;
+/// # use intertrait::*;
+/// # use intertrait::cast::*;
+/// use intertrait::dynamic::set_cast_denylist;
+///
+/// # trait Source: CastFrom {}
+/// # trait FileSystemAccess {}
+/// #[cast_to(FileSystemAccess)]
+/// struct Plugin;
+/// # impl FileSystemAccess for Plugin {}
+/// impl Source for Plugin {}
+///
+/// set_cast_denylist(&[(TypeId::of::<Plugin>(), TypeId::of::<dyn FileSystemAccess>())]);
+/// let source: &dyn Source = &Plugin;
+/// assert!(source.cast::<dyn FileSystemAccess>().is_none());
+/// ```
+///
+/// [`impls_of`]: ../fn.impls_of.html
+pub fn set_cast_denylist(pairs: &[(TypeId, TypeId)]) {
+    let mut denylist = CAST_DENYLIST.write().unwrap();
+    *denylist = pairs.iter().copied().collect();
+    HAS_CAST_DENYLIST.store(!denylist.is_empty(), Ordering::Release);
+    bump_generation();
+}
+
+/// Tests if the cast for a pair of `TypeId`s of a concrete type and a trait is forbidden.
+pub(crate) fn is_denied(key: &(TypeId, TypeId)) -> bool {
+    HAS_CAST_DENYLIST.load(Ordering::Acquire) && CAST_DENYLIST.read().unwrap().contains(key)
+}
+
+/// Returns the caster for a pair of `TypeId`s of a concrete type and a trait resolved by
+/// the fallback, which is to be downcast into `Caster<T>` by the caller.
+///
+/// It is kept apart from the generic callers to be compiled once rather than for every trait.
+fn fallback(key: &(TypeId, TypeId)) -> Option<&'static (dyn Any + Send + Sync)> {
+    let fallback = (*CAST_FALLBACK.read().unwrap())?;
+    fallback(key.0, key.1)
+}
+
+pub(crate) fn caster<T: ?Sized + 'static>(type_id: TypeId) -> Option<Caster<T>> {
+    // The lock is released before calling the fallback, which may register a caster.
+    let caster = DYNAMIC_CASTER_MAP.read().unwrap().caster::<T>(type_id);
+    caster.or_else(|| {
+        fallback(&(type_id, TypeId::of::<T>()))
+            .and_then(|caster| caster.downcast_ref::<Caster<T>>())
+            .copied()
+    })
+}
+
+/// Returns the caster merged from a slice of `CasterEntry`, which is usable for casting
+/// a pinned value unlike one registered with `register_caster` or resolved by the fallback.
+pub(crate) fn pin_caster<T: ?Sized + 'static>(type_id: TypeId) -> Option<Caster<T>> {
+    DYNAMIC_CASTER_MAP.read().unwrap().pin_caster::<T>(type_id)
+}
+
+/// Tests if there is a caster for the cast of `key`, where one resolved by the fallback
+/// must really be a `Caster<T>` if `key` knows `T`.
+pub(crate) fn contains_caster(key: CastKey) -> bool {
+    let pair = key.pair();
+    // The lock is released before calling the fallback, which may register a caster.
+    let contains = DYNAMIC_CASTER_MAP.read().unwrap().contains(&pair);
+    contains
+        || fallback(&pair).is_some_and(|caster| {
+            key.caster_id
+                .map_or(true, |caster_id| (*caster).type_id() == caster_id)
+        })
+}
+
+pub(crate) fn sources(trait_id: TypeId) -> Vec<TypeId> {
+    DYNAMIC_CASTER_MAP
+        .read()
+        .unwrap()
+        .sources(trait_id)
+        .to_vec()
+}
+
+pub(crate) fn traits(type_id: TypeId) -> Vec<(TypeId, &'static str)> {
+    DYNAMIC_CASTER_MAP.read().unwrap().traits(type_id).collect()
+}
+
+pub(crate) fn stable_keys() -> Vec<StableKey> {
+    DYNAMIC_CASTER_MAP.read().unwrap().stable_keys().collect()
+}
+
+pub(crate) fn entries() -> Vec<((TypeId, TypeId), StableKey)> {
+    DYNAMIC_CASTER_MAP.read().unwrap().entries().collect()
+}
@@ -2,14 +2,20 @@ use std::convert::TryInto;
 use std::hash::{BuildHasherDefault, Hasher};
 use std::mem::size_of;
 
-/// A simple `Hasher` implementation tuned for performance.
+/// A `Hasher` for keys made of `TypeId`s, which takes the bits of each `TypeId` as they are.
+///
+/// A `TypeId` is already a hash of the type of high quality, so hashing it again only costs
+/// time. The ids of a composite key are folded into one with a rotation in between, which
+/// keeps `(a, b)` and `(b, a)` apart. Any other data is folded into 64-bit words.
+#[cfg_attr(feature = "btree", allow(dead_code))]
 #[derive(Default)]
-pub struct FastHasher(u64);
+pub struct TypeIdHasher(u64);
 
-/// A `BuildHasher` for `FastHasher`.
-pub type BuildFastHasher = BuildHasherDefault<FastHasher>;
+/// A `BuildHasher` for `TypeIdHasher`.
+#[cfg_attr(feature = "btree", allow(dead_code))]
+pub type BuildTypeIdHasher = BuildHasherDefault<TypeIdHasher>;
 
-impl Hasher for FastHasher {
+impl Hasher for TypeIdHasher {
     fn finish(&self) -> u64 {
         self.0
     }
@@ -18,11 +24,32 @@ impl Hasher for FastHasher {
         let mut bytes = bytes;
         while bytes.len() > size_of::<u64>() {
             let (u64_bytes, remaining) = bytes.split_at(size_of::<u64>());
-            self.0 ^= u64::from_ne_bytes(u64_bytes.try_into().unwrap());
+            self.write_u64(u64::from_ne_bytes(u64_bytes.try_into().unwrap()));
             bytes = remaining
         }
-        self.0 ^= bytes
-            .iter()
-            .fold(0u64, |result, b| (result << 8) | *b as u64);
+        self.write_u64(
+            bytes
+                .iter()
+                .fold(0u64, |result, b| (result << 8) | *b as u64),
+        );
     }
+
+    fn write_u64(&mut self, i: u64) {
+        self.0 = self.0.rotate_left(32) ^ i;
+    }
+
+    fn write_u128(&mut self, i: u128) {
+        self.write_u64(i as u64 ^ (i >> 64) as u64);
+    }
+}
+
+/// Hashes the concatenation of `chunks` with 64-bit FNV-1a, which gives the same value
+/// on every build and run.
+pub fn stable_hash(chunks: &[&[u8]]) -> u64 {
+    chunks
+        .iter()
+        .flat_map(|chunk| chunk.iter())
+        .fold(0xcbf2_9ce4_8422_2325, |hash, b| {
+            (hash ^ u64::from(*b)).wrapping_mul(0x0100_0000_01b3)
+        })
 }
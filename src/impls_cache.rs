@@ -0,0 +1,99 @@
+//! A bounded cache of the results of `impls`, which is enabled with `impls-cache` feature.
+//!
+//! It pays off with `dynamic` feature, where a query for a cast not registered at link time
+//! goes through the locks of the registry and the fallback for the casts allowed at runtime.
+//! Otherwise a query is a single lookup of the registry, which the cache can't beat.
+//!
+//! The cache is shared by all the threads, and split into shards each behind its own lock,
+//! so that concurrent queries for different pairs rarely contend. A full shard evicts the
+//! oldest result to make room for a new one. With `dynamic` feature, a shard is also cleared
+//! when it is found to be filled before the last change of the casts allowed at runtime.
+use std::any::TypeId;
+use std::collections::{HashMap, VecDeque};
+use std::hash::BuildHasher;
+use std::sync::RwLock;
+
+use crate::hasher::BuildTypeIdHasher;
+use crate::Lazy;
+
+const SHARDS: usize = 16;
+const SHARD_CAPACITY: usize = 1024 / SHARDS;
+
+#[derive(Default)]
+struct Shard {
+    generation: usize,
+    results: HashMap<(TypeId, TypeId), bool, BuildTypeIdHasher>,
+    /// The keys of `results` in the order of insertion, the oldest first.
+    order: VecDeque<(TypeId, TypeId)>,
+}
+
+impl Shard {
+    fn insert(&mut self, generation: usize, key: (TypeId, TypeId), result: bool) {
+        if self.generation != generation {
+            self.results.clear();
+            self.order.clear();
+            self.generation = generation;
+        }
+        if self.results.insert(key, result).is_some() {
+            return;
+        }
+        if self.order.len() >= SHARD_CAPACITY {
+            if let Some(oldest) = self.order.pop_front() {
+                self.results.remove(&oldest);
+            }
+        }
+        self.order.push_back(key);
+    }
+}
+
+static CACHE: Lazy<[RwLock<Shard>; SHARDS]> =
+    Lazy::new(|| std::array::from_fn(|_| RwLock::default()));
+
+/// Returns the shard holding the result for `key`.
+fn shard(key: &(TypeId, TypeId)) -> &'static RwLock<Shard> {
+    // The middle bits, which the maps in the shards don't index the buckets with.
+    let hash = BuildTypeIdHasher::default().hash_one(key);
+    &CACHE[(hash >> 32) as usize % SHARDS]
+}
+
+/// Returns the generation of the casts allowed at runtime, which is shared by all the
+/// threads unlike that including the scoped registries of `testing` feature.
+fn generation() -> usize {
+    #[cfg(feature = "dynamic")]
+    let generation = crate::dynamic::generation();
+    #[cfg(not(feature = "dynamic"))]
+    let generation = 0;
+    generation
+}
+
+/// Returns the cached result for a pair of `TypeId`s of a concrete type and a trait,
+/// or computes it with `compute` and caches it.
+pub fn impls<F: FnOnce() -> bool>(key: (TypeId, TypeId), compute: F) -> bool {
+    // The results in the scoped registries of the current thread aren't for the other threads.
+    #[cfg(feature = "testing")]
+    {
+        if crate::testing::in_scope() {
+            return compute();
+        }
+    }
+
+    let shard = shard(&key);
+    let generation = generation();
+    {
+        let shard = shard.read().unwrap();
+        if shard.generation == generation {
+            if let Some(result) = shard.results.get(&key) {
+                return *result;
+            }
+        }
+    }
+
+    // Computed outside of the lock, since it may query the cache through a fallback.
+    let result = compute();
+    let mut shard = shard.write().unwrap();
+    // The result may be stale if the casts have changed during the computation.
+    if generation == self::generation() {
+        shard.insert(generation, key, result);
+    }
+    result
+}
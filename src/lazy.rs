@@ -0,0 +1,31 @@
+use std::ops::Deref;
+use std::sync::OnceLock;
+
+/// A value initialized on the first access, built on `std::sync::OnceLock` in place of
+/// `std::sync::LazyLock`, which is only available from Rust 1.80.
+pub struct Lazy<T> {
+    cell: OnceLock<T>,
+    init: fn() -> T,
+}
+
+impl<T> Lazy<T> {
+    pub const fn new(init: fn() -> T) -> Self {
+        Lazy {
+            cell: OnceLock::new(),
+            init,
+        }
+    }
+
+    /// Initializes the value of `this` if it hasn't been yet, and returns a reference to it.
+    pub fn force(this: &Self) -> &T {
+        this.cell.get_or_init(this.init)
+    }
+}
+
+impl<T> Deref for Lazy<T> {
+    type Target = T;
+
+    fn deref(&self) -> &T {
+        Lazy::force(self)
+    }
+}
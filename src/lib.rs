@@ -47,64 +47,530 @@
 //! use [`CastFromSync`] in place of [`CastFrom`] and add `[sync]` flag before the list
 //! of traits in the macros. Refer to the documents for each of macros for details.
 //!
-//! For casting, refer to traits defined in [`cast`] module.
+//! The casts are registered into a single global registry. A framework keeping a table of
+//! casts per subsystem can declare a [`Registry`] of its own with [`registry!`] and register
+//! casts into it with `registry = NAME` in the macros.
+//!
+//! For casting, refer to traits defined in [`cast`] module. [`prelude`] module re-exports them
+//! along with the macros, and [`cast_ref`], [`cast_mut`] and [`cast_box`] cast without them.
 //!
 //! [cast_to]: ./attr.cast_to.html
 //! [castable_to]: ./macro.castable_to.html
+//! [`Registry`]: ./struct.Registry.html
+//! [`registry!`]: ./macro.registry.html
 //! [`CastFrom`]: ./trait.CastFrom.html
 //! [`CastFromSync`]: ./trait.CastFromSync.html
 //! [`cast`]: ./cast/index.html
+//! [`prelude`]: ./prelude/index.html
+//! [`cast_ref`]: ./fn.cast_ref.html
+//! [`cast_mut`]: ./fn.cast_mut.html
+//! [`cast_box`]: ./fn.cast_box.html
 //! [`Any`]: https://doc.rust-lang.org/std/any/trait.Any.html
 //! [`Arc`]: https://doc.rust-lang.org/std/sync/struct.Arc.html
 use std::any::{Any, TypeId};
-use std::collections::HashMap;
 use std::rc::Rc;
 use std::sync::Arc;
 
+#[cfg(feature = "std-lazy")]
+#[cfg_attr(feature = "disabled", allow(unused_imports))]
+use crate::lazy::Lazy;
+#[cfg(not(feature = "disabled"))]
 use linkme::distributed_slice;
+#[cfg(all(not(feature = "std-lazy"), feature = "once_cell"))]
+#[cfg_attr(feature = "disabled", allow(unused_imports))]
 use once_cell::sync::Lazy;
+#[cfg(all(not(feature = "std-lazy"), feature = "once_cell"))]
+use once_cell::sync::OnceCell as OnceLock;
+#[cfg(feature = "std-lazy")]
+use std::sync::OnceLock;
+
+#[cfg(not(any(feature = "std-lazy", feature = "once_cell")))]
+compile_error!("Either `once_cell` or `std-lazy` feature must be enabled to build the registry");
 
 pub use intertrait_macros::*;
+// Referred to by the output of the macros, which then needs no `linkme` dependency of the user.
+#[doc(hidden)]
+pub use linkme;
 
-use crate::hasher::BuildFastHasher;
+#[cfg(feature = "check-linking")]
+pub use crate::linking::assert_all_linked;
+#[cfg(feature = "check-linking")]
+#[doc(hidden)]
+pub use crate::linking::{LinkMarker, LINK_MARKERS};
+pub use crate::marker::CastMarker;
+#[cfg(not(feature = "disabled"))]
+#[doc(hidden)]
+pub use crate::marker::MARKERS;
+pub use crate::owned::{CastFallback, Registry};
+#[doc(hidden)]
+pub use crate::registry::{CasterEntry, CASTER_ABI_VERSION};
+pub use crate::registry::{StableKey, VersionMismatch};
+pub use crate::validate::{validate_registry, RegistryWarning};
 
 pub mod cast;
+mod deref;
+#[cfg(feature = "dynamic")]
+#[cfg_attr(feature = "disabled", allow(dead_code))]
+pub mod dynamic;
+#[cfg_attr(feature = "disabled", allow(dead_code))]
 mod hasher;
+#[cfg(feature = "impls-cache")]
+mod impls_cache;
+#[cfg(feature = "std-lazy")]
+#[cfg_attr(feature = "disabled", allow(dead_code))]
+mod lazy;
+#[cfg(feature = "check-linking")]
+#[cfg_attr(feature = "disabled", allow(dead_code))]
+mod linking;
+mod marker;
+mod owned;
+#[cfg(feature = "perfect-hash")]
+#[cfg_attr(feature = "disabled", allow(dead_code))]
+mod perfect_hash;
+#[cfg(feature = "plugin")]
+#[cfg_attr(feature = "disabled", allow(dead_code))]
+pub mod plugin;
+pub mod prelude;
+#[cfg_attr(feature = "disabled", allow(dead_code))]
+mod registry;
+#[cfg(feature = "sorted-table")]
+#[cfg_attr(feature = "disabled", allow(dead_code))]
+mod sorted_table;
+#[cfg(feature = "testing")]
+#[cfg_attr(feature = "disabled", allow(dead_code))]
+pub mod testing;
+mod validate;
 
 #[doc(hidden)]
-pub type BoxedCaster = Box<dyn Any + Send + Sync>;
+pub type BoxedCaster = Box<dyn AnyCaster>;
 
 #[cfg(doctest)]
 doc_comment::doctest!("../README.md");
 
-/// A distributed slice gathering constructor functions for [`Caster<T>`]s.
+/// A distributed slice gathering [`Caster<T>`]s.
 ///
-/// A constructor function returns `TypeId` of a concrete type involved in the casting
-/// and a `Box` of a trait object backed by a [`Caster<T>`].
+/// Each element is a static [`CasterEntry`] holding a function returning `TypeId` of
+/// a concrete type involved in the casting and a reference to a static [`StaticCaster<T>`].
 ///
+/// [`CasterEntry`]: ./struct.CasterEntry.html
 /// [`Caster<T>`]: ./struct.Caster.html
+/// [`StaticCaster<T>`]: ./struct.StaticCaster.html
+#[cfg(not(feature = "disabled"))]
+#[doc(hidden)]
+#[distributed_slice]
+pub static CASTERS: [CasterEntry] = [..];
+
+/// A distributed slice gathering constructor functions for [`DerefTo`]s registered
+/// with `#[cast_to(deref_to = Target)]`.
+///
+/// A constructor function returns `TypeId` of a type implementing `Deref` and a [`DerefTo`]
+/// to its target.
+///
+/// [`DerefTo`]: ./struct.DerefTo.html
+#[cfg(not(feature = "disabled"))]
 #[doc(hidden)]
 #[distributed_slice]
-pub static CASTERS: [fn() -> (TypeId, BoxedCaster)] = [..];
+pub static DEREFS: [fn() -> (TypeId, DerefTo)] = [..];
+
+/// A relationship from a type implementing `Deref` to its target, which lets a reference
+/// to the former be cast with the casters of the latter.
+#[doc(hidden)]
+#[derive(Clone, Copy)]
+pub struct DerefTo {
+    /// `TypeId` of the target.
+    pub target: TypeId,
+
+    /// Dereferences an immutable reference to the type to that to the target.
+    pub deref_ref: fn(from: &dyn Any) -> &dyn Any,
+
+    /// Dereferences a mutable reference to the type to that to the target,
+    /// which exists only if the type implements `DerefMut`.
+    pub deref_mut: Option<fn(from: &mut dyn Any) -> &mut dyn Any>,
+}
+
+/// Emits the registration of a link marker generated along with a caster by the macros
+/// if `check-linking` feature is enabled, and nothing otherwise.
+#[cfg(feature = "check-linking")]
+#[doc(hidden)]
+#[macro_export]
+macro_rules! __link_marker {
+    ($($item:tt)*) => {
+        $($item)*
+    };
+}
+
+/// Emits the registration of a link marker generated along with a caster by the macros
+/// if `check-linking` feature is enabled, and nothing otherwise.
+#[cfg(not(feature = "check-linking"))]
+#[doc(hidden)]
+#[macro_export]
+macro_rules! __link_marker {
+    ($($item:tt)*) => {};
+}
+
+/// Emits the registrations generated by the macros unless `disabled` feature is enabled,
+/// in which case the macros leave only the items they are applied to.
+#[cfg(not(feature = "disabled"))]
+#[doc(hidden)]
+#[macro_export]
+macro_rules! __registration {
+    ($($item:tt)*) => {
+        $($item)*
+    };
+}
+
+/// Emits the registrations generated by the macros unless `disabled` feature is enabled,
+/// in which case the macros leave only the items they are applied to.
+#[cfg(feature = "disabled")]
+#[doc(hidden)]
+#[macro_export]
+macro_rules! __registration {
+    ($($item:tt)*) => {};
+}
+
+/// Casts a reference, a mutable reference or a `Box` of a trait object into type `T`,
+/// which reads as `cast!(source => T)` in place of calling `cast::<T>()` of the `cast` traits.
+///
+/// - `cast!(source => T)` casts `&*source` into `Option<&T>`.
+/// - `cast!(mut source => T)` casts `&mut *source` into `Option<&mut T>`.
+/// - `cast!(box source => T)` casts `source` of `Box` into `Result<Box<T>, Box<_>>`.
+///
+/// As `source` is dereferenced before the cast, it can be either a reference or a smart
+/// pointer to the trait object, and no trait needs to be in scope.
+///
+/// # Examples
+/// ```
+/// # use intertrait::*;
+/// # #[cast_to(Greet)]
+/// # struct Data;
+/// # trait Greet {
+/// #     fn greet(&self);
+/// # }
+/// # impl Greet for Data {
+/// #    fn greet(&self) {
+/// #        println!("Hello");
+/// #    }
+/// # }
+/// trait Source: CastFrom {}
+/// impl Source for Data {}
+///
+/// let mut source: Box<dyn Source> = Box::new(Data);
+/// cast!(source => dyn Greet).unwrap().greet();
+/// cast!(mut source => dyn Greet).unwrap().greet();
+/// cast!(box source => dyn Greet).ok().unwrap().greet();
+/// ```
+#[macro_export]
+macro_rules! cast {
+    (mut $source:expr => $target:ty) => {
+        $crate::cast_mut::<$target, _>(&mut *$source)
+    };
+    (box $source:expr => $target:ty) => {
+        $crate::cast_box::<$target, _>($source)
+    };
+    ($source:expr => $target:ty) => {
+        $crate::cast_ref::<$target, _>(&*$source)
+    };
+}
+
+/// Casts a trait object into each of several types, which discovers the capabilities of
+/// the underlying value at once.
+///
+/// - `query!(source => name1: T1, name2: T2, ...)` yields a struct with fields `name1: Option<&T1>`,
+///   `name2: Option<&T2>` and so on.
+/// - `query!(source => T1, T2, ...)` yields a tuple of `(Option<&T1>, Option<&T2>, ...)`.
+///
+/// As with [`cast!`], `source` is dereferenced before the casts, so it can be either
+/// a reference or a smart pointer to the trait object.
+///
+/// # Examples
+/// ```
+/// # use intertrait::*;
+/// # trait Greet {
+/// #     fn greet(&self);
+/// # }
+/// # trait Render {
+/// #     fn render(&self);
+/// # }
+/// # impl Greet for Plugin {
+/// #    fn greet(&self) {
+/// #        println!("Hello");
+/// #    }
+/// # }
+/// trait Source: CastFrom {}
+/// #[cast_to(Greet)]
+/// struct Plugin;
+/// impl Source for Plugin {}
+///
+/// let plugin: Box<dyn Source> = Box::new(Plugin);
+/// let caps = query!(plugin => greet: dyn Greet, render: dyn Render);
+/// caps.greet.unwrap().greet();
+/// assert!(caps.render.is_none());
+///
+/// let (greet, render) = query!(plugin => dyn Greet, dyn Render);
+/// assert!(greet.is_some() && render.is_none());
+/// ```
+///
+/// [`cast!`]: ./macro.cast.html
+#[macro_export]
+macro_rules! query {
+    ($source:expr => $($name:ident : $target:ty),+ $(,)?) => {{
+        #[allow(dead_code)]
+        #[derive(Clone, Copy)]
+        struct Query<'a> {
+            $($name: Option<&'a $target>,)+
+        }
+        let source = &*$source;
+        Query {
+            $($name: $crate::cast_ref::<$target, _>(source),)+
+        }
+    }};
+    ($source:expr => $($target:ty),+ $(,)?) => {{
+        let source = &*$source;
+        ($($crate::cast_ref::<$target, _>(source),)+)
+    }};
+}
+
+/// Tries casting a trait object into each of the types in the arms in order like `match`,
+/// and evaluates the arm of the first type it can be cast into with the result bound to
+/// the name of the arm. The catch-all arm `_` is evaluated if none of them matches.
+///
+/// ```ignore
+/// match_cast!(source => {
+///     name1: T1 => expr1,
+///     name2: T2 => expr2,
+///     _ => expr3,
+/// })
+/// ```
+///
+/// `match_cast!(mut source => { ... })` binds mutable references instead. As with [`cast!`],
+/// `source` is dereferenced before the casts, so it can be either a reference or a smart
+/// pointer to the trait object. The arm `_` can be omitted if the arms evaluate to `()`.
+///
+/// # Examples
+/// ```
+/// # use intertrait::*;
+/// # trait GpuDraw {
+/// #     fn draw_gpu(&self) -> String;
+/// # }
+/// # trait CpuDraw {
+/// #     fn draw_cpu(&self) -> String;
+/// # }
+/// # impl CpuDraw for Canvas {
+/// #     fn draw_cpu(&self) -> String {
+/// #         "cpu".to_string()
+/// #     }
+/// # }
+/// trait Source: CastFrom {}
+/// #[cast_to(CpuDraw)]
+/// struct Canvas;
+/// impl Source for Canvas {}
+///
+/// let source: &dyn Source = &Canvas;
+/// let drawn = match_cast!(source => {
+///     gpu: dyn GpuDraw => gpu.draw_gpu(),
+///     cpu: dyn CpuDraw => cpu.draw_cpu(),
+///     _ => "nothing".to_string(),
+/// });
+/// assert_eq!(drawn, "cpu");
+/// ```
+///
+/// [`cast!`]: ./macro.cast.html
+#[macro_export]
+macro_rules! match_cast {
+    (mut $source:expr => {
+        $($name:ident : $target:ty => $arm:expr),+ $(, _ => $default:expr)? $(,)?
+    }) => {{
+        let source = &mut *$source;
+        $(
+            if let Some($name) = $crate::cast_mut::<$target, _>(source) {
+                $arm
+            } else
+        )+ {
+            $($default)?
+        }
+    }};
+    ($source:expr => {
+        $($name:ident : $target:ty => $arm:expr),+ $(, _ => $default:expr)? $(,)?
+    }) => {{
+        let source = &*$source;
+        $(
+            if let Some($name) = $crate::cast_ref::<$target, _>(source) {
+                $arm
+            } else
+        )+ {
+            $($default)?
+        }
+    }};
+}
 
-/// A `HashMap` mapping `TypeId` of a [`Caster<T>`] to an instance of it.
+/// Casts a reference or a mutable reference to a trait object into type `T` like [`cast!`],
+/// caching the caster resolved at each call site, which pays off in a loop casting values
+/// of the same concrete type.
+///
+/// - `cached_cast!(source => T)` casts `&*source` into `Option<&T>`.
+/// - `cached_cast!(mut source => T)` casts `&mut *source` into `Option<&mut T>`.
+///
+/// Each call site keeps a thread-local [`CastCache`] of the caster for the concrete type
+/// last cast, so `T` can't depend on the generic parameters of the enclosing function.
+///
+/// # Examples
+/// ```
+/// # use intertrait::*;
+/// # trait Greet {
+/// #     fn greet(&self) -> usize;
+/// # }
+/// # impl Greet for Data {
+/// #    fn greet(&self) -> usize {
+/// #        1
+/// #    }
+/// # }
+/// trait Source: CastFrom {}
+/// #[cast_to(Greet)]
+/// struct Data;
+/// impl Source for Data {}
+///
+/// let sources: Vec<&dyn Source> = vec![&Data; 100];
+/// let mut greeted = 0;
+/// for source in sources {
+///     // The registry is looked up only for the first one.
+///     greeted += cached_cast!(source => dyn Greet).unwrap().greet();
+/// }
+/// assert_eq!(greeted, 100);
+/// ```
+///
+/// [`cast!`]: ./macro.cast.html
+/// [`CastCache`]: ./cast/struct.CastCache.html
+#[macro_export]
+macro_rules! cached_cast {
+    (mut $source:expr => $target:ty) => {{
+        ::std::thread_local! {
+            static CACHE: $crate::cast::CastCache<$target> = $crate::cast::CastCache::new();
+        }
+        let source = &mut *$source;
+        CACHE.with(move |cache| cache.cast_mut(source))
+    }};
+    ($source:expr => $target:ty) => {{
+        ::std::thread_local! {
+            static CACHE: $crate::cast::CastCache<$target> = $crate::cast::CastCache::new();
+        }
+        let source = &*$source;
+        CACHE.with(|cache| cache.cast_ref(source))
+    }};
+}
+
+/// A `Registry` of the [`Caster<T>`]s gathered in [`CASTERS`].
 ///
 /// [`Caster<T>`]: ./struct.Caster.html
-static CASTER_MAP: Lazy<HashMap<(TypeId, TypeId), BoxedCaster, BuildFastHasher>> =
-    Lazy::new(|| {
-        CASTERS
-            .iter()
-            .map(|f| {
-                let (type_id, caster) = f();
-                ((type_id, (*caster).type_id()), caster)
-            })
-            .collect()
-    });
+/// [`CASTERS`]: ./static.CASTERS.html
+#[cfg(not(feature = "disabled"))]
+static CASTER_MAP: Lazy<registry::Registry> =
+    Lazy::new(|| registry::Registry::from_slice(&CASTERS).unwrap_or_else(|err| panic!("{}", err)));
+
+/// Exports the casters registered in a dynamic library (e.g. a plugin) as the function named
+/// [`plugin::CASTERS_SYMBOL`], which lets a host load the library with [`plugin::load`].
+///
+/// It's meant to be invoked once at the root of a crate of `crate-type = ["cdylib"]`.
+///
+/// ```ignore
+/// intertrait::export_casters!();
+/// ```
+///
+/// [`plugin::CASTERS_SYMBOL`]: ./plugin/constant.CASTERS_SYMBOL.html
+/// [`plugin::load`]: ./plugin/fn.load.html
+#[cfg(not(feature = "disabled"))]
+#[macro_export]
+macro_rules! export_casters {
+    () => {
+        #[doc(hidden)]
+        #[no_mangle]
+        pub fn __intertrait_casters() -> &'static [$crate::CasterEntry] {
+            &$crate::CASTERS
+        }
+    };
+}
+
+/// Builds the registry of casters eagerly, which is otherwise built on the first cast.
+///
+/// Calling it at a controlled point during startup keeps the first cast, e.g. one on
+/// a latency-sensitive path, from paying the cost of building the registry. The tables of
+/// the registry are built in parallel with `rayon` feature. Calling it again does nothing.
+///
+/// # Examples
+/// ```
+/// // At startup, before serving requests casting trait objects
+/// intertrait::init();
+/// ```
+pub fn init() {
+    #[cfg(all(not(feature = "disabled"), feature = "rayon"))]
+    rayon::join(
+        || {
+            Lazy::force(&CASTER_MAP);
+        },
+        || rayon::join(deref::init, marker::init),
+    );
+    #[cfg(all(not(feature = "disabled"), not(feature = "rayon")))]
+    {
+        Lazy::force(&CASTER_MAP);
+        deref::init();
+        marker::init();
+    }
+}
 
 fn cast_arc_panic<T: ?Sized + 'static>(_: Arc<dyn Any + Sync + Send>) -> Arc<T> {
     panic!("Prepend [sync] to the list of target traits for Sync + Send types")
 }
 
+/// Reinterprets a pointer to a value of a concrete type of `type_id` as that to `T`, only if
+/// `T` is the concrete type, which lets `T` of the casts be a concrete type as well as a trait.
+///
+/// `T` isn't known to be `Sized` at compile time, but it is if it's the concrete type,
+/// in which case a pointer to `T` is as thin as `*const ()`.
+fn downcast_ptr<T: ?Sized + 'static>(type_id: TypeId, ptr: *const ()) -> Option<*const T> {
+    if type_id != TypeId::of::<T>() {
+        return None;
+    }
+    // SAFETY: `T` is `Sized` as checked above, so both are thin pointers of the same layout.
+    Some(unsafe { std::mem::transmute_copy::<*const (), *const T>(&ptr) })
+}
+
+/// Downcasts `any` into `T` if `T` is its concrete type.
+fn downcast_ref<T: ?Sized + 'static>(any: &dyn Any) -> Option<&T> {
+    let ptr = downcast_ptr::<T>((*any).type_id(), any as *const dyn Any as *const ())?;
+    // SAFETY: The pointer is to the value of `any` of type `T`.
+    Some(unsafe { &*ptr })
+}
+
+/// Downcasts `any` into `T` if `T` is its concrete type.
+fn downcast_mut<T: ?Sized + 'static>(any: &mut dyn Any) -> Option<&mut T> {
+    let type_id = (*any).type_id();
+    let ptr = downcast_ptr::<T>(type_id, any as *mut dyn Any as *const ())?;
+    // SAFETY: The pointer is to the value of `any` of type `T`, borrowed mutably.
+    Some(unsafe { &mut *(ptr as *mut T) })
+}
+
+/// Downcasts `any` into `T` if `T` is its concrete type. If fails, returns `any`.
+fn downcast_box<T: ?Sized + 'static>(any: Box<dyn Any>) -> Result<Box<T>, Box<dyn Any>> {
+    match downcast_ptr::<T>((*any).type_id(), &*any as *const dyn Any as *const ()) {
+        Some(ptr) => {
+            // SAFETY: The ownership of the value of type `T` is moved from `any`.
+            let _ = Box::into_raw(any);
+            Ok(unsafe { Box::from_raw(ptr as *mut T) })
+        }
+        None => Err(any),
+    }
+}
+
+/// Panics with a message telling that a value of type `source` can't be cast into `T`.
+fn panic_not_castable<T: ?Sized>(source: &str) -> ! {
+    let target = std::any::type_name::<T>();
+    panic!(
+        "{} is not castable to {}; did you forget #[cast_to({})]?",
+        source,
+        target,
+        target.trim_start_matches("dyn ")
+    )
+}
+
 /// A `Caster` knows how to cast a reference to or `Box` of a trait object for `Any`
 /// to a trait object of trait `T`. Each `Caster` instance is specific to a concrete type.
 /// That is, it knows how to cast to single specific trait implemented by single specific type.
@@ -123,6 +589,13 @@ pub struct Caster<T: ?Sized + 'static> {
 
     /// Casts a `Box` holding a trait object for `Any` to another `Box` holding a trait object
     /// for trait `T`.
+    ///
+    /// It should return the allocation of `from` as is without moving the value out of it.
+    /// [`CastBox::cast_pin`] relies on it only for a caster referred to by a [`CasterEntry`],
+    /// whose creator promises it.
+    ///
+    /// [`CastBox::cast_pin`]: ./cast/trait.CastBox.html#tymethod.cast_pin
+    /// [`CasterEntry`]: ./struct.CasterEntry.html
     pub cast_box: fn(from: Box<dyn Any>) -> Box<T>,
 
     /// Casts an `Rc` holding a trait object for `Any` to another `Rc` holding a trait object
@@ -132,10 +605,24 @@ pub struct Caster<T: ?Sized + 'static> {
     /// Casts an `Arc` holding a trait object for `Any + Sync + Send + 'static`
     /// to another `Arc` holding a trait object for trait `T`.
     pub cast_arc: fn(from: Arc<dyn Any + Sync + Send + 'static>) -> Arc<T>,
+
+    /// The name of the concrete type, which is meant for diagnostics.
+    pub type_name: &'static str,
+
+    /// The label given with `#[cast_to(label = "...")]`, which is meant for introspection.
+    pub label: Option<&'static str>,
 }
 
+impl<T: ?Sized + 'static> Clone for Caster<T> {
+    fn clone(&self) -> Self {
+        *self
+    }
+}
+
+impl<T: ?Sized + 'static> Copy for Caster<T> {}
+
 impl<T: ?Sized + 'static> Caster<T> {
-    pub fn new(
+    pub const fn new(
         cast_ref: fn(from: &dyn Any) -> &T,
         cast_mut: fn(from: &mut dyn Any) -> &mut T,
         cast_box: fn(from: Box<dyn Any>) -> Box<T>,
@@ -147,10 +634,12 @@ impl<T: ?Sized + 'static> Caster<T> {
             cast_box,
             cast_rc,
             cast_arc: cast_arc_panic,
+            type_name: UNKNOWN_TYPE_NAME,
+            label: None,
         }
     }
 
-    pub fn new_sync(
+    pub const fn new_sync(
         cast_ref: fn(from: &dyn Any) -> &T,
         cast_mut: fn(from: &mut dyn Any) -> &mut T,
         cast_box: fn(from: Box<dyn Any>) -> Box<T>,
@@ -163,15 +652,492 @@ impl<T: ?Sized + 'static> Caster<T> {
             cast_box,
             cast_rc,
             cast_arc,
+            type_name: UNKNOWN_TYPE_NAME,
+            label: None,
         }
     }
 }
 
+/// A [`Caster<T>`] built in place as a static by the macros, along with a function returning
+/// the name of the concrete type, which can't be computed in a constant.
+///
+/// [`Caster<T>`]: ./struct.Caster.html
+#[doc(hidden)]
+pub struct StaticCaster<T: ?Sized + 'static> {
+    /// Returns the name of the concrete type, which is meant for diagnostics.
+    pub type_name: fn() -> &'static str,
+
+    /// The caster whose `type_name` is left unknown.
+    pub caster: Caster<T>,
+}
+
+/// The name of the concrete type of a [`Caster<T>`] created without specifying it.
+///
+/// [`Caster<T>`]: ./struct.Caster.html
+const UNKNOWN_TYPE_NAME: &str = "{unknown}";
+
+/// A type-erased [`Caster<T>`], which is what the registry holds.
+///
+/// [`Caster<T>`]: ./struct.Caster.html
+#[doc(hidden)]
+pub trait AnyCaster: Any + Send + Sync {
+    /// Returns the `Caster<T>` as `Any` to be downcast.
+    fn as_any(&self) -> &(dyn Any + Send + Sync);
+
+    /// Returns `TypeId` of trait `T` of the `Caster<T>`, which keys it in the registry.
+    fn trait_id(&self) -> TypeId;
+
+    /// Returns the names of the concrete type and trait `T` of the `Caster<T>`.
+    fn names(&self) -> (&'static str, &'static str);
+
+    /// Returns the label of the `Caster<T>`.
+    fn label(&self) -> Option<&'static str>;
+
+    /// Returns a copy of the `Caster<T>` in a `Box`.
+    fn clone_box(&self) -> BoxedCaster;
+}
+
+impl<T: ?Sized + 'static> AnyCaster for Caster<T> {
+    fn as_any(&self) -> &(dyn Any + Send + Sync) {
+        self
+    }
+
+    fn trait_id(&self) -> TypeId {
+        TypeId::of::<T>()
+    }
+
+    fn names(&self) -> (&'static str, &'static str) {
+        (self.type_name, std::any::type_name::<T>())
+    }
+
+    fn label(&self) -> Option<&'static str> {
+        self.label
+    }
+
+    fn clone_box(&self) -> BoxedCaster {
+        Box::new(*self)
+    }
+}
+
+impl<T: ?Sized + 'static> AnyCaster for StaticCaster<T> {
+    fn as_any(&self) -> &(dyn Any + Send + Sync) {
+        &self.caster
+    }
+
+    fn trait_id(&self) -> TypeId {
+        TypeId::of::<T>()
+    }
+
+    fn names(&self) -> (&'static str, &'static str) {
+        ((self.type_name)(), std::any::type_name::<T>())
+    }
+
+    fn label(&self) -> Option<&'static str> {
+        self.caster.label
+    }
+
+    fn clone_box(&self) -> BoxedCaster {
+        Box::new(Caster {
+            type_name: (self.type_name)(),
+            ..self.caster
+        })
+    }
+}
+
+/// `TypeId`s identifying a cast from a concrete type into trait `T`, whose pair of
+/// the concrete type and the trait keys the registries.
+///
+/// The lookups taking it in place of `T` are compiled once rather than for every trait `T`
+/// a value is cast into.
+#[derive(Clone, Copy)]
+struct CastKey {
+    type_id: TypeId,
+    trait_id: TypeId,
+
+    /// `TypeId` of `Caster<T>`, which a caster resolved by the fallback must be of.
+    /// It's unknown for a key built from `TypeId`s only, in which case the caster is trusted.
+    #[cfg(feature = "dynamic")]
+    caster_id: Option<TypeId>,
+}
+
+impl CastKey {
+    fn new<T: ?Sized + 'static>(type_id: TypeId) -> Self {
+        CastKey {
+            type_id,
+            trait_id: TypeId::of::<T>(),
+            #[cfg(feature = "dynamic")]
+            caster_id: Some(TypeId::of::<Caster<T>>()),
+        }
+    }
+
+    /// Creates a key for a cast into a trait only known by `trait_id` at runtime.
+    fn erased(type_id: TypeId, trait_id: TypeId) -> Self {
+        CastKey {
+            type_id,
+            trait_id,
+            #[cfg(feature = "dynamic")]
+            caster_id: None,
+        }
+    }
+
+    /// Returns the key for the same cast from another concrete type of `type_id`.
+    fn with_type_id(self, type_id: TypeId) -> Self {
+        CastKey { type_id, ..self }
+    }
+
+    #[cfg(any(
+        not(feature = "disabled"),
+        feature = "dynamic",
+        feature = "impls-cache"
+    ))]
+    fn pair(&self) -> (TypeId, TypeId) {
+        (self.type_id, self.trait_id)
+    }
+}
+
 /// Returns a `Caster<S, T>` from a concrete type `S` to a trait `T` implemented by it.
-fn caster<T: ?Sized + 'static>(type_id: TypeId) -> Option<&'static Caster<T>> {
-    CASTER_MAP
-        .get(&(type_id, TypeId::of::<Caster<T>>()))
-        .and_then(|caster| caster.downcast_ref::<Caster<T>>())
+#[cfg(not(feature = "disabled"))]
+fn caster<T: ?Sized + 'static>(type_id: TypeId) -> Option<Caster<T>> {
+    #[cfg(feature = "dynamic")]
+    {
+        if dynamic::is_denied(&(type_id, TypeId::of::<T>())) {
+            return None;
+        }
+    }
+    #[cfg(feature = "testing")]
+    {
+        if let Some(caster) = testing::caster::<T>(type_id) {
+            return caster;
+        }
+    }
+    let caster = CASTER_MAP.caster::<T>(type_id);
+    #[cfg(feature = "dynamic")]
+    let caster = caster.or_else(|| dynamic::caster::<T>(type_id));
+    caster
+}
+
+/// Returns no `Caster<T>` as casting is disabled.
+#[cfg(feature = "disabled")]
+fn caster<T: ?Sized + 'static>(_: TypeId) -> Option<Caster<T>> {
+    None
+}
+
+/// Returns a `Caster<T>` for casting a pinned value of a concrete type of `type_id`, which
+/// must be referred to by a `CasterEntry` so as not to move the value. Neither the casters
+/// registered at runtime, those in the testing scopes, nor the fallback are used.
+#[cfg(not(feature = "disabled"))]
+fn pin_caster<T: ?Sized + 'static>(type_id: TypeId) -> Option<Caster<T>> {
+    #[cfg(feature = "dynamic")]
+    {
+        if dynamic::is_denied(&(type_id, TypeId::of::<T>())) {
+            return None;
+        }
+    }
+    #[cfg(feature = "testing")]
+    {
+        if testing::caster::<T>(type_id).is_some() {
+            return None;
+        }
+    }
+    let caster = CASTER_MAP.pin_caster::<T>(type_id);
+    #[cfg(feature = "dynamic")]
+    let caster = caster.or_else(|| dynamic::pin_caster::<T>(type_id));
+    caster
+}
+
+/// Returns no `Caster<T>` as casting is disabled.
+#[cfg(feature = "disabled")]
+fn pin_caster<T: ?Sized + 'static>(_: TypeId) -> Option<Caster<T>> {
+    None
+}
+
+/// Tests if there is a caster for the cast of `key`.
+#[cfg(not(feature = "disabled"))]
+fn contains_caster(key: CastKey) -> bool {
+    #[cfg(feature = "testing")]
+    {
+        if let Some(contains) = testing::contains(key.pair()) {
+            #[cfg(feature = "dynamic")]
+            let contains = contains && !dynamic::is_denied(&key.pair());
+            return contains;
+        }
+    }
+    let contains = CASTER_MAP.contains(&key.pair());
+    #[cfg(feature = "dynamic")]
+    let contains = (contains || dynamic::contains_caster(key)) && !dynamic::is_denied(&key.pair());
+    contains
+}
+
+/// Tests nothing as casting is disabled.
+#[cfg(feature = "disabled")]
+fn contains_caster(_: CastKey) -> bool {
+    false
+}
+
+/// Returns the generation of the casts allowed at runtime, which changes whenever they do,
+/// so that what is cached from the registry can be told to be stale.
+#[cfg(any(feature = "dynamic", feature = "testing"))]
+fn generation() -> usize {
+    let generation = 0usize;
+    #[cfg(feature = "dynamic")]
+    let generation = generation.wrapping_add(dynamic::generation());
+    #[cfg(feature = "testing")]
+    let generation = generation.wrapping_add(testing::generation());
+    generation
+}
+
+/// Returns the generation of the casts, which never changes without `dynamic` or `testing`
+/// feature.
+#[cfg(not(any(feature = "dynamic", feature = "testing")))]
+fn generation() -> usize {
+    0
+}
+
+/// Tests if a value of a concrete type of `type_id` can be cast into `T`.
+fn impls<T: ?Sized + 'static>(type_id: TypeId) -> bool {
+    impls_key(CastKey::new::<T>(type_id))
+}
+
+/// Tests if the cast of `key` is possible, which is the body of `impls` kept apart from it
+/// to be compiled once for all the traits.
+fn impls_key(key: CastKey) -> bool {
+    if key.type_id == key.trait_id {
+        // A downcast into the concrete type itself.
+        return true;
+    }
+    let impls = || contains_caster(key) || deref::contains(key);
+    #[cfg(feature = "impls-cache")]
+    let impls = || impls_cache::impls(key.pair(), impls);
+    impls()
+}
+
+/// Returns an iterator over `TypeId`s of all the concrete types registered for casting
+/// into a trait `T`.
+///
+/// The concrete types are looked up from an index built along with the registry,
+/// so it doesn't involve scanning all the casters.
+///
+/// # Examples
+/// ```
+/// # use std::any::TypeId;
+/// # use intertrait::*;
+/// # trait Render {}
+/// #[cast_to(Render)]
+/// struct Sprite;
+/// # impl Render for Sprite {}
+///
+/// let renderers: Vec<TypeId> = impls_of::<dyn Render>().collect();
+/// assert_eq!(renderers, vec![TypeId::of::<Sprite>()]);
+/// ```
+#[cfg(not(feature = "disabled"))]
+pub fn impls_of<T: ?Sized + 'static>() -> impl Iterator<Item = TypeId> {
+    let trait_id = TypeId::of::<T>();
+    let sources = CASTER_MAP.sources(trait_id).iter().copied();
+    #[cfg(feature = "dynamic")]
+    let sources = sources.chain(
+        dynamic::sources(trait_id)
+            .into_iter()
+            .filter(move |type_id| !CASTER_MAP.contains(&(*type_id, trait_id))),
+    );
+    #[cfg(feature = "dynamic")]
+    let sources = sources.filter(move |type_id| !dynamic::is_denied(&(*type_id, trait_id)));
+    sources
+}
+
+/// Returns an empty iterator as casting is disabled.
+#[cfg(feature = "disabled")]
+pub fn impls_of<T: ?Sized + 'static>() -> impl Iterator<Item = TypeId> {
+    std::iter::empty()
+}
+
+/// Returns an iterator over `TypeId`s and the names of all the traits registered for casting
+/// into from the concrete type backing `obj`, which tells what capabilities it exposes.
+///
+/// Only the casts registered for the concrete type itself are reported, but not those
+/// reachable through `deref_to`, nor the markers.
+///
+/// # Examples
+/// ```
+/// # use std::any::TypeId;
+/// # use intertrait::*;
+/// # trait Source: CastFrom {}
+/// # trait Render {}
+/// #[cast_to(Render)]
+/// struct Sprite;
+/// # impl Render for Sprite {}
+/// impl Source for Sprite {}
+///
+/// let source: &dyn Source = &Sprite;
+/// for (trait_id, name) in traits_of(source) {
+///     println!("{}", name);
+///     assert_eq!(trait_id, TypeId::of::<dyn Render>());
+/// }
+/// ```
+#[cfg(not(feature = "disabled"))]
+pub fn traits_of<S: ?Sized + CastFrom>(obj: &S) -> impl Iterator<Item = (TypeId, &'static str)> {
+    let type_id = concrete_type_id(obj);
+    let traits = CASTER_MAP.traits(type_id);
+    #[cfg(feature = "dynamic")]
+    let traits = traits.chain(
+        dynamic::traits(type_id)
+            .into_iter()
+            .filter(move |(trait_id, _)| !CASTER_MAP.contains(&(type_id, *trait_id))),
+    );
+    #[cfg(feature = "dynamic")]
+    let traits = traits.filter(move |(trait_id, _)| !dynamic::is_denied(&(type_id, *trait_id)));
+    traits
+}
+
+/// Returns an empty iterator as casting is disabled.
+#[cfg(feature = "disabled")]
+pub fn traits_of<S: ?Sized + CastFrom>(_: &S) -> impl Iterator<Item = (TypeId, &'static str)> {
+    std::iter::empty()
+}
+
+/// Returns the keys of all the registered casts in a stable order, which is the same
+/// across builds and runs as long as the same casts are registered.
+///
+/// # Examples
+/// ```
+/// # use intertrait::*;
+/// # trait Greet {}
+/// #[cast_to(Greet)]
+/// struct Data;
+/// # impl Greet for Data {}
+///
+/// for key in registry_snapshot() {
+///     println!("{} => {}", key.type_name(), key.trait_name());
+/// }
+/// ```
+#[cfg(not(feature = "disabled"))]
+pub fn registry_snapshot() -> Vec<StableKey> {
+    let mut keys: Vec<StableKey> = CASTER_MAP.stable_keys().collect();
+    #[cfg(feature = "dynamic")]
+    keys.extend(dynamic::stable_keys());
+    keys.sort();
+    keys.dedup();
+    keys
+}
+
+/// Returns no keys as casting is disabled.
+#[cfg(feature = "disabled")]
+pub fn registry_snapshot() -> Vec<StableKey> {
+    Vec::new()
+}
+
+/// Returns the names of the concrete type and the trait of all the registered casts sorted
+/// by the names, which is meant for printing the whole table of casts to find out why a cast
+/// fails. It's enabled with `dump` feature.
+///
+/// # Examples
+/// ```
+/// # use intertrait::*;
+/// # trait Greet {}
+/// #[cast_to(Greet)]
+/// struct Data;
+/// # impl Greet for Data {}
+///
+/// for (type_name, trait_name) in dump() {
+///     eprintln!("{} => {}", type_name, trait_name);
+/// }
+/// ```
+#[cfg(feature = "dump")]
+pub fn dump() -> Vec<(&'static str, &'static str)> {
+    let mut casts: Vec<(&'static str, &'static str)> = registry_snapshot()
+        .iter()
+        .map(|key| (key.type_name(), key.trait_name()))
+        .collect();
+    casts.sort_unstable();
+    casts
+}
+
+/// Returns `TypeId` of the concrete type backing `obj`.
+///
+/// Unlike calling `type_id()` directly on a reference, which may resolve to the `TypeId`
+/// of the reference or the trait object itself, this always identifies the concrete type.
+///
+/// # Examples
+/// ```
+/// # use std::any::TypeId;
+/// # use intertrait::*;
+/// trait Source: CastFrom {}
+/// struct Data;
+/// impl Source for Data {}
+///
+/// let source: &dyn Source = &Data;
+/// assert_eq!(concrete_type_id(source), TypeId::of::<Data>());
+/// ```
+pub fn concrete_type_id<S: ?Sized + CastFrom>(obj: &S) -> TypeId {
+    Any::type_id(obj.ref_any())
+}
+
+/// Tests if `a` and `b` are backed by the same concrete type.
+///
+/// # Examples
+/// ```
+/// # use intertrait::*;
+/// trait Source: CastFrom {}
+/// struct Data;
+/// impl Source for Data {}
+/// struct Other;
+/// impl Source for Other {}
+///
+/// let (a, b, c): (&dyn Source, &dyn Source, &dyn Source) = (&Data, &Data, &Other);
+/// assert!(same_concrete(a, b));
+/// assert!(!same_concrete(a, c));
+/// ```
+pub fn same_concrete<A, B>(a: &A, b: &B) -> bool
+where
+    A: ?Sized + CastFrom,
+    B: ?Sized + CastFrom,
+{
+    concrete_type_id(a) == concrete_type_id(b)
+}
+
+/// Casts `source` into a reference to type `T`, which is the same as `source.cast::<T>()`
+/// without [`CastRef`] in scope.
+///
+/// # Examples
+/// ```
+/// # use intertrait::*;
+/// # #[cast_to(Greet)]
+/// # struct Data;
+/// # trait Greet {
+/// #     fn greet(&self);
+/// # }
+/// # impl Greet for Data {
+/// #    fn greet(&self) {
+/// #        println!("Hello");
+/// #    }
+/// # }
+/// trait Source: CastFrom {}
+/// impl Source for Data {}
+///
+/// let source: &dyn Source = &Data;
+/// cast_ref::<dyn Greet, _>(source).unwrap().greet();
+/// ```
+///
+/// [`CastRef`]: ./cast/trait.CastRef.html
+pub fn cast_ref<T: ?Sized + 'static, S: ?Sized + CastFrom>(source: &S) -> Option<&T> {
+    cast::CastRef::cast::<T>(source)
+}
+
+/// Casts `source` into a mutable reference to type `T`, which is the same as
+/// `source.cast::<T>()` without [`CastMut`] in scope.
+///
+/// [`CastMut`]: ./cast/trait.CastMut.html
+pub fn cast_mut<T: ?Sized + 'static, S: ?Sized + CastFrom>(source: &mut S) -> Option<&mut T> {
+    cast::CastMut::cast::<T>(source)
+}
+
+/// Casts `source` into a `Box` of type `T`, which is the same as `source.cast::<T>()`
+/// without [`CastBox`] in scope. If fails, returns `source`.
+///
+/// [`CastBox`]: ./cast/trait.CastBox.html
+pub fn cast_box<T: ?Sized + 'static, S: ?Sized + CastFrom>(
+    source: Box<S>,
+) -> Result<Box<T>, Box<S>> {
+    cast::CastBox::cast::<T>(source)
 }
 
 /// `CastFrom` must be extended by a trait that wants to allow for casting into another trait.
@@ -179,13 +1145,26 @@ fn caster<T: ?Sized + 'static>(type_id: TypeId) -> Option<&'static Caster<T>> {
 /// It is used for obtaining a trait object for [`Any`] from a trait object for its sub-trait,
 /// and blanket implemented for all `Sized + Any + 'static` types.
 ///
+/// A trait object for a trait not extending it is rejected by a bound on `CastFrom` with
+/// a message pointing at the missing supertrait. Beware that calling a method for casting on
+/// a reference to such an object still compiles, as the reference itself is `Sized + 'static`,
+/// but the cast always fails.
+///
+/// It is sealed, so it can't be implemented outside this crate. The casts of pinned values
+/// rely on its methods returning the value given as is, without moving it.
+///
 /// # Examples
 /// ```ignore
 /// trait Source: CastFrom {
 ///     ...
 /// }
 /// ```
-pub trait CastFrom: Any + 'static {
+#[diagnostic::on_unimplemented(
+    message = "`{Self}` can't be cast as it doesn't extend `CastFrom`",
+    label = "casting from `{Self}` requires `CastFrom`",
+    note = "add `CastFrom` as a supertrait of the source trait, e.g. `trait Source: CastFrom {{}}`"
+)]
+pub trait CastFrom: Any + sealed::Sealed + 'static {
     /// Returns a immutable reference to `Any`, which is backed by the type implementing this trait.
     fn ref_any(&self) -> &dyn Any;
 
@@ -197,6 +1176,11 @@ pub trait CastFrom: Any + 'static {
 
     /// Returns an `Rc` of `Any`, which is backed by the type implementing this trait.
     fn rc_any(self: Rc<Self>) -> Rc<dyn Any>;
+
+    /// Returns the name of the type implementing this trait, which is meant for diagnostics.
+    fn type_name(&self) -> &'static str {
+        std::any::type_name::<Self>()
+    }
 }
 
 /// `CastFromSync` must be extended by a trait that is `Any + Sync + Send + 'static`
@@ -212,8 +1196,37 @@ pub trait CastFrom: Any + 'static {
 ///     ...
 /// }
 /// ```
+#[diagnostic::on_unimplemented(
+    message = "`{Self}` can't be cast into `Arc` as it doesn't extend `CastFromSync`",
+    label = "casting from `{Self}` behind `Arc` requires `CastFromSync`",
+    note = "add `CastFromSync` as a supertrait of the source trait, e.g. `trait Source: CastFromSync {{}}`"
+)]
 pub trait CastFromSync: CastFrom + Sync + Send + 'static {
     fn arc_any(self: Arc<Self>) -> Arc<dyn Any + Sync + Send + 'static>;
+
+    /// Moves the value out of the `Arc` into a `Box` of `Any`. If fails, returns the `Arc`
+    /// as that of `Any`.
+    ///
+    /// It fails if another `Arc` points to the value. By default, it always fails, which is
+    /// for a type implementing this trait that isn't `Sized` and so can't be moved out.
+    fn unwrap_any(self: Arc<Self>) -> Result<Box<dyn Any>, Arc<dyn Any + Sync + Send + 'static>> {
+        Err(self.arc_any())
+    }
+}
+
+mod sealed {
+    use std::any::Any;
+
+    /// A supertrait of `CastFrom` keeping it from being implemented outside this crate.
+    pub trait Sealed {}
+
+    impl<T: Sized + Any + 'static> Sealed for T {}
+
+    impl Sealed for dyn Any + 'static {}
+
+    impl Sealed for dyn Any + Send + 'static {}
+
+    impl Sealed for dyn Any + Sync + Send + 'static {}
 }
 
 impl<T: Sized + Any + 'static> CastFrom for T {
@@ -252,10 +1265,35 @@ impl CastFrom for dyn Any + 'static {
     }
 }
 
+impl CastFrom for dyn Any + Send + 'static {
+    fn ref_any(&self) -> &dyn Any {
+        self
+    }
+
+    fn mut_any(&mut self) -> &mut dyn Any {
+        self
+    }
+
+    fn box_any(self: Box<Self>) -> Box<dyn Any> {
+        self
+    }
+
+    fn rc_any(self: Rc<Self>) -> Rc<dyn Any> {
+        self
+    }
+}
+
 impl<T: Sized + Sync + Send + 'static> CastFromSync for T {
     fn arc_any(self: Arc<Self>) -> Arc<dyn Any + Sync + Send + 'static> {
         self
     }
+
+    fn unwrap_any(self: Arc<Self>) -> Result<Box<dyn Any>, Arc<dyn Any + Sync + Send + 'static>> {
+        match Arc::try_unwrap(self) {
+            Ok(value) => Ok(Box::new(value)),
+            Err(this) => Err(this),
+        }
+    }
 }
 
 impl CastFrom for dyn Any + Sync + Send + 'static {
@@ -282,20 +1320,31 @@ impl CastFromSync for dyn Any + Sync + Send + 'static {
     }
 }
 
-#[cfg(test)]
+#[cfg(all(test, not(feature = "disabled")))]
 mod tests {
     use std::any::{Any, TypeId};
     use std::fmt::{Debug, Display};
 
     use linkme::distributed_slice;
 
-    use crate::{BoxedCaster, CastFromSync};
+    use crate::CastFromSync;
 
     use super::cast::*;
     use super::*;
 
     #[distributed_slice(super::CASTERS)]
-    static TEST_CASTER: fn() -> (TypeId, BoxedCaster) = create_test_caster;
+    static TEST_CASTER: CasterEntry =
+        unsafe { CasterEntry::new(TypeId::of::<TestStruct>, &TEST_DEBUG) };
+
+    static TEST_DEBUG: Caster<dyn Debug> = Caster::<dyn Debug> {
+        cast_ref: |from| from.downcast_ref::<TestStruct>().unwrap(),
+        cast_mut: |from| from.downcast_mut::<TestStruct>().unwrap(),
+        cast_box: |from| from.downcast::<TestStruct>().unwrap(),
+        cast_rc: |from| from.downcast::<TestStruct>().unwrap(),
+        cast_arc: |from| from.downcast::<TestStruct>().unwrap(),
+        type_name: "intertrait::tests::TestStruct",
+        label: None,
+    };
 
     #[derive(Debug)]
     struct TestStruct;
@@ -304,18 +1353,6 @@ mod tests {
 
     impl SourceTrait for TestStruct {}
 
-    fn create_test_caster() -> (TypeId, BoxedCaster) {
-        let type_id = TypeId::of::<TestStruct>();
-        let caster = Box::new(Caster::<dyn Debug> {
-            cast_ref: |from| from.downcast_ref::<TestStruct>().unwrap(),
-            cast_mut: |from| from.downcast_mut::<TestStruct>().unwrap(),
-            cast_box: |from| from.downcast::<TestStruct>().unwrap(),
-            cast_rc: |from| from.downcast::<TestStruct>().unwrap(),
-            cast_arc: |from| from.downcast::<TestStruct>().unwrap(),
-        });
-        (type_id, caster)
-    }
-
     #[test]
     fn cast_ref() {
         let ts = TestStruct;
@@ -380,6 +1417,19 @@ mod tests {
         assert!(display.is_err());
     }
 
+    #[test]
+    fn cast_box_wrong_returns_original() {
+        let ts = Box::new(TestStruct);
+        let ptr = &*ts as *const TestStruct as *const u8;
+        let st: Box<dyn SourceTrait> = ts;
+        let st = match st.cast::<dyn Display>() {
+            Ok(_) => panic!("must not be castable"),
+            Err(st) => st,
+        };
+        assert_eq!(&*st as *const _ as *const u8, ptr);
+        assert!(st.cast::<dyn Debug>().is_ok());
+    }
+
     #[test]
     fn cast_rc_wrong() {
         let ts = Rc::new(TestStruct);
@@ -396,6 +1446,27 @@ mod tests {
         assert!(display.is_err());
     }
 
+    #[test]
+    fn cast_arc_wrong_returns_original() {
+        let ts = Arc::new(TestStruct);
+        let st: Arc<dyn SourceTrait> = ts.clone();
+        assert_eq!(Arc::strong_count(&ts), 2);
+        let st = match st.cast::<dyn Display>() {
+            Ok(_) => panic!("must not be castable"),
+            Err(st) => st,
+        };
+        assert_eq!(Arc::strong_count(&ts), 2);
+        assert_eq!(&*st as *const _ as *const u8, &*ts as *const _ as *const u8);
+    }
+
+    #[test]
+    fn cast_ref_through_arc() {
+        let ts = Arc::new(TestStruct);
+        let st: Arc<dyn SourceTrait> = ts;
+        assert!((*st).cast::<dyn Debug>().is_some());
+        assert!((*st).cast::<dyn Display>().is_none());
+    }
+
     #[test]
     fn cast_ref_from_any() {
         let ts = TestStruct;
@@ -420,6 +1491,30 @@ mod tests {
         assert!(debug.is_ok());
     }
 
+    #[test]
+    fn cast_box_from_any_send() {
+        let ts = Box::new(TestStruct);
+        let st: Box<dyn Any + Send> = ts;
+        let debug = st.cast::<dyn Debug>();
+        assert!(debug.is_ok());
+    }
+
+    #[test]
+    fn cast_box_from_any_send_sync() {
+        let ts = Box::new(TestStruct);
+        let st: Box<dyn Any + Send + Sync> = ts;
+        let debug = st.cast::<dyn Debug>();
+        assert!(debug.is_ok());
+    }
+
+    #[test]
+    fn cast_ref_from_any_send() {
+        let ts = TestStruct;
+        let st: &(dyn Any + Send) = &ts;
+        assert!(st.cast::<dyn Debug>().is_some());
+        assert!(st.cast::<dyn Display>().is_none());
+    }
+
     #[test]
     fn cast_rc_from_any() {
         let ts = Rc::new(TestStruct);
@@ -436,6 +1531,210 @@ mod tests {
         assert!(debug.is_ok());
     }
 
+    #[test]
+    fn try_unwrap_cast_arc_from_any() {
+        let st: Arc<dyn Any + Send + Sync> = Arc::new(TestStruct);
+        assert!(st.try_unwrap_cast::<dyn Debug>().is_err());
+
+        let st: Arc<dyn SourceTrait> = Arc::new(TestStruct);
+        assert!(st.try_unwrap_cast::<dyn Debug>().is_ok());
+    }
+
+    #[test]
+    fn cast_borrowed_refs() {
+        fn debugs<'a>(sources: &'a [&'a dyn SourceTrait]) -> Vec<&'a dyn Debug> {
+            super::cast::cast_borrowed_refs(sources).collect()
+        }
+
+        #[derive(Debug)]
+        struct Unregistered;
+        impl SourceTrait for Unregistered {}
+
+        let (ts1, ts2, unregistered) = (TestStruct, TestStruct, Unregistered);
+        let sources: Vec<&dyn SourceTrait> = vec![&ts1, &unregistered, &ts2];
+        let debugs = debugs(&sources);
+        assert_eq!(debugs.len(), 2);
+        assert_eq!(format!("{:?}", debugs[0]), "TestStruct");
+    }
+
+    #[test]
+    fn expect_cast_ref() {
+        let ts = TestStruct;
+        let st: &dyn SourceTrait = &ts;
+        st.expect_cast::<dyn Debug>();
+    }
+
+    #[test]
+    #[should_panic(
+        expected = "intertrait::tests::TestStruct is not castable to dyn core::fmt::Display; \
+                    did you forget #[cast_to(core::fmt::Display)]?"
+    )]
+    fn expect_cast_ref_wrong() {
+        let ts = TestStruct;
+        let st: &dyn SourceTrait = &ts;
+        st.expect_cast::<dyn Display>();
+    }
+
+    #[test]
+    #[should_panic(expected = "TestStruct is not castable to dyn core::fmt::Display")]
+    fn expect_cast_mut_wrong() {
+        let mut ts = TestStruct;
+        let st: &mut dyn SourceTrait = &mut ts;
+        st.expect_cast::<dyn Display>();
+    }
+
+    #[test]
+    #[should_panic(expected = "TestStruct is not castable to dyn core::fmt::Display")]
+    fn expect_cast_box_wrong() {
+        let ts = Box::new(TestStruct);
+        let st: Box<dyn SourceTrait> = ts;
+        st.expect_cast::<dyn Display>();
+    }
+
+    #[test]
+    #[should_panic(expected = "TestStruct is not castable to dyn core::fmt::Display")]
+    fn expect_cast_rc_wrong() {
+        let ts = Rc::new(TestStruct);
+        let st: Rc<dyn SourceTrait> = ts;
+        st.expect_cast::<dyn Display>();
+    }
+
+    #[test]
+    fn expect_cast_arc() {
+        let ts = Arc::new(TestStruct);
+        let st: Arc<dyn SourceTrait> = ts;
+        let debug = st.expect_cast::<dyn Debug>();
+        assert_eq!(format!("{:?}", debug), "TestStruct");
+    }
+
+    #[test]
+    #[should_panic(expected = "TestStruct is not castable to dyn core::fmt::Display")]
+    fn expect_cast_arc_wrong() {
+        let ts = Arc::new(TestStruct);
+        let st: Arc<dyn SourceTrait> = ts;
+        st.expect_cast::<dyn Display>();
+    }
+
+    #[test]
+    fn cast_cursor() {
+        #[derive(Debug)]
+        struct Unregistered;
+        impl SourceTrait for Unregistered {}
+
+        let mut ts = TestStruct;
+        let sources: Vec<Box<dyn SourceTrait>> = vec![
+            Box::new(TestStruct),
+            Box::new(TestStruct),
+            Box::new(Unregistered),
+            Box::new(TestStruct),
+        ];
+        let mut cursor = CastCursor::<dyn Debug>::new();
+        let casts: Vec<bool> = sources
+            .iter()
+            .map(|source| cursor.cast_ref(&**source).is_some())
+            .collect();
+        assert_eq!(casts, vec![true, true, false, true]);
+        assert!(cursor.cast_mut(&mut ts).is_some());
+    }
+
+    #[test]
+    fn stable_key() {
+        let key = StableKey::new("a::Data", "dyn a::Greet");
+        assert_eq!(key.hash(), 0x9cc2_8174_c2f3_b15d);
+        assert_eq!(key.type_name(), "a::Data");
+        assert_eq!(key.trait_name(), "dyn a::Greet");
+        let other = StableKey::new("a::Data", "dyn a::Render");
+        assert_eq!(key < other, key.hash() < other.hash());
+    }
+
+    #[test]
+    fn registry_snapshot() {
+        let snapshot = super::registry_snapshot();
+        assert!(snapshot.windows(2).all(|keys| keys[0] < keys[1]));
+        assert!(snapshot.contains(&StableKey::new(
+            "intertrait::tests::TestStruct",
+            "dyn core::fmt::Debug"
+        )));
+    }
+
+    #[test]
+    fn cast_non_null() {
+        let ts = TestStruct;
+        let st: &dyn SourceTrait = &ts;
+        let ptr = st.cast_non_null::<dyn Debug>().unwrap();
+        let debug = unsafe { ptr.as_ref() };
+        assert_eq!(format!("{:?}", debug), "TestStruct");
+        assert_eq!(
+            ptr.as_ptr() as *const u8,
+            &ts as *const TestStruct as *const u8
+        );
+        assert!(st.cast_non_null::<dyn Display>().is_none());
+    }
+
+    #[test]
+    fn with_cast_ref() {
+        let ts = TestStruct;
+        let st: &dyn SourceTrait = &ts;
+        let debug = st.with_cast::<dyn Debug, _>(|debug| format!("{:?}", debug));
+        assert_eq!(debug.unwrap(), "TestStruct");
+        assert!(st.with_cast::<dyn Display, _>(|_| unreachable!()).is_none());
+    }
+
+    #[test]
+    fn with_cast_mut() {
+        let mut ts = TestStruct;
+        let st: &mut dyn SourceTrait = &mut ts;
+        let debug = st.with_cast::<dyn Debug, _>(|debug| format!("{:?}", debug));
+        assert_eq!(debug.unwrap(), "TestStruct");
+        assert!(st
+            .with_cast::<dyn Display, ()>(|_| unreachable!())
+            .is_none());
+    }
+
+    #[test]
+    fn cast_iter() {
+        let sources: Vec<Box<dyn SourceTrait>> = vec![Box::new(TestStruct), Box::new(TestStruct)];
+        let debugs: Vec<String> = sources
+            .iter()
+            .flat_map(|source| (**source).cast_iter::<dyn Debug>())
+            .map(|debug| format!("{:?}", debug))
+            .collect();
+        assert_eq!(debugs, vec!["TestStruct", "TestStruct"]);
+        assert_eq!(
+            sources
+                .iter()
+                .flat_map(|source| (**source).cast_iter::<dyn Display>())
+                .count(),
+            0
+        );
+    }
+
+    #[test]
+    fn box_any_to() {
+        let any: Box<dyn Any> = Box::new(TestStruct);
+        let any = any.downcast::<u8>().unwrap_err();
+        let debug = super::cast::box_any_to::<dyn Debug>(any).unwrap();
+        assert_eq!(format!("{:?}", debug), "TestStruct");
+
+        let any: Box<dyn Any> = Box::new(TestStruct);
+        let any = match super::cast::box_any_to::<dyn Display>(any) {
+            Ok(_) => panic!("must not be cast"),
+            Err(any) => any,
+        };
+        assert!(any.is::<TestStruct>());
+    }
+
+    #[test]
+    fn same_concrete() {
+        let (a, b) = (TestStruct, TestStruct);
+        let a: &dyn SourceTrait = &a;
+        let b: &dyn SourceTrait = &b;
+        assert!(super::same_concrete(a, b));
+        assert!(super::same_concrete(a, &TestStruct));
+        assert!(!super::same_concrete(a, &0u8 as &dyn Any));
+        assert_eq!(concrete_type_id(a), TypeId::of::<TestStruct>());
+    }
+
     #[test]
     fn impls_ref() {
         let ts = TestStruct;
@@ -505,4 +1804,106 @@ mod tests {
         let st: Arc<dyn SourceTrait> = ts;
         assert!(!(*st).impls::<dyn Display>());
     }
+
+    #[test]
+    fn impls_all() {
+        let ts = TestStruct;
+        let st: &dyn SourceTrait = &ts;
+        assert!(st.impls_all::<(&dyn Debug,)>());
+        assert!(st.impls_all::<(&dyn Debug, &TestStruct)>());
+        assert!(!st.impls_all::<(&dyn Debug, &dyn Display)>());
+        assert!(!st.impls_all::<(&dyn Display, &dyn Debug, &TestStruct)>());
+    }
+
+    #[cfg(feature = "perfect-hash")]
+    #[test]
+    fn perfect_map() {
+        use crate::perfect_hash::PerfectMap;
+
+        let keys = [
+            TypeId::of::<u8>(),
+            TypeId::of::<u16>(),
+            TypeId::of::<u32>(),
+            TypeId::of::<u64>(),
+            TypeId::of::<i8>(),
+            TypeId::of::<i16>(),
+            TypeId::of::<i32>(),
+            TypeId::of::<i64>(),
+            TypeId::of::<f32>(),
+            TypeId::of::<f64>(),
+            TypeId::of::<bool>(),
+            TypeId::of::<char>(),
+            TypeId::of::<String>(),
+        ];
+        let mut map = PerfectMap::default();
+        for (i, key) in keys.iter().enumerate() {
+            *map.entry(*key).or_default() += i;
+        }
+        map.reindex();
+        // A key inserted after reindexing is found until the next reindexing as well.
+        *map.entry(TypeId::of::<TestStruct>()).or_default() += 100;
+        *map.entry(keys[0]).or_default() += 100;
+
+        let check = |map: &PerfectMap<usize>| {
+            for (i, key) in keys.iter().enumerate().skip(1) {
+                assert_eq!(map.get(key), Some(&i));
+            }
+            assert_eq!(map.get(&keys[0]), Some(&100));
+            assert_eq!(map.get(&TypeId::of::<TestStruct>()), Some(&100));
+            assert_eq!(map.get(&TypeId::of::<()>()), None);
+            assert_eq!(map.iter().count(), keys.len() + 1);
+        };
+        check(&map);
+        map.reindex();
+        check(&map);
+    }
+
+    #[cfg(feature = "sorted-table")]
+    #[test]
+    fn sorted_map() {
+        use crate::sorted_table::SortedMap;
+
+        let keys = [
+            TypeId::of::<u8>(),
+            TypeId::of::<u16>(),
+            TypeId::of::<u32>(),
+            TypeId::of::<u64>(),
+            TypeId::of::<bool>(),
+        ];
+        let mut map = SortedMap::default();
+        for (i, key) in keys.iter().enumerate() {
+            *map.entry(*key).or_default() += i;
+        }
+        map.reindex();
+        // A key inserted after sorting is found until the next sort as well.
+        *map.entry(TypeId::of::<TestStruct>()).or_default() += 100;
+
+        let check = |map: &SortedMap<usize>| {
+            for (i, key) in keys.iter().enumerate() {
+                assert_eq!(map.get(key), Some(&i));
+            }
+            assert_eq!(map.get(&TypeId::of::<TestStruct>()), Some(&100));
+            assert_eq!(map.get(&TypeId::of::<()>()), None);
+        };
+        check(&map);
+        map.reindex();
+        check(&map);
+        let sorted: Vec<TypeId> = map.iter().map(|(key, _)| *key).collect();
+        assert!(sorted.windows(2).all(|pair| pair[0] < pair[1]));
+    }
+
+    #[test]
+    fn type_id_hasher() {
+        use std::hash::BuildHasher;
+
+        use crate::hasher::BuildTypeIdHasher;
+
+        let hasher = BuildTypeIdHasher::default();
+        let a = TypeId::of::<TestStruct>();
+        let b = TypeId::of::<dyn Debug>();
+        assert_ne!(hasher.hash_one((a, b)), hasher.hash_one((b, a)));
+        assert_ne!(hasher.hash_one((a, a)), hasher.hash_one((b, b)));
+        assert_ne!(hasher.hash_one(a), hasher.hash_one(b));
+        assert_eq!(hasher.hash_one(a), hasher.hash_one(a));
+    }
 }
@@ -0,0 +1,78 @@
+use std::any::TypeId;
+
+use linkme::distributed_slice;
+
+#[cfg(not(feature = "disabled"))]
+use crate::{StableKey, CASTERS, CASTER_MAP};
+
+/// A distributed slice gathering a [`LinkMarker`] for each caster generated by the macros,
+/// which is checked against [`CASTERS`] by [`assert_all_linked`].
+///
+/// [`LinkMarker`]: ./struct.LinkMarker.html
+/// [`CASTERS`]: ./static.CASTERS.html
+/// [`assert_all_linked`]: ./fn.assert_all_linked.html
+#[doc(hidden)]
+#[distributed_slice]
+pub static LINK_MARKERS: [fn() -> LinkMarker] = [..];
+
+/// A record that the macros have generated a caster for a cast from `S` to `T`.
+#[doc(hidden)]
+pub struct LinkMarker {
+    key: (TypeId, TypeId),
+    names: (&'static str, &'static str),
+}
+
+impl LinkMarker {
+    pub fn new<S: 'static, T: ?Sized + 'static>() -> Self {
+        LinkMarker {
+            key: (TypeId::of::<S>(), TypeId::of::<T>()),
+            names: (std::any::type_name::<S>(), std::any::type_name::<T>()),
+        }
+    }
+}
+
+/// Asserts that every caster generated by [`#[cast_to]`][cast_to], [`castable_to!`] or
+/// [`register_all!`] has made it into the registry, i.e. hasn't been stripped by the linker.
+///
+/// Each of the macros registers a marker along with a caster, in a separate distributed slice.
+/// The casters are then counted against the markers, and the casts of any marker without
+/// its caster are reported.
+///
+/// # Panics
+/// Panics listing the casts whose casters are missing.
+///
+/// [cast_to]: ./attr.cast_to.html
+/// [`castable_to!`]: ./macro.castable_to.html
+/// [`register_all!`]: ./macro.register_all.html
+#[cfg(not(feature = "disabled"))]
+pub fn assert_all_linked() {
+    if CASTERS.len() >= LINK_MARKERS.len()
+        && LINK_MARKERS
+            .iter()
+            .all(|marker| CASTER_MAP.contains(&marker().key))
+    {
+        return;
+    }
+
+    let mut missing: Vec<StableKey> = LINK_MARKERS
+        .iter()
+        .map(|marker| marker())
+        .filter(|marker| !CASTER_MAP.contains(&marker.key))
+        .map(|marker| StableKey::new(marker.names.0, marker.names.1))
+        .collect();
+    missing.sort();
+    let missing: Vec<_> = missing
+        .iter()
+        .map(|key| format!("{} => {}", key.type_name(), key.trait_name()))
+        .collect();
+    panic!(
+        "{} of {} casters are missing from the registry: {}",
+        missing.len(),
+        LINK_MARKERS.len(),
+        missing.join(", ")
+    );
+}
+
+/// Asserts nothing as casting is disabled, in which case no casters or markers are generated.
+#[cfg(feature = "disabled")]
+pub fn assert_all_linked() {}
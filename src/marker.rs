@@ -0,0 +1,51 @@
+//! Capabilities recorded with `#[cast_to(marker Trait)]` for traits that can't form trait
+//! objects, e.g. those with associated functions only.
+//!
+//! Such a trait has no `dyn Trait` type to key it, so it is keyed by the type generated for it
+//! by `#[cast_marker]`. A capability can only be tested with [`CastRef::impls_marker`], as there
+//! is nothing to cast into.
+//!
+//! [`CastRef::impls_marker`]: ../cast/trait.CastRef.html#tymethod.impls_marker
+use std::any::TypeId;
+
+#[cfg(not(feature = "disabled"))]
+use linkme::distributed_slice;
+
+#[cfg(not(feature = "disabled"))]
+use crate::registry::Map;
+#[cfg(not(feature = "disabled"))]
+use crate::Lazy;
+
+/// A type generated by `#[cast_marker]` to identify a trait that can't form trait objects.
+pub trait CastMarker: 'static {}
+
+/// A distributed slice gathering functions returning `TypeId`s of a concrete type and
+/// a [`CastMarker`] for the capabilities it is registered with.
+///
+/// [`CastMarker`]: ./trait.CastMarker.html
+#[cfg(not(feature = "disabled"))]
+#[doc(hidden)]
+#[distributed_slice]
+pub static MARKERS: [fn() -> (TypeId, TypeId)] = [..];
+
+#[cfg(not(feature = "disabled"))]
+static MARKER_MAP: Lazy<Map<(TypeId, TypeId), ()>> =
+    Lazy::new(|| MARKERS.iter().map(|f| (f(), ())).collect());
+
+/// Builds the map of the capabilities eagerly.
+#[cfg(not(feature = "disabled"))]
+pub(crate) fn init() {
+    Lazy::force(&MARKER_MAP);
+}
+
+/// Tests if a concrete type of `type_id` is registered with the capability of `marker_id`.
+#[cfg(not(feature = "disabled"))]
+pub(crate) fn contains(type_id: TypeId, marker_id: TypeId) -> bool {
+    MARKER_MAP.contains_key(&(type_id, marker_id))
+}
+
+/// Tests nothing as casting is disabled.
+#[cfg(feature = "disabled")]
+pub(crate) fn contains(_: TypeId, _: TypeId) -> bool {
+    false
+}
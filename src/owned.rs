@@ -0,0 +1,218 @@
+//! A registry of casters owned by its user, which is isolated from the global one.
+use std::any::{Any, TypeId};
+use std::fmt;
+use std::sync::RwLock;
+
+use crate::registry::Registry as Casters;
+use crate::{downcast_box, downcast_mut, downcast_ref, CastFrom, Caster, CasterEntry, OnceLock};
+
+/// A resolver of casters consulted when no caster is registered for a cast.
+///
+/// It is called with `TypeId` of the concrete type of the value being cast and `TypeId` of
+/// the target trait `T`, and returns a `Caster<T>` if it can handle the cast.
+///
+/// A returned value which isn't a `Caster<T>` is ignored, so a fallback can't make a cast
+/// produce a value of a wrong type. But it is the obligation of the fallback that the returned
+/// `Caster<T>` does handle the concrete type of the given `TypeId`; otherwise the cast panics.
+pub type CastFallback =
+    fn(type_id: TypeId, trait_id: TypeId) -> Option<&'static (dyn Any + Send + Sync)>;
+
+/// A registry of casters isolated from the global one, which lets a framework keep a table
+/// of casts per subsystem.
+///
+/// It is either declared as a static with [`registry!`], into which the casters are
+/// registered with `registry = NAME` given to [`cast_to`] or [`castable_to!`], or created
+/// with [`new`] and filled with [`register_caster`].
+///
+/// The casts in a registry are looked up only through it, and it looks up nothing else,
+/// i.e. neither the global registry, the casts allowed at runtime with `dynamic` feature,
+/// nor the `Deref` relationships. A lookup missing the casters of a registry can be bridged
+/// to elsewhere by a fallback installed with [`set_cast_fallback`].
+///
+/// # Examples
+/// ```
+/// use intertrait::*;
+///
+/// trait Source: CastFrom {}
+/// trait Greet {
+///     fn greet(&self) -> &'static str;
+/// }
+///
+/// struct Data;
+/// impl Source for Data {}
+/// impl Greet for Data {
+///     fn greet(&self) -> &'static str {
+///         "Hello"
+///     }
+/// }
+///
+/// let mut registry = Registry::new();
+/// registry.register_caster::<Data, dyn Greet>(Caster::new(
+///     |from| from.downcast_ref::<Data>().unwrap(),
+///     |from| from.downcast_mut::<Data>().unwrap(),
+///     |from| from.downcast::<Data>().unwrap(),
+///     |from| from.downcast::<Data>().unwrap(),
+/// ));
+///
+/// let source: &dyn Source = &Data;
+/// assert!(registry.impls::<dyn Greet, _>(source));
+/// assert_eq!(registry.cast_ref::<dyn Greet, _>(source).unwrap().greet(), "Hello");
+/// ```
+///
+/// [`registry!`]: ./macro.registry.html
+/// [`cast_to`]: ./attr.cast_to.html
+/// [`castable_to!`]: ./macro.castable_to.html
+/// [`new`]: #method.new
+/// [`register_caster`]: #method.register_caster
+/// [`set_cast_fallback`]: #method.set_cast_fallback
+pub struct Registry {
+    entries: fn() -> &'static [CasterEntry],
+    casters: OnceLock<Casters>,
+    fallback: RwLock<Option<CastFallback>>,
+}
+
+impl Registry {
+    /// Creates an empty registry.
+    pub const fn new() -> Self {
+        Registry::from_entries(|| &[])
+    }
+
+    /// Creates a registry with the casters referred to by the entries returned by `entries`,
+    /// e.g. a distributed slice, which is called on the first lookup.
+    ///
+    /// # Panics
+    /// The first lookup panics if any of the entries is of another version.
+    pub const fn from_entries(entries: fn() -> &'static [CasterEntry]) -> Self {
+        Registry {
+            entries,
+            casters: OnceLock::new(),
+            fallback: RwLock::new(None),
+        }
+    }
+
+    /// Registers `caster` for casting from a concrete type `S` to a trait `T`.
+    ///
+    /// If a caster for the same pair of `S` and `T` has already been registered, it is
+    /// replaced with the new one.
+    pub fn register_caster<S: 'static, T: ?Sized + 'static>(&mut self, caster: Caster<T>) {
+        let caster = Caster {
+            type_name: std::any::type_name::<S>(),
+            ..caster
+        };
+        self.casters();
+        self.casters
+            .get_mut()
+            .unwrap()
+            .insert(TypeId::of::<S>(), Box::new(caster));
+    }
+
+    /// Installs `fallback` to be consulted when this registry has no caster for a cast,
+    /// e.g. to bridge to the registry of another crate or a scripting layer. It replaces
+    /// the one previously installed.
+    ///
+    /// It is for this registry only; the fallback of the global one is installed with
+    /// [`dynamic::set_cast_fallback`].
+    ///
+    /// [`dynamic::set_cast_fallback`]: ./dynamic/fn.set_cast_fallback.html
+    pub fn set_cast_fallback(&self, fallback: CastFallback) {
+        *self.fallback.write().unwrap() = Some(fallback);
+    }
+
+    /// Uninstalls the fallback installed with [`set_cast_fallback`] if any.
+    ///
+    /// [`set_cast_fallback`]: #method.set_cast_fallback
+    pub fn clear_cast_fallback(&self) {
+        *self.fallback.write().unwrap() = None;
+    }
+
+    /// Casts `source` into a reference to type `T` with the casters in this registry.
+    pub fn cast_ref<'a, T: ?Sized + 'static, S: ?Sized + CastFrom>(
+        &self,
+        source: &'a S,
+    ) -> Option<&'a T> {
+        let any = source.ref_any();
+        if let Some(target) = downcast_ref::<T>(any) {
+            return Some(target);
+        }
+        let caster = self.caster::<T>(any.type_id())?;
+        Some((caster.cast_ref)(any))
+    }
+
+    /// Casts `source` into a mutable reference to type `T` with the casters in this registry.
+    pub fn cast_mut<'a, T: ?Sized + 'static, S: ?Sized + CastFrom>(
+        &self,
+        source: &'a mut S,
+    ) -> Option<&'a mut T> {
+        let any = source.mut_any();
+        let type_id = (*any).type_id();
+        if type_id == TypeId::of::<T>() {
+            return downcast_mut::<T>(any);
+        }
+        let caster = self.caster::<T>(type_id)?;
+        Some((caster.cast_mut)(any))
+    }
+
+    /// Casts `source` into a `Box` of type `T` with the casters in this registry.
+    /// If fails, returns `source`.
+    pub fn cast_box<T: ?Sized + 'static, S: ?Sized + CastFrom>(
+        &self,
+        source: Box<S>,
+    ) -> Result<Box<T>, Box<S>> {
+        let type_id = (*source).type_id();
+        if type_id == TypeId::of::<T>() {
+            return Ok(downcast_box::<T>(source.box_any()).ok().unwrap());
+        }
+        match self.caster::<T>(type_id) {
+            Some(caster) => Ok((caster.cast_box)(source.box_any())),
+            None => Err(source),
+        }
+    }
+
+    /// Tests if `source` can be cast into type `T` with the casters in this registry.
+    pub fn impls<T: ?Sized + 'static, S: ?Sized + CastFrom>(&self, source: &S) -> bool {
+        let type_id = (*source).type_id();
+        let key = (type_id, TypeId::of::<T>());
+        type_id == TypeId::of::<T>()
+            || self.casters().contains(&key)
+            || self
+                .fallback(&key)
+                .is_some_and(|caster| caster.is::<Caster<T>>())
+    }
+
+    /// Returns a `Caster<T>` for a concrete type of `type_id`, which is resolved by
+    /// the fallback if this registry has none.
+    fn caster<T: ?Sized + 'static>(&self, type_id: TypeId) -> Option<Caster<T>> {
+        self.casters().caster::<T>(type_id).or_else(|| {
+            self.fallback(&(type_id, TypeId::of::<T>()))
+                .and_then(|caster| caster.downcast_ref::<Caster<T>>())
+                .copied()
+        })
+    }
+
+    /// Returns the caster for a pair of `TypeId`s of a concrete type and a trait resolved by
+    /// the fallback, which is to be downcast into `Caster<T>` by the caller.
+    fn fallback(&self, key: &(TypeId, TypeId)) -> Option<&'static (dyn Any + Send + Sync)> {
+        let fallback = (*self.fallback.read().unwrap())?;
+        fallback(key.0, key.1)
+    }
+
+    fn casters(&self) -> &Casters {
+        self.casters.get_or_init(|| {
+            Casters::from_slice((self.entries)()).unwrap_or_else(|err| panic!("{}", err))
+        })
+    }
+}
+
+impl Default for Registry {
+    fn default() -> Self {
+        Registry::new()
+    }
+}
+
+impl fmt::Debug for Registry {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Registry")
+            .field("casts", &self.casters().entries().count())
+            .finish()
+    }
+}
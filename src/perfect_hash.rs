@@ -0,0 +1,158 @@
+//! A map keyed by `TypeId` with a perfect hash index, which holds the casters of a registry
+//! with `perfect-hash` feature.
+use std::any::TypeId;
+use std::cmp::Reverse;
+use std::hash::BuildHasher;
+
+use crate::hasher::BuildTypeIdHasher;
+
+/// The average number of keys sharing a seed in the index.
+const BUCKET_SIZE: usize = 4;
+
+/// The number of seeds tried for a bucket before giving up and retrying with more slots.
+const MAX_SEED: u32 = 1 << 16;
+
+/// A slot of the index pointing at no entry.
+const EMPTY: u32 = u32::MAX;
+
+/// A map from `TypeId` to `V` looked up with a single probe into an index built by
+/// hash-and-displace, where the keys are grouped into buckets and each bucket is given
+/// a seed placing all of its keys in distinct empty slots.
+///
+/// Keys inserted after the index is built are scanned linearly until [`reindex`] is called,
+/// which is meant to be done once after inserting a batch of keys.
+///
+/// [`reindex`]: #method.reindex
+#[derive(Default)]
+pub(crate) struct PerfectMap<V> {
+    entries: Vec<(TypeId, V)>,
+    seeds: Vec<u32>,
+    slots: Vec<u32>,
+    indexed: usize,
+}
+
+/// A view into the entry of a key in a `PerfectMap`, which is either occupied or vacant.
+pub(crate) struct Entry<'a, V> {
+    map: &'a mut PerfectMap<V>,
+    key: TypeId,
+}
+
+impl<'a, V: Default> Entry<'a, V> {
+    /// Returns the value of the key, inserting the default value if the key is vacant.
+    pub fn or_default(self) -> &'a mut V {
+        let index = match self.map.position(&self.key) {
+            Some(index) => index,
+            None => {
+                self.map.entries.push((self.key, V::default()));
+                self.map.entries.len() - 1
+            }
+        };
+        &mut self.map.entries[index].1
+    }
+}
+
+impl<V> PerfectMap<V> {
+    /// Returns the value of `key`.
+    pub fn get(&self, key: &TypeId) -> Option<&V> {
+        self.position(key).map(|index| &self.entries[index].1)
+    }
+
+    /// Returns the entry of `key` for in-place manipulation.
+    pub fn entry(&mut self, key: TypeId) -> Entry<'_, V> {
+        Entry { map: self, key }
+    }
+
+    /// Returns the pairs of the keys and the values in the order of insertion.
+    pub fn iter(&self) -> impl Iterator<Item = (&TypeId, &V)> {
+        self.entries.iter().map(|(key, value)| (key, value))
+    }
+
+    /// Rebuilds the index to cover all the keys inserted so far.
+    pub fn reindex(&mut self) {
+        if self.indexed == self.entries.len() {
+            return;
+        }
+        let hashes: Vec<u64> = self.entries.iter().map(|(key, _)| hash(key)).collect();
+        let mut len = hashes.len();
+        loop {
+            if let Some((seeds, slots)) = build_index(&hashes, len) {
+                self.seeds = seeds;
+                self.slots = slots;
+                break;
+            }
+            len += len / 8 + 1;
+        }
+        self.indexed = self.entries.len();
+    }
+
+    fn position(&self, key: &TypeId) -> Option<usize> {
+        if !self.slots.is_empty() {
+            let hash = hash(key);
+            let seed = self.seeds[bucket_of(hash, self.seeds.len())];
+            let index = self.slots[slot_of(hash, seed, self.slots.len())];
+            if index != EMPTY && self.entries[index as usize].0 == *key {
+                return Some(index as usize);
+            }
+        }
+        self.entries[self.indexed..]
+            .iter()
+            .position(|(k, _)| k == key)
+            .map(|index| self.indexed + index)
+    }
+}
+
+/// Builds the seeds of the buckets and the slots pointing at the keys of `hashes`, unless
+/// a bucket fits in none of `len` slots with any of the seeds tried.
+fn build_index(hashes: &[u64], len: usize) -> Option<(Vec<u32>, Vec<u32>)> {
+    let bucket_count = hashes.len().div_ceil(BUCKET_SIZE);
+    let mut buckets = vec![Vec::new(); bucket_count];
+    for (index, hash) in hashes.iter().enumerate() {
+        buckets[bucket_of(*hash, bucket_count)].push(index);
+    }
+    // Larger buckets are harder to place, so they are placed while more slots are empty.
+    let mut order: Vec<usize> = (0..bucket_count).collect();
+    order.sort_by_key(|bucket| Reverse(buckets[*bucket].len()));
+
+    let mut seeds = vec![0; bucket_count];
+    let mut slots = vec![EMPTY; len];
+    let mut placed = Vec::with_capacity(BUCKET_SIZE);
+    for bucket in order {
+        let keys = &buckets[bucket];
+        if keys.is_empty() {
+            break;
+        }
+        seeds[bucket] = (0..MAX_SEED).find(|seed| {
+            placed.clear();
+            keys.iter().all(|index| {
+                let slot = slot_of(hashes[*index], *seed, len);
+                let vacant = slots[slot] == EMPTY && !placed.contains(&slot);
+                placed.push(slot);
+                vacant
+            })
+        })?;
+        for (index, slot) in keys.iter().zip(&placed) {
+            slots[*slot] = *index as u32;
+        }
+    }
+    Some((seeds, slots))
+}
+
+fn hash(key: &TypeId) -> u64 {
+    BuildTypeIdHasher::default().hash_one(key)
+}
+
+fn bucket_of(hash: u64, bucket_count: usize) -> usize {
+    (mix(hash, 0) % bucket_count as u64) as usize
+}
+
+fn slot_of(hash: u64, seed: u32, len: usize) -> usize {
+    (mix(hash, u64::from(seed) + 1) % len as u64) as usize
+}
+
+/// Scrambles `hash` with `seed` by the finalizer of SplitMix64.
+fn mix(hash: u64, seed: u64) -> u64 {
+    let mut z = hash ^ seed.wrapping_mul(0x9e37_79b9_7f4a_7c15);
+    z = (z ^ (z >> 30)).wrapping_mul(0xbf58_476d_1ce4_e5b9);
+    z = (z ^ (z >> 27)).wrapping_mul(0x94d0_49bb_1331_11eb);
+    z ^ (z >> 31)
+}
@@ -0,0 +1,153 @@
+//! `plugin` module loads a dynamic library (e.g. a plugin) and merges the casters registered
+//! in it into the registry, which is enabled with `plugin` feature.
+//!
+//! The casters registered with `#[cast_to]` in a library are gathered into its own copy of
+//! the registry, which the host can't see. The library exports them with [`export_casters!`],
+//! and the host loads it with [`load`], after which the casts in the library are visible to
+//! the host as well.
+//!
+//! ```ignore
+//! // In the plugin, which is a crate of `crate-type = ["cdylib"]`
+//! intertrait::export_casters!();
+//!
+//! // In the host
+//! let plugin = unsafe { intertrait::plugin::load("path/to/libplugin.so")? };
+//!
+//! // On reloading the plugin
+//! unsafe { plugin.unload() };
+//! let plugin = unsafe { intertrait::plugin::load("path/to/libplugin.so")? };
+//! ```
+//!
+//! The host and the plugin must be built with the same compiler, as the exported function
+//! and the caster entries aren't of a stable ABI. Entries built against an incompatible
+//! version of this crate are refused, as [`merge_slice`] does.
+//!
+//! [`export_casters!`]: ../macro.export_casters.html
+//! [`load`]: ./fn.load.html
+//! [`merge_slice`]: ../dynamic/fn.merge_slice.html
+use std::error::Error;
+use std::ffi::OsStr;
+use std::fmt;
+use std::mem::ManuallyDrop;
+
+use libloading::Library;
+
+use crate::dynamic::{merge_slice_revocable, MergeError, RegistrationToken};
+use crate::CasterEntry;
+
+/// The name of the function exported by [`export_casters!`], which returns the caster entries
+/// of the library.
+///
+/// [`export_casters!`]: ../macro.export_casters.html
+pub const CASTERS_SYMBOL: &str = "__intertrait_casters";
+
+/// A dynamic library loaded with [`load`], whose casters are merged into the registry.
+///
+/// Dropping it unregisters the casters, but the library stays loaded, since values of
+/// the types in it may still be around. Call [`unload`] to unload it, or [`keep`] to keep
+/// the casters registered for good.
+///
+/// [`load`]: ./fn.load.html
+/// [`unload`]: #method.unload
+/// [`keep`]: #method.keep
+pub struct Plugin {
+    library: ManuallyDrop<Library>,
+    token: RegistrationToken,
+}
+
+impl Plugin {
+    /// Returns the library, which is meant for looking up the other symbols in it.
+    pub fn library(&self) -> &Library {
+        &self.library
+    }
+
+    /// Keeps the casters of the plugin registered and the library loaded for good.
+    pub fn keep(self) {
+        self.token.keep();
+    }
+
+    /// Unregisters the casters of the plugin and unloads the library, after which
+    /// the plugin can be loaded again.
+    ///
+    /// # Safety
+    /// Nothing referring to the code or the data in the library may be used afterwards,
+    /// including values of the types in it, trait objects for them and the casters for them
+    /// held elsewhere, e.g. by [`CasterHandle`].
+    ///
+    /// [`CasterHandle`]: ../cast/struct.CasterHandle.html
+    pub unsafe fn unload(self) {
+        let Plugin { library, token } = self;
+        token.revoke();
+        drop(ManuallyDrop::into_inner(library));
+    }
+}
+
+impl fmt::Debug for Plugin {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Plugin")
+            .field("token", &self.token)
+            .finish()
+    }
+}
+
+/// The reason why [`load`] failed to load a plugin.
+///
+/// [`load`]: ./fn.load.html
+#[derive(Debug)]
+pub enum PluginError {
+    /// The library couldn't be opened, or it exports no [`CASTERS_SYMBOL`].
+    ///
+    /// [`CASTERS_SYMBOL`]: ./constant.CASTERS_SYMBOL.html
+    Load(libloading::Error),
+
+    /// The casters in the library couldn't be merged, in which case nothing is merged.
+    Merge(MergeError),
+}
+
+impl fmt::Display for PluginError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            PluginError::Load(err) => write!(f, "failed to load a plugin: {}", err),
+            PluginError::Merge(err) => {
+                write!(f, "failed to merge the casters of a plugin: {}", err)
+            }
+        }
+    }
+}
+
+impl Error for PluginError {
+    fn source(&self) -> Option<&(dyn Error + 'static)> {
+        match self {
+            PluginError::Load(err) => Some(err),
+            PluginError::Merge(err) => Some(err),
+        }
+    }
+}
+
+/// Loads the dynamic library at `path`, and merges the casters it exports with
+/// [`export_casters!`] into the registry.
+///
+/// Fails if any of the casts in the library has already been registered, e.g. by loading
+/// the same library twice without unloading it, in which case nothing is merged and
+/// the library is unloaded.
+///
+/// # Safety
+/// Loading a library runs its initialization routines, and calls the function exported as
+/// [`CASTERS_SYMBOL`], both of which must be sound. The function must be the one exported by
+/// [`export_casters!`] in a library built with the same compiler as the host.
+///
+/// [`export_casters!`]: ../macro.export_casters.html
+/// [`CASTERS_SYMBOL`]: ./constant.CASTERS_SYMBOL.html
+pub unsafe fn load<P: AsRef<OsStr>>(path: P) -> Result<Plugin, PluginError> {
+    let library = Library::new(path).map_err(PluginError::Load)?;
+    let token = {
+        let casters = library
+            .get::<fn() -> &'static [CasterEntry]>(CASTERS_SYMBOL.as_bytes())
+            .map_err(PluginError::Load)?;
+        merge_slice_revocable(casters()).map_err(PluginError::Merge)?
+    };
+    Ok(Plugin {
+        library: ManuallyDrop::new(library),
+        token,
+    })
+}
@@ -0,0 +1,28 @@
+//! `prelude` module re-exports what's needed for casting in most cases, including the traits
+//! providing `cast` methods and the macros registering target traits.
+//!
+//! ```
+//! use intertrait::prelude::*;
+//!
+//! # trait Greet {
+//! #     fn greet(&self);
+//! # }
+//! #[cast_to(Greet)]
+//! struct Data;
+//! # impl Greet for Data {
+//! #    fn greet(&self) {
+//! #        println!("Hello");
+//! #    }
+//! # }
+//! trait Source: CastFrom {}
+//! impl Source for Data {}
+//!
+//! let source: &dyn Source = &Data;
+//! source.cast::<dyn Greet>().unwrap().greet();
+//! ```
+pub use crate::cast::{CastArc, CastBox, CastMut, CastRc, CastRef};
+pub use crate::{
+    cast, cast_marker, cast_to, castable_to, closed_cast, match_cast, query, register_all, registry,
+};
+pub use crate::{cast_box, cast_mut, cast_ref};
+pub use crate::{CastFrom, CastFromSync};
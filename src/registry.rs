@@ -0,0 +1,482 @@
+use std::any::TypeId;
+use std::cmp::Ordering;
+#[cfg(feature = "btree")]
+use std::collections::BTreeMap;
+#[cfg(not(feature = "btree"))]
+use std::collections::HashMap;
+use std::error::Error;
+use std::fmt;
+use std::hash::{Hash, Hasher};
+use std::ops::Deref;
+
+use crate::hasher::stable_hash;
+#[cfg(not(feature = "btree"))]
+use crate::hasher::BuildTypeIdHasher;
+#[cfg(feature = "perfect-hash")]
+use crate::perfect_hash::PerfectMap;
+#[cfg(feature = "sorted-table")]
+use crate::sorted_table::SortedMap;
+use crate::{AnyCaster, BoxedCaster, Caster};
+
+/// The map used by a `Registry`, which is a `BTreeMap` with `btree` feature.
+#[cfg(not(feature = "btree"))]
+pub(crate) type Map<K, V> = HashMap<K, V, BuildTypeIdHasher>;
+
+/// The map used by a `Registry`, which is a `BTreeMap` with `btree` feature.
+#[cfg(feature = "btree")]
+pub(crate) type Map<K, V> = BTreeMap<K, V>;
+
+/// The map keyed by `TypeId` used by a `Registry`, which is a sorted `Vec` with
+/// `sorted-table` feature.
+#[cfg(not(feature = "sorted-table"))]
+type TypeMap<V> = Map<TypeId, V>;
+
+/// The map keyed by `TypeId` used by a `Registry`, which is a sorted `Vec` with
+/// `sorted-table` feature.
+#[cfg(feature = "sorted-table")]
+type TypeMap<V> = SortedMap<V>;
+
+/// The table of casters for each concrete type in a `Registry`, which is indexed with
+/// a perfect hash with `perfect-hash` feature, taking precedence over `sorted-table`.
+#[cfg(not(feature = "perfect-hash"))]
+type CasterTable = TypeMap<Vec<(TypeId, StoredCaster)>>;
+
+/// The table of casters for each concrete type in a `Registry`, which is indexed with
+/// a perfect hash with `perfect-hash` feature, taking precedence over `sorted-table`.
+#[cfg(feature = "perfect-hash")]
+type CasterTable = PerfectMap<Vec<(TypeId, StoredCaster)>>;
+
+/// The version of the format of [`CasterEntry`], which is bumped whenever the entry or
+/// the layout of the caster it refers to changes incompatibly.
+///
+/// [`CasterEntry`]: ./struct.CasterEntry.html
+pub const CASTER_ABI_VERSION: u32 = 2;
+
+/// An entry of [`CASTERS`], which refers to a static caster for a concrete type of `TypeId`
+/// returned by a function, tagged with the version of the format it is built in.
+///
+/// The macros build both the entry and the caster in place as statics, so gathering them
+/// into the registry involves no allocation of the casters.
+///
+/// A slice of entries exposed by a dynamically loaded library (e.g. a plugin) may have
+/// been built against another version of this crate, in which case the `Caster<T>` behind
+/// the reference and the vtable of [`AnyCaster`] may be laid out differently. Touching
+/// such a caster is undefined behavior, so the version is checked before anything else,
+/// and an entry of another version is refused without being touched.
+///
+/// # ABI guarantees
+/// Across the versions of this crate:
+/// - The entry is `#[repr(C)]` and the version is its first field, always a `u32`.
+/// - `CASTER_ABI_VERSION` is bumped whenever anything else in the entry changes, including
+///   the layout of `Caster<T>` and the methods of `AnyCaster`.
+///
+/// Nothing is guaranteed about the layout of the rest of the entry, which is only read
+/// when the versions match. The host and the library must still be built with the same
+/// compiler, since neither the calling convention of `fn() -> TypeId` nor the layout
+/// of trait objects is stable across compilers.
+///
+/// # Pinning
+/// Creating an entry is `unsafe`, since the casters referred to by entries are trusted not to
+/// move the value being cast, which [`CastBox::cast_pin`] and [`CastMut::cast_pin`] rely on.
+/// Casters registered otherwise, e.g. with `dynamic::register_caster`, are never used for
+/// casting a pinned value.
+///
+/// [`CASTERS`]: ./static.CASTERS.html
+/// [`AnyCaster`]: ./trait.AnyCaster.html
+/// [`CastBox::cast_pin`]: ./cast/trait.CastBox.html#tymethod.cast_pin
+/// [`CastMut::cast_pin`]: ./cast/trait.CastMut.html#tymethod.cast_pin
+#[repr(C)]
+pub struct CasterEntry {
+    version: u32,
+    type_id: fn() -> TypeId,
+    caster: &'static dyn AnyCaster,
+}
+
+impl CasterEntry {
+    /// Creates an entry of the current version.
+    ///
+    /// # Safety
+    /// The `cast_box` of the caster must return the allocation of its argument as is, and
+    /// neither `cast_box` nor `cast_mut` may move the value out of its argument, e.g. with
+    /// `std::mem::replace`. The casters generated by the macros, which only coerce the value
+    /// downcast into the concrete type, satisfy it.
+    pub const unsafe fn new(type_id: fn() -> TypeId, caster: &'static dyn AnyCaster) -> Self {
+        Self::with_version(CASTER_ABI_VERSION, type_id, caster)
+    }
+
+    /// Creates an entry tagged with `version`, which simulates an entry built against
+    /// another version of this crate.
+    ///
+    /// # Safety
+    /// The same as [`new`].
+    ///
+    /// [`new`]: #method.new
+    pub const unsafe fn with_version(
+        version: u32,
+        type_id: fn() -> TypeId,
+        caster: &'static dyn AnyCaster,
+    ) -> Self {
+        CasterEntry {
+            version,
+            type_id,
+            caster,
+        }
+    }
+
+    /// Returns the version of the format the entry is built in.
+    pub fn version(&self) -> u32 {
+        self.version
+    }
+
+    /// Returns `TypeId` of the concrete type and the caster, only if the entry is built in
+    /// the current version. Otherwise, returns the mismatch.
+    pub(crate) fn parts(&self) -> Result<(TypeId, &'static dyn AnyCaster), VersionMismatch> {
+        if self.version != CASTER_ABI_VERSION {
+            return Err(VersionMismatch {
+                found: self.version,
+            });
+        }
+        Ok(((self.type_id)(), self.caster))
+    }
+}
+
+/// An error telling that an entry of casters was built in a version of the format other than
+/// [`CASTER_ABI_VERSION`], typically by a library built against another version of this crate.
+///
+/// [`CASTER_ABI_VERSION`]: ./constant.CASTER_ABI_VERSION.html
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct VersionMismatch {
+    found: u32,
+}
+
+impl VersionMismatch {
+    /// Returns the version of the refused entry.
+    pub fn found(&self) -> u32 {
+        self.found
+    }
+
+    /// Returns the version expected, which is `CASTER_ABI_VERSION`.
+    pub fn expected(&self) -> u32 {
+        CASTER_ABI_VERSION
+    }
+}
+
+impl fmt::Display for VersionMismatch {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "a caster entry of version {} can't be used with version {} of the entry format; \
+             rebuild the library providing it against the same version of intertrait",
+            self.found,
+            self.expected()
+        )
+    }
+}
+
+impl Error for VersionMismatch {}
+
+/// A key identifying a cast from a concrete type to a trait, which is ordered stably
+/// across builds and runs unlike a pair of `TypeId`s.
+///
+/// It is ordered first by a hash of the names of the concrete type and the trait computed
+/// with a fixed hash function, and then by the names themselves. The label of the cast
+/// it carries doesn't take part in comparisons.
+#[derive(Clone, Copy, Debug)]
+pub struct StableKey {
+    hash: u64,
+    type_name: &'static str,
+    trait_name: &'static str,
+    label: Option<&'static str>,
+}
+
+impl StableKey {
+    /// Creates a key for a cast from a concrete type to a trait given their names.
+    pub fn new(type_name: &'static str, trait_name: &'static str) -> Self {
+        let hash = stable_hash(&[type_name.as_bytes(), &[0], trait_name.as_bytes()]);
+        StableKey {
+            hash,
+            type_name,
+            trait_name,
+            label: None,
+        }
+    }
+
+    /// Returns the stable hash of the names.
+    pub fn hash(&self) -> u64 {
+        self.hash
+    }
+
+    /// Returns the name of the concrete type.
+    pub fn type_name(&self) -> &'static str {
+        self.type_name
+    }
+
+    /// Returns the name of the trait.
+    pub fn trait_name(&self) -> &'static str {
+        self.trait_name
+    }
+
+    /// Returns the label given with `#[cast_to(label = "...")]` if any.
+    pub fn label(&self) -> Option<&'static str> {
+        self.label
+    }
+
+    fn ordered(&self) -> (u64, &'static str, &'static str) {
+        (self.hash, self.type_name, self.trait_name)
+    }
+}
+
+impl PartialEq for StableKey {
+    fn eq(&self, other: &Self) -> bool {
+        self.ordered() == other.ordered()
+    }
+}
+
+impl Eq for StableKey {}
+
+impl PartialOrd for StableKey {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl Ord for StableKey {
+    fn cmp(&self, other: &Self) -> Ordering {
+        self.ordered().cmp(&other.ordered())
+    }
+}
+
+impl Hash for StableKey {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        self.ordered().hash(state)
+    }
+}
+
+/// A table of [`Caster<T>`]s for each concrete type, each of which is keyed by `TypeId` of
+/// trait `T`, along with a reverse index from trait `T` to the concrete types.
+///
+/// Most types are cast into only a handful of traits, so scanning the short table of a type
+/// is cheaper than hashing a pair of `TypeId`s. Keying by `T` rather than `Caster<T>` lets
+/// a lookup of whether a cast is registered involve no `Caster<T>` at all.
+///
+/// [`Caster<T>`]: ./struct.Caster.html
+#[derive(Default)]
+pub(crate) struct Registry {
+    casters: CasterTable,
+    sources: TypeMap<Vec<TypeId>>,
+}
+
+/// A caster held by a [`Registry`], which is either a static one referred to by
+/// a [`CasterEntry`] or one registered at runtime.
+///
+/// Only the former is trusted not to move the value being cast, as promised on creating
+/// the entry, so only it is used for casting a pinned value.
+///
+/// [`Registry`]: ./struct.Registry.html
+/// [`CasterEntry`]: ./struct.CasterEntry.html
+enum StoredCaster {
+    Static(&'static dyn AnyCaster),
+    Boxed(BoxedCaster),
+}
+
+impl Clone for StoredCaster {
+    fn clone(&self) -> Self {
+        match self {
+            StoredCaster::Static(caster) => StoredCaster::Static(*caster),
+            StoredCaster::Boxed(caster) => StoredCaster::Boxed(caster.clone_box()),
+        }
+    }
+}
+
+impl Deref for StoredCaster {
+    type Target = dyn AnyCaster;
+
+    fn deref(&self) -> &Self::Target {
+        match self {
+            StoredCaster::Static(caster) => *caster,
+            StoredCaster::Boxed(caster) => &**caster,
+        }
+    }
+}
+
+/// How to resolve a conflict of casters for the same cast in [`Registry::extend_from`].
+///
+/// [`Registry::extend_from`]: ./struct.Registry.html#method.extend_from
+#[cfg(feature = "dynamic")]
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub(crate) enum ConflictPolicy {
+    /// The caster from the other registry replaces the one in this registry.
+    Override,
+
+    /// Nothing is merged if any conflict exists, and the conflicts are reported.
+    Error,
+}
+
+impl Registry {
+    /// Creates a registry with the casters referred to by the entries in `slice`.
+    ///
+    /// Fails if any of the entries is of another version, in which case none is used.
+    pub fn from_slice(slice: &[CasterEntry]) -> Result<Self, VersionMismatch> {
+        let mut parts = Vec::with_capacity(slice.len());
+        let mut mismatch = None;
+        for entry in slice {
+            match entry.parts() {
+                Ok(part) => parts.push(part),
+                Err(err) => mismatch = mismatch.or(Some(err)),
+            }
+        }
+        if let Some(err) = mismatch {
+            return Err(err);
+        }
+        let mut registry = Registry::default();
+        for (type_id, caster) in parts {
+            registry.insert_stored(type_id, StoredCaster::Static(caster));
+        }
+        registry.reindex();
+        Ok(registry)
+    }
+
+    /// Merges the casters in `other` into this registry, resolving the conflicts with those
+    /// for the same casts in this registry by `policy`.
+    ///
+    /// Returns the keys of the conflicting casts as an error only with `ConflictPolicy::Error`,
+    /// in which case this registry is left untouched.
+    #[cfg(feature = "dynamic")]
+    pub fn extend_from(
+        &mut self,
+        other: &Registry,
+        policy: ConflictPolicy,
+    ) -> Result<(), Vec<StableKey>> {
+        if policy == ConflictPolicy::Error {
+            let mut conflicts: Vec<StableKey> = other
+                .entries()
+                .filter(|(id, _)| self.contains(id))
+                .map(|(_, key)| key)
+                .collect();
+            if !conflicts.is_empty() {
+                conflicts.sort();
+                return Err(conflicts);
+            }
+        }
+        for (type_id, table) in other.casters.iter() {
+            for (_, caster) in table {
+                self.insert_stored(*type_id, caster.clone());
+            }
+        }
+        self.reindex();
+        Ok(())
+    }
+
+    /// Inserts `caster` for a concrete type of `type_id`, replacing the one already inserted
+    /// for the same pair of the concrete type and the target trait.
+    pub fn insert(&mut self, type_id: TypeId, caster: BoxedCaster) {
+        self.insert_stored(type_id, StoredCaster::Boxed(caster));
+        self.reindex();
+    }
+
+    fn insert_stored(&mut self, type_id: TypeId, caster: StoredCaster) {
+        let trait_id = caster.trait_id();
+        let table = self.casters.entry(type_id).or_default();
+        match table.iter_mut().find(|(id, _)| *id == trait_id) {
+            Some(entry) => entry.1 = caster,
+            None => {
+                table.push((trait_id, caster));
+                self.sources.entry(trait_id).or_default().push(type_id);
+            }
+        }
+    }
+
+    /// Removes the caster for a pair of `TypeId`s of a concrete type and a trait, returning
+    /// whether there was one.
+    #[cfg(feature = "dynamic")]
+    pub fn remove(&mut self, &(type_id, trait_id): &(TypeId, TypeId)) -> bool {
+        if !self.contains(&(type_id, trait_id)) {
+            return false;
+        }
+        // The keys are left in the outer maps, which keeps their indices valid.
+        self.casters
+            .entry(type_id)
+            .or_default()
+            .retain(|(id, _)| *id != trait_id);
+        self.sources
+            .entry(trait_id)
+            .or_default()
+            .retain(|id| *id != type_id);
+        true
+    }
+
+    /// Rebuilds the indices of the tables after inserting casters, if any.
+    fn reindex(&mut self) {
+        #[cfg(any(feature = "perfect-hash", feature = "sorted-table"))]
+        self.casters.reindex();
+        #[cfg(feature = "sorted-table")]
+        self.sources.reindex();
+    }
+
+    /// Returns the caster for a pair of `TypeId`s of a concrete type and a trait.
+    fn get(&self, &(type_id, trait_id): &(TypeId, TypeId)) -> Option<&StoredCaster> {
+        self.casters
+            .get(&type_id)?
+            .iter()
+            .find(|(id, _)| *id == trait_id)
+            .map(|(_, caster)| caster)
+    }
+
+    /// Returns a `Caster<T>` for a concrete type of `type_id`.
+    pub fn caster<T: ?Sized + 'static>(&self, type_id: TypeId) -> Option<Caster<T>> {
+        self.get(&(type_id, TypeId::of::<T>()))
+            .and_then(|caster| caster.as_any().downcast_ref::<Caster<T>>())
+            .copied()
+    }
+
+    /// Returns a `Caster<T>` for a concrete type of `type_id` only if it is referred to by
+    /// a [`CasterEntry`], which is trusted not to move the value being cast.
+    ///
+    /// [`CasterEntry`]: ./struct.CasterEntry.html
+    pub fn pin_caster<T: ?Sized + 'static>(&self, type_id: TypeId) -> Option<Caster<T>> {
+        match self.get(&(type_id, TypeId::of::<T>()))? {
+            StoredCaster::Static(caster) => caster.as_any().downcast_ref::<Caster<T>>().copied(),
+            StoredCaster::Boxed(_) => None,
+        }
+    }
+
+    /// Tests if there is a caster for a pair of `TypeId`s of a concrete type and a trait.
+    pub fn contains(&self, key: &(TypeId, TypeId)) -> bool {
+        self.get(key).is_some()
+    }
+
+    /// Returns `TypeId`s of the concrete types having a caster into the trait of `trait_id`.
+    pub fn sources(&self, trait_id: TypeId) -> &[TypeId] {
+        self.sources.get(&trait_id).map_or(&[], Vec::as_slice)
+    }
+
+    /// Returns `TypeId`s and the names of the traits a concrete type of `type_id` has
+    /// a caster into.
+    pub fn traits(&self, type_id: TypeId) -> impl Iterator<Item = (TypeId, &'static str)> + '_ {
+        self.casters
+            .get(&type_id)
+            .into_iter()
+            .flatten()
+            .map(|(trait_id, caster)| (*trait_id, caster.names().1))
+    }
+
+    /// Returns the keys of all the casts in this registry.
+    pub fn stable_keys(&self) -> impl Iterator<Item = StableKey> + '_ {
+        self.entries().map(|(_, key)| key)
+    }
+
+    /// Returns pairs of `TypeId`s of a concrete type and a trait along with
+    /// the stable keys of all the casts in this registry.
+    pub fn entries(&self) -> impl Iterator<Item = ((TypeId, TypeId), StableKey)> + '_ {
+        self.casters.iter().flat_map(|(type_id, table)| {
+            table.iter().map(move |(trait_id, caster)| {
+                let (type_name, trait_name) = caster.names();
+                let key = StableKey {
+                    label: caster.label(),
+                    ..StableKey::new(type_name, trait_name)
+                };
+                ((*type_id, *trait_id), key)
+            })
+        })
+    }
+}
@@ -0,0 +1,75 @@
+//! A map keyed by `TypeId` backed by a sorted `Vec`, which holds the casters of a registry
+//! with `sorted-table` feature.
+use std::any::TypeId;
+
+/// A map from `TypeId` to `V` held in a `Vec` sorted by the keys, which is looked up with
+/// a binary search and takes no more memory than the entries themselves.
+///
+/// Keys inserted after the entries are sorted are scanned linearly until [`reindex`] is called,
+/// which is meant to be done once after inserting a batch of keys.
+///
+/// [`reindex`]: #method.reindex
+#[derive(Default)]
+pub(crate) struct SortedMap<V> {
+    entries: Vec<(TypeId, V)>,
+    sorted: usize,
+}
+
+/// A view into the entry of a key in a `SortedMap`, which is either occupied or vacant.
+pub(crate) struct Entry<'a, V> {
+    map: &'a mut SortedMap<V>,
+    key: TypeId,
+}
+
+impl<'a, V: Default> Entry<'a, V> {
+    /// Returns the value of the key, inserting the default value if the key is vacant.
+    pub fn or_default(self) -> &'a mut V {
+        let index = match self.map.position(&self.key) {
+            Some(index) => index,
+            None => {
+                self.map.entries.push((self.key, V::default()));
+                self.map.entries.len() - 1
+            }
+        };
+        &mut self.map.entries[index].1
+    }
+}
+
+impl<V> SortedMap<V> {
+    /// Returns the value of `key`.
+    pub fn get(&self, key: &TypeId) -> Option<&V> {
+        self.position(key).map(|index| &self.entries[index].1)
+    }
+
+    /// Returns the entry of `key` for in-place manipulation.
+    pub fn entry(&mut self, key: TypeId) -> Entry<'_, V> {
+        Entry { map: self, key }
+    }
+
+    /// Returns the pairs of the keys and the values in the order of the keys, followed by
+    /// those inserted since the last sort.
+    #[cfg_attr(feature = "perfect-hash", allow(dead_code))]
+    pub fn iter(&self) -> impl Iterator<Item = (&TypeId, &V)> {
+        self.entries.iter().map(|(key, value)| (key, value))
+    }
+
+    /// Sorts all the entries inserted so far.
+    pub fn reindex(&mut self) {
+        if self.sorted == self.entries.len() {
+            return;
+        }
+        self.entries.sort_by_key(|(key, _)| *key);
+        self.entries.shrink_to_fit();
+        self.sorted = self.entries.len();
+    }
+
+    fn position(&self, key: &TypeId) -> Option<usize> {
+        if let Ok(index) = self.entries[..self.sorted].binary_search_by_key(key, |(k, _)| *k) {
+            return Some(index);
+        }
+        self.entries[self.sorted..]
+            .iter()
+            .position(|(k, _)| k == key)
+            .map(|index| self.sorted + index)
+    }
+}
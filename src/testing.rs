@@ -0,0 +1,190 @@
+//! `testing` module overlays registries scoped to a thread over the global one, which is
+//! enabled with `testing` feature.
+//!
+//! Tests run in parallel share the global registry, so a cast registered at runtime by one
+//! of them is visible to the others. A registry created with [`scoped_registry`] is visible
+//! only to the thread creating it until it is dropped, and takes precedence over the global
+//! one, so that a test can add or shadow casts without leaking them into the other tests.
+//!
+//! ```
+//! # use intertrait::*;
+//! # use intertrait::cast::*;
+//! use intertrait::testing::scoped_registry;
+//!
+//! # trait Source: CastFrom {}
+//! # trait Greet {
+//! #     fn greet(&self) -> &'static str;
+//! # }
+//! struct Data;
+//! impl Source for Data {}
+//! impl Greet for Data {
+//!     fn greet(&self) -> &'static str {
+//!         "hello"
+//!     }
+//! }
+//!
+//! let source: &dyn Source = &Data;
+//! {
+//!     let registry = scoped_registry();
+//!     registry.register_caster::<Data, dyn Greet>(Caster::new(
+//!         |from| from.downcast_ref::<Data>().unwrap(),
+//!         |from| from.downcast_mut::<Data>().unwrap(),
+//!         |from| from.downcast::<Data>().unwrap(),
+//!         |from| from.downcast::<Data>().unwrap(),
+//!     ));
+//!     assert_eq!(source.cast::<dyn Greet>().unwrap().greet(), "hello");
+//! }
+//! assert!(!source.impls::<dyn Greet>());
+//! ```
+//!
+//! The scoped registries are consulted for casts and `impls` queries, but not for
+//! [`impls_of`], [`traits_of`] or [`registry_snapshot`], which report the global registry
+//! only.
+//!
+//! [`scoped_registry`]: ./fn.scoped_registry.html
+//! [`impls_of`]: ../fn.impls_of.html
+//! [`traits_of`]: ../fn.traits_of.html
+//! [`registry_snapshot`]: ../fn.registry_snapshot.html
+use std::any::TypeId;
+use std::cell::{Cell, RefCell};
+use std::collections::HashSet;
+use std::marker::PhantomData;
+
+use crate::registry::Registry;
+use crate::Caster;
+
+/// The casts registered and hidden in a scope.
+#[derive(Default)]
+struct Scope {
+    registry: Registry,
+    hidden: HashSet<(TypeId, TypeId)>,
+}
+
+thread_local! {
+    /// The scopes alive in the thread, the innermost last.
+    static SCOPES: RefCell<Vec<Scope>> = const { RefCell::new(Vec::new()) };
+
+    /// A counter incremented whenever the scopes in the thread change, which invalidates
+    /// the results cached before.
+    static GENERATION: Cell<usize> = const { Cell::new(0) };
+}
+
+pub(crate) fn generation() -> usize {
+    GENERATION.with(Cell::get)
+}
+
+fn bump_generation() {
+    GENERATION.with(|generation| generation.set(generation.get().wrapping_add(1)));
+}
+
+/// Creates a registry scoped to the current thread, which is overlaid over the global one
+/// and the registries created before until it is dropped.
+pub fn scoped_registry() -> ScopedRegistry {
+    let depth = SCOPES.with(|scopes| {
+        let mut scopes = scopes.borrow_mut();
+        scopes.push(Scope::default());
+        scopes.len()
+    });
+    bump_generation();
+    ScopedRegistry {
+        depth,
+        _not_send: PhantomData,
+    }
+}
+
+/// A registry scoped to a thread created with [`scoped_registry`], which is removed when
+/// dropped.
+///
+/// The registries must be dropped in the reverse order of the creation.
+///
+/// [`scoped_registry`]: ./fn.scoped_registry.html
+pub struct ScopedRegistry {
+    depth: usize,
+    _not_send: PhantomData<*const ()>,
+}
+
+impl ScopedRegistry {
+    /// Registers `caster` for casting from a concrete type `S` to a trait `T` in this scope,
+    /// which shadows the caster for the same cast outside of it.
+    pub fn register_caster<S: 'static, T: ?Sized + 'static>(&self, caster: Caster<T>) {
+        let caster = Caster {
+            type_name: std::any::type_name::<S>(),
+            ..caster
+        };
+        self.with_scope(|scope| {
+            let key = (TypeId::of::<S>(), TypeId::of::<T>());
+            scope.hidden.remove(&key);
+            scope.registry.insert(key.0, Box::new(caster));
+        });
+    }
+
+    /// Hides the cast from a concrete type `S` to a trait `T` in this scope, which fails
+    /// regardless of the casters registered outside of it.
+    pub fn hide<S: 'static, T: ?Sized + 'static>(&self) {
+        self.with_scope(|scope| {
+            scope.hidden.insert((TypeId::of::<S>(), TypeId::of::<T>()));
+        });
+    }
+
+    fn with_scope<F: FnOnce(&mut Scope)>(&self, f: F) {
+        SCOPES.with(|scopes| f(&mut scopes.borrow_mut()[self.depth - 1]));
+        bump_generation();
+    }
+}
+
+impl Drop for ScopedRegistry {
+    fn drop(&mut self) {
+        let depth = SCOPES.with(|scopes| {
+            let mut scopes = scopes.borrow_mut();
+            let depth = scopes.len();
+            scopes.pop();
+            depth
+        });
+        bump_generation();
+        if !std::thread::panicking() {
+            assert_eq!(
+                depth, self.depth,
+                "scoped registries must be dropped in the reverse order of the creation"
+            );
+        }
+    }
+}
+
+/// Returns the result of the innermost scope knowing the cast of `key`, with which `found`
+/// is called, or `None` if no scope knows it. A hidden cast is reported as `Some(None)`.
+fn lookup<R, F>(key: (TypeId, TypeId), found: F) -> Option<Option<R>>
+where
+    F: Fn(&Registry) -> Option<R>,
+{
+    SCOPES.with(|scopes| {
+        for scope in scopes.borrow().iter().rev() {
+            if scope.hidden.contains(&key) {
+                return Some(None);
+            }
+            if let Some(result) = found(&scope.registry) {
+                return Some(Some(result));
+            }
+        }
+        None
+    })
+}
+
+/// Returns the caster for casting a value of a concrete type of `type_id` into `T` in
+/// the scopes, which is `Some(None)` if the cast is hidden, and `None` if no scope knows it.
+pub(crate) fn caster<T: ?Sized + 'static>(type_id: TypeId) -> Option<Option<Caster<T>>> {
+    lookup((type_id, TypeId::of::<T>()), |registry| {
+        registry.caster::<T>(type_id)
+    })
+}
+
+/// Tests if any scope is alive in the current thread.
+#[cfg_attr(not(feature = "impls-cache"), allow(dead_code))]
+pub(crate) fn in_scope() -> bool {
+    SCOPES.with(|scopes| !scopes.borrow().is_empty())
+}
+
+/// Tests if the cast of a pair of `TypeId`s of a concrete type and a trait is possible in
+/// the scopes, which is `None` if no scope knows it.
+pub(crate) fn contains(key: (TypeId, TypeId)) -> Option<bool> {
+    lookup(key, |registry| registry.contains(&key).then_some(())).map(|found| found.is_some())
+}
@@ -0,0 +1,135 @@
+use std::any::TypeId;
+use std::collections::HashMap;
+use std::fmt;
+
+#[cfg(not(feature = "disabled"))]
+use crate::CASTERS;
+use crate::{StableKey, UNKNOWN_TYPE_NAME};
+
+/// A structural problem of the registry of casters found by [`validate_registry`].
+///
+/// [`validate_registry`]: ./fn.validate_registry.html
+#[derive(Clone, Debug, PartialEq, Eq, PartialOrd, Ord)]
+pub enum RegistryWarning {
+    /// The cast is registered more than once at link time, e.g. with both `#[cast_to]`
+    /// on the `impl` and `castable_to!`. Only one of the casters is used.
+    Duplicate(StableKey),
+
+    /// The target is `Any`, for which casting is pointless as every `CastFrom` already
+    /// provides a trait object for `Any`.
+    AnyTarget(StableKey),
+
+    /// The caster doesn't know the name of the concrete type, which makes the other
+    /// diagnostics about it less helpful.
+    UnknownTypeName(StableKey),
+
+    /// The cast registered at runtime is never used because the same cast is registered
+    /// at link time, which takes precedence.
+    #[cfg(feature = "dynamic")]
+    Shadowed(StableKey),
+}
+
+impl RegistryWarning {
+    /// Returns the key of the cast the warning is about.
+    pub fn key(&self) -> StableKey {
+        match self {
+            RegistryWarning::Duplicate(key)
+            | RegistryWarning::AnyTarget(key)
+            | RegistryWarning::UnknownTypeName(key) => *key,
+            #[cfg(feature = "dynamic")]
+            RegistryWarning::Shadowed(key) => *key,
+        }
+    }
+}
+
+impl fmt::Display for RegistryWarning {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let key = self.key();
+        let problem = match self {
+            RegistryWarning::Duplicate(_) => "is registered more than once",
+            RegistryWarning::AnyTarget(_) => "targets Any",
+            RegistryWarning::UnknownTypeName(_) => "has no name of the concrete type",
+            #[cfg(feature = "dynamic")]
+            RegistryWarning::Shadowed(_) => "registered at runtime is shadowed",
+        };
+        write!(
+            f,
+            "the cast from {} to {} {}",
+            key.type_name(),
+            key.trait_name(),
+            problem
+        )
+    }
+}
+
+/// Tests if `trait_name` is that of `Any` possibly with auto traits.
+fn is_any(trait_name: &str) -> bool {
+    let name = trait_name.trim_start_matches("dyn ").split(" + ").next();
+    name == Some("core::any::Any") || name == Some("std::any::Any")
+}
+
+/// Checks the registry of casters for structural problems likely to be bugs, and returns
+/// the warnings in a stable order. It is meant to be asserted empty in a test.
+///
+/// It can't verify that a concrete type really implements the target trait, which
+/// the compiler does for the casters generated by the macros.
+///
+/// # Examples
+/// ```
+/// # use intertrait::*;
+/// # trait Greet {}
+/// #[cast_to(Greet)]
+/// struct Data;
+/// # impl Greet for Data {}
+///
+/// assert_eq!(validate_registry(), vec![]);
+/// ```
+pub fn validate_registry() -> Vec<RegistryWarning> {
+    #[allow(unused_mut)]
+    let mut counts: HashMap<(TypeId, TypeId), (StableKey, usize)> = HashMap::new();
+    #[cfg(not(feature = "disabled"))]
+    for (type_id, caster) in CASTERS.iter().filter_map(|entry| entry.parts().ok()) {
+        let (type_name, trait_name) = caster.names();
+        counts
+            .entry((type_id, caster.trait_id()))
+            .or_insert((StableKey::new(type_name, trait_name), 0))
+            .1 += 1;
+    }
+
+    let mut warnings = Vec::new();
+    for (key, count) in counts.values() {
+        if *count > 1 {
+            warnings.push(RegistryWarning::Duplicate(*key));
+        }
+    }
+
+    #[cfg(feature = "dynamic")]
+    let entries = {
+        let dynamic = crate::dynamic::entries();
+        for (id, key) in &dynamic {
+            if counts.contains_key(id) {
+                warnings.push(RegistryWarning::Shadowed(*key));
+            }
+        }
+        counts
+            .values()
+            .map(|(key, _)| *key)
+            .chain(dynamic.into_iter().map(|(_, key)| key))
+            .collect::<Vec<_>>()
+    };
+    #[cfg(not(feature = "dynamic"))]
+    let entries = counts.values().map(|(key, _)| *key).collect::<Vec<_>>();
+
+    for key in entries {
+        if is_any(key.trait_name()) {
+            warnings.push(RegistryWarning::AnyTarget(key));
+        }
+        if key.type_name() == UNKNOWN_TYPE_NAME {
+            warnings.push(RegistryWarning::UnknownTypeName(key));
+        }
+    }
+
+    warnings.sort();
+    warnings.dedup();
+    warnings
+}
@@ -0,0 +1,168 @@
+#![cfg(not(feature = "disabled"))]
+
+use std::thread;
+
+use intertrait::cast::*;
+use intertrait::*;
+
+trait Source: CastFrom {}
+
+trait Greet {
+    fn greet(&self) -> &'static str;
+}
+
+trait Count {
+    fn count(&self) -> usize;
+}
+
+struct Data;
+
+#[cast_to([send])]
+impl Greet for Data {
+    fn greet(&self) -> &'static str {
+        "hello"
+    }
+}
+
+impl Count for Data {
+    fn count(&self) -> usize {
+        1
+    }
+}
+
+impl Source for Data {}
+
+castable_to! { Data => [send] Count }
+
+#[cast_to(Greet)]
+struct Plain;
+
+impl Greet for Plain {
+    fn greet(&self) -> &'static str {
+        "plain"
+    }
+}
+
+impl Source for Plain {}
+
+#[test]
+fn test_cast_box_send() {
+    let source: Box<dyn Source + Send> = Box::new(Data);
+    let greet = source
+        .cast::<dyn Greet + Send>()
+        .unwrap_or_else(|_| panic!());
+    let handle = thread::spawn(move || greet.greet());
+    assert_eq!(handle.join().unwrap(), "hello");
+}
+
+#[test]
+fn test_cast_ref_send() {
+    let source: &(dyn Source + Send) = &Data;
+    assert_eq!(source.cast::<dyn Count + Send>().unwrap().count(), 1);
+    // The cast without the marker is still registered.
+    assert_eq!(source.cast::<dyn Count>().unwrap().count(), 1);
+}
+
+#[test]
+fn test_cast_send_unregistered() {
+    let source: &dyn Source = &Plain;
+    assert!(source.impls::<dyn Greet>());
+    assert!(!source.impls::<dyn Greet + Send>());
+}
+
+trait SyncSource: CastFromSync {}
+
+#[cast_to([sync] Greet)]
+struct Shared;
+
+impl Greet for Shared {
+    fn greet(&self) -> &'static str {
+        "shared"
+    }
+}
+
+impl SyncSource for Shared {}
+
+#[test]
+fn test_cast_send_sync() {
+    let source: &dyn SyncSource = &Shared;
+    let greet: &(dyn Greet + Send + Sync) = source.cast::<dyn Greet + Send + Sync>().unwrap();
+    assert_eq!(greet.greet(), "shared");
+
+    let source: Box<dyn SyncSource> = Box::new(Shared);
+    let greet = source
+        .cast::<dyn Greet + Send + Sync>()
+        .unwrap_or_else(|_| panic!());
+    let greets: std::sync::Mutex<Vec<Box<dyn Greet + Send + Sync>>> = Default::default();
+    greets.lock().unwrap().push(greet);
+    assert_eq!(greets.lock().unwrap()[0].greet(), "shared");
+
+    let source: std::sync::Arc<dyn SyncSource> = std::sync::Arc::new(Shared);
+    let greet = source
+        .cast::<dyn Greet + Send + Sync>()
+        .unwrap_or_else(|_| panic!());
+    let handle = thread::spawn(move || greet.greet());
+    assert_eq!(handle.join().unwrap(), "shared");
+}
+
+#[test]
+fn test_try_add_send() {
+    let (sender, receiver) = std::sync::mpsc::channel::<Box<dyn Greet + Send>>();
+    let sources: Vec<Box<dyn Source>> = vec![Box::new(Data), Box::new(Plain)];
+    let mut rejected = Vec::new();
+    for source in sources {
+        match try_add_send::<dyn Greet + Send, _>(source) {
+            Ok(greet) => sender.send(greet).unwrap(),
+            Err(source) => rejected.push(source),
+        }
+    }
+    drop(sender);
+    let handle = thread::spawn(move || {
+        receiver
+            .iter()
+            .map(|greet| greet.greet())
+            .collect::<Vec<_>>()
+    });
+    assert_eq!(handle.join().unwrap(), vec!["hello"]);
+    // Plain isn't registered with [send].
+    assert_eq!(rejected.len(), 1);
+    assert!((*rejected[0]).impls::<dyn Greet>());
+}
+
+#[cast_to]
+impl Count for Plain {
+    fn count(&self) -> usize {
+        2
+    }
+}
+
+#[test]
+fn test_try_add_send_without_flags() {
+    // Plain is Send, but the casts aren't registered with [send] or [sync].
+    let source: Box<dyn Source> = Box::new(Plain);
+    let source = try_add_send::<dyn Count + Send, _>(source)
+        .map(|_| ())
+        .unwrap_err();
+    let source = try_add_sync::<dyn Count + Send + Sync, _>(source)
+        .map(|_| ())
+        .unwrap_err();
+    assert_eq!((*source).cast::<dyn Count>().unwrap().count(), 2);
+}
+
+#[test]
+fn test_try_add_sync() {
+    let source: Box<dyn Source> = Box::new(Data);
+    let source = try_add_sync::<dyn Greet + Send + Sync, _>(source)
+        .map(|_| ())
+        .unwrap_err();
+    assert!(try_add_send::<dyn Greet + Send, _>(source).is_ok());
+
+    let source: Box<dyn SyncSource> = Box::new(Shared);
+    let greet = try_add_sync::<dyn Greet + Send + Sync, _>(source)
+        .ok()
+        .unwrap();
+    assert_eq!(
+        std::sync::Arc::<dyn Greet + Send + Sync>::from(greet).greet(),
+        "shared"
+    );
+}
@@ -0,0 +1,73 @@
+#![cfg(not(feature = "disabled"))]
+
+use std::sync::Arc;
+
+use intertrait::cast::*;
+use intertrait::*;
+
+trait Source: CastFromSync {
+    fn name(&self) -> &'static str;
+}
+
+trait Count {
+    fn count(&self) -> u32;
+    fn increment(&mut self);
+}
+
+trait Greet {
+    fn greet(&self) -> String;
+}
+
+#[cast_to([sync] Count, Greet)]
+struct Data(u32);
+
+impl Count for Data {
+    fn count(&self) -> u32 {
+        self.0
+    }
+
+    fn increment(&mut self) {
+        self.0 += 1;
+    }
+}
+
+impl Greet for Data {
+    fn greet(&self) -> String {
+        format!("count {}", self.0)
+    }
+}
+
+impl Source for Data {
+    fn name(&self) -> &'static str {
+        "data"
+    }
+}
+
+#[test]
+fn test_cached_cast_box() {
+    let mut source = CachedCast::new(Box::new(Data(1)) as Box<dyn Source>);
+    for _ in 0..3 {
+        source.cast_mut::<dyn Count>().unwrap().increment();
+    }
+    assert_eq!(source.cast::<dyn Count>().unwrap().count(), 4);
+    assert_eq!(source.cast::<dyn Greet>().unwrap().greet(), "count 4");
+    assert!(source.cast::<dyn std::fmt::Debug>().is_none());
+
+    // Casts not through a caster still work.
+    assert_eq!(source.cast::<Data>().unwrap().0, 4);
+    source.cast_mut::<Data>().unwrap().0 = 10;
+
+    // It dereferences to the trait object.
+    assert_eq!(source.name(), "data");
+    let source: Box<dyn Source> = CachedCast::into_inner(source);
+    assert_eq!((*source).cast::<dyn Count>().unwrap().count(), 10);
+}
+
+#[test]
+fn test_cached_cast_arc() {
+    let shared: Arc<dyn Source> = Arc::new(Data(1));
+    let source = CachedCast::new(shared.clone());
+    assert_eq!(source.cast::<dyn Count>().unwrap().count(), 1);
+    assert_eq!(source.cast::<dyn Count>().unwrap().count(), 1);
+    assert_eq!(Arc::strong_count(&shared), 2);
+}
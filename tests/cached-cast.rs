@@ -0,0 +1,81 @@
+#![cfg(not(feature = "disabled"))]
+
+use intertrait::cast::*;
+use intertrait::*;
+
+trait Source: CastFrom {}
+
+trait Count {
+    fn count(&self) -> u32;
+    fn increment(&mut self);
+}
+
+#[cast_to(Count)]
+struct A(u32);
+
+#[cast_to(Count)]
+struct B(u32);
+
+struct C;
+
+impl Count for A {
+    fn count(&self) -> u32 {
+        self.0
+    }
+
+    fn increment(&mut self) {
+        self.0 += 1;
+    }
+}
+
+impl Count for B {
+    fn count(&self) -> u32 {
+        self.0 * 10
+    }
+
+    fn increment(&mut self) {
+        self.0 += 1;
+    }
+}
+
+impl Source for A {}
+
+impl Source for B {}
+
+impl Source for C {}
+
+fn count(source: &dyn Source) -> Option<u32> {
+    cached_cast!(source => dyn Count).map(Count::count)
+}
+
+#[test]
+fn test_cached_cast_mixed_types() {
+    let sources: Vec<&dyn Source> = vec![&A(1), &A(2), &B(3), &C, &A(4), &C, &B(5)];
+    let counts: Vec<_> = sources.into_iter().map(count).collect();
+    assert_eq!(
+        counts,
+        vec![Some(1), Some(2), Some(30), None, Some(4), None, Some(50)]
+    );
+}
+
+#[test]
+fn test_cached_cast_mut() {
+    let mut sources: Vec<Box<dyn Source>> = vec![Box::new(A(1)), Box::new(B(1)), Box::new(C)];
+    for _ in 0..3 {
+        for source in sources.iter_mut() {
+            if let Some(count) = cached_cast!(mut *source => dyn Count) {
+                count.increment();
+            }
+        }
+    }
+    let counts: Vec<_> = sources.iter().map(|source| count(&**source)).collect();
+    assert_eq!(counts, vec![Some(4), Some(40), None]);
+}
+
+#[test]
+fn test_cast_cache_concrete() {
+    let cache = CastCache::<A>::new();
+    let source: &dyn Source = &A(1);
+    assert_eq!(cache.cast_ref(source).unwrap().0, 1);
+    assert!(cache.cast_ref(&B(1) as &dyn Source).is_none());
+}
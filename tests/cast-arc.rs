@@ -0,0 +1,93 @@
+#![cfg(not(feature = "disabled"))]
+
+use std::sync::Arc;
+use std::thread;
+
+use intertrait::cast::*;
+use intertrait::*;
+
+trait Source: CastFromSync {}
+
+trait Greet {
+    fn greet(&self) -> &'static str;
+}
+
+#[cast_to([sync] Greet)]
+struct Shared;
+
+impl Greet for Shared {
+    fn greet(&self) -> &'static str {
+        "shared"
+    }
+}
+
+impl Source for Shared {}
+
+#[cast_to(Greet)]
+struct Local;
+
+impl Greet for Local {
+    fn greet(&self) -> &'static str {
+        "local"
+    }
+}
+
+impl Source for Local {}
+
+#[test]
+fn test_cast_arc() {
+    let source: Arc<dyn Source> = Arc::new(Shared);
+    let handle = {
+        let source = source.clone();
+        thread::spawn(move || {
+            let greet = source.cast::<dyn Greet>().unwrap_or_else(|_| panic!());
+            greet.greet()
+        })
+    };
+    assert_eq!(handle.join().unwrap(), "shared");
+    assert_eq!(Arc::strong_count(&source), 1);
+}
+
+#[test]
+#[should_panic(expected = "Prepend [sync] to the list of target traits")]
+fn test_cast_arc_without_sync() {
+    let source: Arc<dyn Source> = Arc::new(Local);
+    let _ = source.cast::<dyn Greet>();
+}
+
+#[test]
+fn test_cast_cloned() {
+    let source: Arc<dyn Source> = Arc::new(Shared);
+    let greet = source.cast_cloned::<dyn Greet>().unwrap();
+    assert_eq!(greet.greet(), "shared");
+    assert_eq!(Arc::strong_count(&source), 2);
+
+    assert!(source.cast_cloned::<dyn std::fmt::Debug>().is_none());
+    assert_eq!(Arc::strong_count(&source), 2);
+}
+
+#[test]
+fn test_box_to_arc() {
+    // A cast registered without [sync] can still be made into an Arc through a Box.
+    let source: Box<dyn Source> = Box::new(Local);
+    let greet = box_to_arc::<dyn Greet, _>(source).ok().unwrap();
+    assert_eq!(greet.greet(), "local");
+
+    let source: Box<dyn Source> = Box::new(Local);
+    assert!(box_to_arc::<dyn std::fmt::Debug, _>(source).is_err());
+}
+
+#[test]
+fn test_try_unwrap_cast() {
+    let source: Arc<dyn Source> = Arc::new(Local);
+    let shared = source.clone();
+    let source = source.try_unwrap_cast::<dyn Greet>().err().unwrap();
+    drop(shared);
+
+    let source = source
+        .try_unwrap_cast::<dyn std::fmt::Debug>()
+        .err()
+        .unwrap();
+    let greet: Box<dyn Greet> = source.try_unwrap_cast::<dyn Greet>().ok().unwrap();
+    assert_eq!(greet.greet(), "local");
+}
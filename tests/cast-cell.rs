@@ -0,0 +1,73 @@
+#![cfg(not(feature = "disabled"))]
+
+use std::cell::RefCell;
+use std::rc::Rc;
+
+use intertrait::cast::*;
+use intertrait::*;
+
+trait Widget: CastFrom {}
+
+trait Toggle {
+    fn checked(&self) -> bool;
+
+    fn toggle(&mut self);
+}
+
+trait Scroll {}
+
+#[cast_to(Toggle)]
+struct Checkbox(bool);
+
+impl Widget for Checkbox {}
+
+impl Toggle for Checkbox {
+    fn checked(&self) -> bool {
+        self.0
+    }
+
+    fn toggle(&mut self) {
+        self.0 = !self.0;
+    }
+}
+
+#[test]
+fn test_cast_cell_ref() {
+    let widget: Rc<RefCell<dyn Widget>> = Rc::new(RefCell::new(Checkbox(true)));
+    let toggle = cast_cell_ref::<_, dyn Toggle>(widget.borrow())
+        .ok()
+        .unwrap();
+    assert!(toggle.checked());
+    // Still borrowed through the cast.
+    assert!(widget.try_borrow_mut().is_err());
+    drop(toggle);
+    assert!(widget.try_borrow_mut().is_ok());
+}
+
+#[test]
+fn test_cast_cell_mut() {
+    let widget: Rc<RefCell<dyn Widget>> = Rc::new(RefCell::new(Checkbox(false)));
+    let mut toggle = cast_cell_mut::<_, dyn Toggle>(widget.borrow_mut())
+        .ok()
+        .unwrap();
+    toggle.toggle();
+    assert!(widget.try_borrow().is_err());
+    drop(toggle);
+
+    let toggle = cast_cell_ref::<_, dyn Toggle>(widget.borrow())
+        .ok()
+        .unwrap();
+    assert!(toggle.checked());
+}
+
+#[test]
+fn test_cast_cell_fail() {
+    let widget: Rc<RefCell<dyn Widget>> = Rc::new(RefCell::new(Checkbox(false)));
+    let borrow = cast_cell_mut::<_, dyn Scroll>(widget.borrow_mut())
+        .map(|_| ())
+        .unwrap_err();
+    // The original borrow is given back.
+    assert!(widget.try_borrow().is_err());
+    drop(borrow);
+    assert!(widget.try_borrow().is_ok());
+}
@@ -0,0 +1,83 @@
+use std::any::TypeId;
+
+use intertrait::cast::*;
+use intertrait::dynamic::set_cast_denylist;
+use intertrait::*;
+
+trait Source: CastFrom {}
+
+trait FileSystemAccess {
+    fn path(&self) -> &str;
+}
+
+trait Greet {
+    fn greet(&self) -> &str;
+}
+
+#[cast_to(FileSystemAccess, Greet)]
+struct Plugin;
+
+impl FileSystemAccess for Plugin {
+    fn path(&self) -> &str {
+        "/"
+    }
+}
+
+impl Greet for Plugin {
+    fn greet(&self) -> &str {
+        "Hello"
+    }
+}
+
+impl Source for Plugin {}
+
+#[cast_to(FileSystemAccess)]
+struct Host;
+
+impl FileSystemAccess for Host {
+    fn path(&self) -> &str {
+        "/host"
+    }
+}
+
+impl Source for Host {}
+
+// The denylist is global, so the cases are run in sequence in a single test.
+#[test]
+fn test_cast_denylist() {
+    let (_, handle) = (&Plugin as &dyn Source)
+        .cast_handled::<dyn FileSystemAccess>()
+        .unwrap();
+    let mut cursor = CastCursor::<dyn FileSystemAccess>::new();
+    assert!(cursor.cast_ref(&Plugin as &dyn Source).is_some());
+
+    set_cast_denylist(&[(TypeId::of::<Plugin>(), TypeId::of::<dyn FileSystemAccess>())]);
+
+    let plugin: &dyn Source = &Plugin;
+    assert!(plugin.cast::<dyn FileSystemAccess>().is_none());
+    assert!(!plugin.impls::<dyn FileSystemAccess>());
+    assert_eq!(plugin.cast::<dyn Greet>().unwrap().greet(), "Hello");
+
+    let boxed: Box<dyn Source> = Box::new(Plugin);
+    assert!(boxed.cast::<dyn FileSystemAccess>().is_err());
+
+    // A handle or a cursor resolved before doesn't let the cast through.
+    assert!(handle.cast_ref(plugin).is_none());
+    assert!(cursor.cast_ref(plugin).is_none());
+    assert!(handle
+        .cast_box(Box::new(Plugin) as Box<dyn Source>)
+        .is_err());
+
+    let host: &dyn Source = &Host;
+    assert_eq!(host.cast::<dyn FileSystemAccess>().unwrap().path(), "/host");
+    assert_eq!(
+        impls_of::<dyn FileSystemAccess>().collect::<Vec<_>>(),
+        vec![TypeId::of::<Host>()]
+    );
+
+    set_cast_denylist(&[]);
+    assert_eq!(plugin.cast::<dyn FileSystemAccess>().unwrap().path(), "/");
+    assert_eq!(handle.cast_ref(plugin).unwrap().path(), "/");
+    assert_eq!(cursor.cast_ref(plugin).unwrap().path(), "/");
+    assert_eq!(impls_of::<dyn FileSystemAccess>().count(), 2);
+}
@@ -0,0 +1,84 @@
+#![cfg(not(feature = "disabled"))]
+
+use std::any::{type_name, TypeId};
+use std::error::Error;
+
+use intertrait::cast::*;
+use intertrait::*;
+
+trait Source: CastFrom {}
+
+trait Greet {
+    fn greet(&self) -> &'static str;
+}
+
+trait Count {}
+
+#[cast_to(Greet)]
+struct Data;
+
+impl Greet for Data {
+    fn greet(&self) -> &'static str {
+        "hello"
+    }
+}
+
+impl Source for Data {}
+
+#[test]
+fn test_try_cast_ref() {
+    let source: &dyn Source = &Data;
+    assert_eq!(source.try_cast::<dyn Greet>().unwrap().greet(), "hello");
+
+    let err = source.try_cast::<dyn Count>().err().unwrap();
+    assert_eq!(err.source_type_id(), TypeId::of::<Data>());
+    assert_eq!(err.source_type_name(), type_name::<Data>());
+    assert_eq!(err.target_name(), type_name::<dyn Count>());
+    assert_eq!(
+        err.to_string(),
+        format!(
+            "{} is not castable to {}",
+            type_name::<Data>(),
+            type_name::<dyn Count>()
+        )
+    );
+}
+
+#[test]
+fn test_try_cast_mut() {
+    let mut data = Data;
+    let source: &mut dyn Source = &mut data;
+    assert!(source.try_cast::<dyn Greet>().is_ok());
+    let err = source.try_cast::<dyn Count>().err().unwrap();
+    assert_eq!(err.source_type_id(), TypeId::of::<Data>());
+}
+
+#[test]
+fn test_try_cast_box() {
+    let source: Box<dyn Source> = Box::new(Data);
+    let err = source.try_cast::<dyn Count>().err().unwrap();
+    assert_eq!(err.source_type_name(), type_name::<Data>());
+
+    // The source is given back to be cast again.
+    let source: Box<dyn Source> = err.into_source();
+    let greet = source.try_cast::<dyn Greet>().ok().unwrap();
+    assert_eq!(greet.greet(), "hello");
+}
+
+#[test]
+fn test_cast_error_as_error() {
+    fn greet(source: Box<dyn Source>) -> Result<&'static str, Box<dyn Error + Send + Sync>> {
+        let greet = source
+            .try_cast::<dyn Greet>()
+            .map_err(CastError::without_source)?;
+        Ok(greet.greet())
+    }
+
+    struct Other;
+    impl Source for Other {}
+
+    assert_eq!(greet(Box::new(Data)).unwrap(), "hello");
+    let err = greet(Box::new(Other)).unwrap_err();
+    let err = err.downcast_ref::<CastError>().unwrap();
+    assert_eq!(err.target_name(), type_name::<dyn Greet>());
+}
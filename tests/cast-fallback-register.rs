@@ -0,0 +1,76 @@
+use std::any::{Any, TypeId};
+
+use intertrait::cast::*;
+use intertrait::dynamic::{register_caster, set_cast_fallback};
+use intertrait::{CastFrom, Caster};
+
+trait Source: CastFrom {}
+
+trait Greet {
+    fn greet(&self) -> &'static str;
+}
+
+struct Cast;
+
+impl Greet for Cast {
+    fn greet(&self) -> &'static str {
+        "cast"
+    }
+}
+
+impl Source for Cast {}
+
+struct Queried;
+
+impl Greet for Queried {
+    fn greet(&self) -> &'static str {
+        "queried"
+    }
+}
+
+impl Source for Queried {}
+
+static CAST_GREET: Caster<dyn Greet> = Caster::new(
+    |from| from.downcast_ref::<Cast>().unwrap(),
+    |from| from.downcast_mut::<Cast>().unwrap(),
+    |from| from.downcast::<Cast>().unwrap(),
+    |from| from.downcast::<Cast>().unwrap(),
+);
+
+static QUERIED_GREET: Caster<dyn Greet> = Caster::new(
+    |from| from.downcast_ref::<Queried>().unwrap(),
+    |from| from.downcast_mut::<Queried>().unwrap(),
+    |from| from.downcast::<Queried>().unwrap(),
+    |from| from.downcast::<Queried>().unwrap(),
+);
+
+/// Memoizes the resolved casters by registering them, so that the fallback is called only
+/// once for each.
+fn fallback(type_id: TypeId, trait_id: TypeId) -> Option<&'static (dyn Any + Send + Sync)> {
+    if trait_id != TypeId::of::<dyn Greet>() {
+        None
+    } else if type_id == TypeId::of::<Cast>() {
+        register_caster::<Cast, dyn Greet>(CAST_GREET);
+        Some(&CAST_GREET)
+    } else if type_id == TypeId::of::<Queried>() {
+        register_caster::<Queried, dyn Greet>(QUERIED_GREET);
+        Some(&QUERIED_GREET)
+    } else {
+        None
+    }
+}
+
+#[test]
+fn test_fallback_registering_caster() {
+    set_cast_fallback(fallback);
+
+    // Resolved first by a cast.
+    let cast: &dyn Source = &Cast;
+    assert_eq!(cast.cast::<dyn Greet>().unwrap().greet(), "cast");
+    assert!(cast.impls::<dyn Greet>());
+
+    // Resolved first by a query.
+    let queried: &dyn Source = &Queried;
+    assert!(queried.impls::<dyn Greet>());
+    assert_eq!(queried.cast::<dyn Greet>().unwrap().greet(), "queried");
+}
@@ -0,0 +1,69 @@
+use std::any::{Any, TypeId};
+
+use intertrait::cast::*;
+use intertrait::dynamic::{clear_cast_fallback, set_cast_fallback};
+use intertrait::{CastFrom, Caster};
+use once_cell::sync::Lazy;
+
+trait Source: CastFrom {}
+
+trait Greet {
+    fn greet(&self) -> &'static str;
+}
+
+struct Generated;
+
+impl Greet for Generated {
+    fn greet(&self) -> &'static str {
+        "generated"
+    }
+}
+
+impl Source for Generated {}
+
+struct Other;
+
+impl Greet for Other {
+    fn greet(&self) -> &'static str {
+        "other"
+    }
+}
+
+impl Source for Other {}
+
+static GREET: Lazy<Caster<dyn Greet>> = Lazy::new(|| {
+    Caster::new(
+        |from| from.downcast_ref::<Generated>().unwrap(),
+        |from| from.downcast_mut::<Generated>().unwrap(),
+        |from| from.downcast::<Generated>().unwrap(),
+        |from| from.downcast::<Generated>().unwrap(),
+    )
+});
+
+fn fallback(type_id: TypeId, trait_id: TypeId) -> Option<&'static (dyn Any + Send + Sync)> {
+    if type_id == TypeId::of::<Generated>() && trait_id == TypeId::of::<dyn Greet>() {
+        Some(&*GREET)
+    } else if type_id == TypeId::of::<Other>() {
+        // Not a caster for the requested trait, which must be ignored.
+        Some(&*GREET)
+    } else {
+        None
+    }
+}
+
+#[test]
+fn test_cast_fallback() {
+    let generated: &dyn Source = &Generated;
+    let other: &dyn Source = &Other;
+    assert!(generated.cast::<dyn Greet>().is_none());
+
+    set_cast_fallback(fallback);
+    assert!(generated.impls::<dyn Greet>());
+    assert_eq!(generated.cast::<dyn Greet>().unwrap().greet(), "generated");
+    assert!(generated.cast::<dyn std::fmt::Debug>().is_none());
+    assert!(!other.impls::<dyn std::fmt::Debug>());
+    assert!(other.cast::<dyn std::fmt::Debug>().is_none());
+
+    clear_cast_fallback();
+    assert!(generated.cast::<dyn Greet>().is_none());
+}
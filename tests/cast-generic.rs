@@ -0,0 +1,100 @@
+#![cfg(not(feature = "disabled"))]
+
+use std::ops::{Deref, DerefMut};
+use std::rc::Rc;
+use std::sync::Arc;
+
+use intertrait::cast::*;
+use intertrait::*;
+
+trait Source: CastFromSync {}
+
+trait Count {
+    fn count(&self) -> u32;
+
+    fn increment(&mut self);
+}
+
+trait Unknown {}
+
+#[cast_to([sync] Count)]
+struct Counter(u32);
+
+impl Source for Counter {}
+
+impl Count for Counter {
+    fn count(&self) -> u32 {
+        self.0
+    }
+
+    fn increment(&mut self) {
+        self.0 += 1;
+    }
+}
+
+/// Casts any kind of pointers with a single code path.
+fn count<P>(source: P) -> Result<u32, P>
+where
+    P: Cast<dyn Count>,
+    P::Output: Deref<Target = dyn Count>,
+{
+    source.cast_into().map(|count| count.count())
+}
+
+fn increment<P>(source: P) -> Result<P::Output, P>
+where
+    P: Cast<dyn Count>,
+    P::Output: DerefMut<Target = dyn Count>,
+{
+    source.cast_into().map(|mut count| {
+        count.increment();
+        count
+    })
+}
+
+#[test]
+fn test_cast_any_pointer() {
+    let source: &dyn Source = &Counter(1);
+    assert_eq!(count(source).ok(), Some(1));
+
+    let mut counter = Counter(2);
+    let source: &mut dyn Source = &mut counter;
+    assert_eq!(increment(source).ok().unwrap().count(), 3);
+
+    let source: Box<dyn Source> = Box::new(Counter(4));
+    assert_eq!(increment(source).ok().unwrap().count(), 5);
+
+    let source: Rc<dyn Source> = Rc::new(Counter(6));
+    assert_eq!(count(source).ok(), Some(6));
+
+    let source: Arc<dyn Source> = Arc::new(Counter(7));
+    assert_eq!(count(source).ok(), Some(7));
+}
+
+#[test]
+fn test_cast_into_fails() {
+    let mut counter = Counter(1);
+    let source: &mut dyn Source = &mut counter;
+    let source = Cast::<dyn Unknown>::cast_into(source).err().unwrap();
+    // The mutable reference is given back to be used again.
+    Cast::<dyn Count>::cast_into(source)
+        .ok()
+        .unwrap()
+        .increment();
+    assert_eq!(counter.0, 2);
+
+    let source: Rc<dyn Source> = Rc::new(Counter(1));
+    let source = Cast::<dyn Unknown>::cast_into(source).err().unwrap();
+    assert_eq!(Rc::strong_count(&source), 1);
+}
+
+#[test]
+fn test_filter_cast_rcs() {
+    let sources: Vec<Rc<dyn Source>> = vec![Rc::new(Counter(1)), Rc::new(Counter(2))];
+    let total: u32 = sources
+        .into_iter()
+        .filter_cast::<dyn Count>()
+        .map(|count| count.count())
+        .sum();
+    assert_eq!(total, 3);
+}
@@ -0,0 +1,54 @@
+#![cfg(not(feature = "disabled"))]
+
+use intertrait::*;
+
+trait Render {}
+
+trait Greet {}
+
+#[cast_to(label = "debug", std::fmt::Debug)]
+#[derive(Debug)]
+struct Sprite;
+
+#[cast_to(label = "render")]
+impl Render for Sprite {}
+
+struct Data;
+
+impl Greet for Data {}
+
+castable_to!(Data => label = "greet", Greet);
+
+#[cast_to]
+impl Render for Data {}
+
+fn label_of(type_name: &str, trait_name: &str) -> Option<&'static str> {
+    registry_snapshot()
+        .into_iter()
+        .find(|key| key.type_name() == type_name && key.trait_name() == trait_name)
+        .unwrap()
+        .label()
+}
+
+#[test]
+fn test_cast_labels_in_snapshot() {
+    assert_eq!(
+        label_of("cast_label::Sprite", "dyn cast_label::Render"),
+        Some("render")
+    );
+    assert_eq!(
+        label_of("cast_label::Sprite", "dyn core::fmt::Debug"),
+        Some("debug")
+    );
+    assert_eq!(
+        label_of("cast_label::Data", "dyn cast_label::Greet"),
+        Some("greet")
+    );
+    assert_eq!(label_of("cast_label::Data", "dyn cast_label::Render"), None);
+}
+
+#[test]
+fn test_label_not_compared() {
+    let key = StableKey::new("cast_label::Sprite", "dyn cast_label::Render");
+    assert!(registry_snapshot().contains(&key));
+}
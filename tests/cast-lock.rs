@@ -0,0 +1,72 @@
+use std::sync::Arc;
+
+use intertrait::cast::*;
+use intertrait::*;
+use parking_lot::{Mutex, RwLock};
+
+trait Source: CastFromSync {}
+
+trait Count {
+    fn count(&self) -> u32;
+
+    fn increment(&mut self);
+}
+
+trait Unknown {}
+
+#[cast_to([sync] Count)]
+struct Counter(u32);
+
+impl Source for Counter {}
+
+impl Count for Counter {
+    fn count(&self) -> u32 {
+        self.0
+    }
+
+    fn increment(&mut self) {
+        self.0 += 1;
+    }
+}
+
+#[test]
+fn test_cast_mutex_guard() {
+    let source: Arc<Mutex<dyn Source>> = Arc::new(Mutex::new(Counter(0)));
+    let mut count = cast_mutex_guard::<_, dyn Count>(source.lock())
+        .ok()
+        .unwrap();
+    count.increment();
+    // Still locked through the cast.
+    assert!(source.try_lock().is_none());
+    drop(count);
+
+    let guard = cast_mutex_guard::<_, dyn Unknown>(source.lock())
+        .map(|_| ())
+        .unwrap_err();
+    assert!(source.is_locked());
+    drop(guard);
+
+    let count = cast_mutex_guard::<_, dyn Count>(source.lock())
+        .ok()
+        .unwrap();
+    assert_eq!(count.count(), 1);
+}
+
+#[test]
+fn test_cast_rw_lock_guards() {
+    let source: Arc<RwLock<dyn Source>> = Arc::new(RwLock::new(Counter(0)));
+    let mut count = cast_write_guard::<_, dyn Count>(source.write())
+        .ok()
+        .unwrap();
+    count.increment();
+    assert!(source.try_read().is_none());
+    drop(count);
+
+    let first = cast_read_guard::<_, dyn Count>(source.read()).ok().unwrap();
+    let second = cast_read_guard::<_, dyn Count>(source.read()).ok().unwrap();
+    assert_eq!(first.count() + second.count(), 2);
+    assert!(source.try_write().is_none());
+    drop((first, second));
+
+    assert!(cast_read_guard::<_, dyn Unknown>(source.read()).is_err());
+}
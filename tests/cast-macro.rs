@@ -0,0 +1,56 @@
+#![cfg(not(feature = "disabled"))]
+
+use std::fmt::Debug;
+use std::rc::Rc;
+
+use intertrait::{cast, cast_to, CastFrom};
+
+trait Source: CastFrom {}
+
+trait Counter {
+    fn get(&self) -> u32;
+    fn increment(&mut self);
+}
+
+#[cast_to(Counter)]
+struct Data(u32);
+
+impl Counter for Data {
+    fn get(&self) -> u32 {
+        self.0
+    }
+
+    fn increment(&mut self) {
+        self.0 += 1;
+    }
+}
+
+impl Source for Data {}
+
+#[test]
+fn test_cast_ref() {
+    let source: &dyn Source = &Data(1);
+    assert_eq!(cast!(source => dyn Counter).unwrap().get(), 1);
+    assert!(cast!(source => dyn Debug).is_none());
+
+    let source: Rc<dyn Source> = Rc::new(Data(2));
+    assert_eq!(cast!(source => dyn Counter).unwrap().get(), 2);
+}
+
+#[test]
+fn test_cast_mut() {
+    let mut data = Data(1);
+    let source: &mut dyn Source = &mut data;
+    cast!(mut source => dyn Counter).unwrap().increment();
+    assert!(cast!(mut source => dyn Debug).is_none());
+    assert_eq!(data.0, 2);
+}
+
+#[test]
+fn test_cast_box() {
+    let source: Box<dyn Source> = Box::new(Data(1));
+    let source = cast!(box source => dyn Debug).unwrap_err();
+    assert_eq!(cast!(source => Data).unwrap().0, 1);
+    let counter = cast!(box source => dyn Counter).ok().unwrap();
+    assert_eq!(counter.get(), 1);
+}
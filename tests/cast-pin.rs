@@ -0,0 +1,139 @@
+#![cfg(not(feature = "disabled"))]
+
+use std::future::Future;
+use std::marker::PhantomPinned;
+use std::pin::Pin;
+use std::ptr;
+use std::task::{Context, Poll, RawWaker, RawWakerVTable, Waker};
+
+use intertrait::cast::*;
+use intertrait::*;
+
+#[cast_to(Address)]
+struct Data {
+    address: usize,
+    _pinned: PhantomPinned,
+}
+
+trait Source: CastFrom {
+    fn pin(self: Pin<&mut Self>);
+}
+
+trait Address {
+    fn pinned_at(&self) -> usize;
+
+    fn address(&self) -> usize;
+}
+
+trait Unknown {}
+
+impl Source for Data {
+    fn pin(self: Pin<&mut Self>) {
+        let address = &*self as *const Data as usize;
+        // SAFETY: `address` isn't structurally pinned.
+        unsafe { self.get_unchecked_mut().address = address };
+    }
+}
+
+impl Address for Data {
+    fn pinned_at(&self) -> usize {
+        self.address
+    }
+
+    fn address(&self) -> usize {
+        self as *const Data as usize
+    }
+}
+
+#[test]
+fn test_cast_pin() {
+    let mut source: Pin<Box<dyn Source>> = Box::pin(Data {
+        address: 0,
+        _pinned: PhantomPinned,
+    });
+    source.as_mut().pin();
+
+    let source = source.cast_pin::<dyn Unknown>().err().unwrap();
+    let address = source.cast_pin::<dyn Address>().ok().unwrap();
+    assert_ne!(address.pinned_at(), 0);
+    assert_eq!(address.pinned_at(), address.address());
+}
+
+#[test]
+fn test_cast_pin_into_concrete_type() {
+    let mut source: Pin<Box<dyn Source>> = Box::pin(Data {
+        address: 0,
+        _pinned: PhantomPinned,
+    });
+    source.as_mut().pin();
+
+    let data = source.as_mut().cast_pin::<Data>().unwrap();
+    assert_eq!(data.pinned_at(), data.address());
+    let data = source.cast_pin::<Data>().ok().unwrap();
+    assert_eq!(data.pinned_at(), data.address());
+}
+
+#[test]
+fn test_cast_pin_mut() {
+    let mut source: Pin<Box<dyn Source>> = Box::pin(Data {
+        address: 0,
+        _pinned: PhantomPinned,
+    });
+    source.as_mut().pin();
+
+    assert!(source.as_mut().cast_pin::<dyn Unknown>().is_none());
+    let address = source.as_mut().cast_pin::<dyn Address>().unwrap();
+    assert_ne!(address.pinned_at(), 0);
+    assert_eq!(address.pinned_at(), address.address());
+}
+
+/// A state machine counting the polls, which is `!Unpin` like those of `async` blocks.
+#[cast_to(Future<Output = u32>)]
+struct Countdown {
+    remaining: u32,
+    _pinned: PhantomPinned,
+}
+
+impl Source for Countdown {
+    fn pin(self: Pin<&mut Self>) {}
+}
+
+impl Future for Countdown {
+    type Output = u32;
+
+    fn poll(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<u32> {
+        // SAFETY: `remaining` isn't structurally pinned.
+        let this = unsafe { self.get_unchecked_mut() };
+        if this.remaining == 0 {
+            return Poll::Ready(42);
+        }
+        this.remaining -= 1;
+        cx.waker().wake_by_ref();
+        Poll::Pending
+    }
+}
+
+fn noop_waker() -> Waker {
+    fn clone(_: *const ()) -> RawWaker {
+        RawWaker::new(ptr::null(), &VTABLE)
+    }
+    fn noop(_: *const ()) {}
+    static VTABLE: RawWakerVTable = RawWakerVTable::new(clone, noop, noop, noop);
+    // SAFETY: The functions in the vtable do nothing with the data.
+    unsafe { Waker::from_raw(RawWaker::new(ptr::null(), &VTABLE)) }
+}
+
+#[test]
+fn test_cast_pin_future() {
+    let source: Pin<Box<dyn Source>> = Box::pin(Countdown {
+        remaining: 2,
+        _pinned: PhantomPinned,
+    });
+    let mut future = source.cast_pin::<dyn Future<Output = u32>>().ok().unwrap();
+
+    let waker = noop_waker();
+    let mut cx = Context::from_waker(&waker);
+    assert_eq!(future.as_mut().poll(&mut cx), Poll::Pending);
+    assert_eq!(future.as_mut().poll(&mut cx), Poll::Pending);
+    assert_eq!(future.as_mut().poll(&mut cx), Poll::Ready(42));
+}
@@ -0,0 +1,62 @@
+#![cfg(not(feature = "disabled"))]
+
+use std::sync::Arc;
+
+use intertrait::cast::*;
+use intertrait::*;
+
+trait Node: CastFromSync {}
+
+trait Parent {
+    fn children(&self) -> usize;
+}
+
+trait Named {
+    fn name(&self) -> &'static str;
+}
+
+#[cast_to([sync] Parent, Named)]
+struct Branch;
+
+impl Node for Branch {}
+
+impl Parent for Branch {
+    fn children(&self) -> usize {
+        2
+    }
+}
+
+impl Named for Branch {
+    fn name(&self) -> &'static str {
+        "branch"
+    }
+}
+
+#[test]
+fn test_cast_ptr_eq() {
+    let node: Arc<dyn Node> = Arc::new(Branch);
+    let parent = node
+        .clone()
+        .cast::<dyn Parent>()
+        .unwrap_or_else(|_| panic!());
+    let named = node
+        .clone()
+        .cast::<dyn Named>()
+        .unwrap_or_else(|_| panic!());
+    assert_eq!(parent.children(), 2);
+    assert_eq!(named.name(), "branch");
+
+    assert!(cast_ptr_eq(&parent, &named));
+    assert!(cast_ptr_eq(&node, &parent));
+    assert!(cast_ptr_eq(&named, &node));
+    assert_eq!(Arc::strong_count(&node), 3);
+}
+
+#[test]
+fn test_cast_ptr_ne() {
+    let first: Arc<dyn Node> = Arc::new(Branch);
+    let second: Arc<dyn Node> = Arc::new(Branch);
+    let named = second.cast::<dyn Named>().unwrap_or_else(|_| panic!());
+
+    assert!(!cast_ptr_eq(&first, &named));
+}
@@ -0,0 +1,59 @@
+#![cfg(not(feature = "disabled"))]
+
+use std::any::{Any, TypeId};
+
+use intertrait::cast::*;
+use intertrait::*;
+
+#[cast_to]
+impl Counter for Data {
+    fn get(&self) -> u32 {
+        self.0
+    }
+
+    fn increment(&mut self) {
+        self.0 += 1;
+    }
+}
+
+struct Data(u32);
+
+trait Counter {
+    fn get(&self) -> u32;
+    fn increment(&mut self);
+}
+
+trait Other {}
+
+/// A handle passed across an FFI boundary.
+#[repr(C)]
+struct Handle {
+    ptr: *mut dyn Any,
+    type_id: TypeId,
+}
+
+fn new_handle<T: Any>(value: T) -> Handle {
+    Handle {
+        ptr: Box::into_raw(Box::new(value) as Box<dyn Any>),
+        type_id: TypeId::of::<T>(),
+    }
+}
+
+#[test]
+fn test_cast_ptr() {
+    let handle = new_handle(Data(1));
+    unsafe {
+        let counter = cast_ptr_mut::<dyn Counter>(handle.ptr, handle.type_id).unwrap();
+        (*counter).increment();
+        let counter = cast_ptr::<dyn Counter>(handle.ptr, handle.type_id).unwrap();
+        assert_eq!((*counter).get(), 2);
+
+        let data = cast_ptr::<Data>(handle.ptr, handle.type_id).unwrap();
+        assert_eq!((*data).0, 2);
+
+        assert!(cast_ptr::<dyn Other>(handle.ptr, handle.type_id).is_none());
+        assert!(cast_ptr_mut::<u32>(handle.ptr, handle.type_id).is_none());
+
+        drop(Box::from_raw(handle.ptr));
+    }
+}
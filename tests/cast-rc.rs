@@ -0,0 +1,80 @@
+#![cfg(not(feature = "disabled"))]
+
+use std::rc::Rc;
+
+use intertrait::cast::*;
+use intertrait::*;
+
+trait Source: CastFrom {}
+
+trait Greet {
+    fn greet(&self) -> &'static str;
+}
+
+trait Count {
+    fn count(&self) -> usize;
+}
+
+#[cast_to]
+impl Greet for Data {
+    fn greet(&self) -> &'static str {
+        "hello"
+    }
+}
+
+impl Count for Data {
+    fn count(&self) -> usize {
+        self.0
+    }
+}
+
+struct Data(usize);
+
+impl Source for Data {}
+
+castable_to! { Data => Count }
+
+#[test]
+fn test_cast_rc() {
+    let source: Rc<dyn Source> = Rc::new(Data(3));
+    let greet = source
+        .clone()
+        .cast::<dyn Greet>()
+        .unwrap_or_else(|_| panic!());
+    let count = source
+        .clone()
+        .cast::<dyn Count>()
+        .unwrap_or_else(|_| panic!());
+    assert_eq!(greet.greet(), "hello");
+    assert_eq!(count.count(), 3);
+
+    // The casts share the allocation of the source.
+    assert_eq!(Rc::strong_count(&source), 3);
+}
+
+#[test]
+fn test_cast_rc_fail() {
+    let source: Rc<dyn Source> = Rc::new(Data(3));
+    let other = source.clone();
+    let source = match source.cast::<dyn std::fmt::Debug>() {
+        Ok(_) => panic!("Data must not be cast into Debug"),
+        Err(source) => source,
+    };
+
+    assert!(Rc::ptr_eq(&source, &other));
+    assert_eq!(Rc::strong_count(&source), 2);
+}
+
+#[test]
+fn test_box_to_rc() {
+    let source: Box<dyn Source> = Box::new(Data(5));
+    let count = box_to_rc::<dyn Count, _>(source).ok().unwrap();
+    assert_eq!(count.count(), 5);
+    assert_eq!(Rc::strong_count(&count), 1);
+
+    let source: Box<dyn Source> = Box::new(Data(5));
+    let source = box_to_rc::<dyn std::fmt::Debug, _>(source)
+        .map(|_| ())
+        .unwrap_err();
+    assert!((*source).impls::<dyn Count>());
+}
@@ -0,0 +1,68 @@
+#![cfg(not(feature = "disabled"))]
+
+use intertrait::cast::*;
+use intertrait::*;
+
+trait Plugin: CastFrom {
+    fn name(&self) -> &'static str;
+}
+
+trait Render {
+    fn render(&self) -> String;
+}
+
+#[cast_to(Render)]
+struct Canvas(&'static str);
+
+struct Logger(&'static str);
+
+impl Plugin for Canvas {
+    fn name(&self) -> &'static str {
+        self.0
+    }
+}
+
+impl Plugin for Logger {
+    fn name(&self) -> &'static str {
+        self.0
+    }
+}
+
+impl Render for Canvas {
+    fn render(&self) -> String {
+        format!("<{}>", self.0)
+    }
+}
+
+fn plugins() -> Vec<Box<dyn Plugin>> {
+    vec![
+        Box::new(Canvas("a")),
+        Box::new(Logger("b")),
+        Box::new(Canvas("c")),
+        Box::new(Logger("d")),
+    ]
+}
+
+#[test]
+fn test_cast_vec() {
+    let (renders, others) = cast_vec::<dyn Render, _>(plugins());
+    let renders: Vec<String> = renders.iter().map(|render| render.render()).collect();
+    let others: Vec<&str> = others.iter().map(|plugin| plugin.name()).collect();
+    assert_eq!(renders, vec!["<a>", "<c>"]);
+    assert_eq!(others, vec!["b", "d"]);
+
+    let (renders, others) = cast_vec::<dyn Render, dyn Plugin>(Vec::new());
+    assert!(renders.is_empty() && others.is_empty());
+}
+
+#[test]
+fn test_retain_cast() {
+    let mut plugins = plugins();
+    let renders = retain_cast::<dyn Render, _>(&mut plugins);
+    assert_eq!(renders.len(), 2);
+    let names: Vec<&str> = plugins.iter().map(|plugin| plugin.name()).collect();
+    assert_eq!(names, vec!["b", "d"]);
+
+    assert!(retain_cast::<dyn Render, _>(&mut plugins).is_empty());
+    assert_eq!(plugins.len(), 2);
+}
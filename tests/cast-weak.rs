@@ -0,0 +1,65 @@
+#![cfg(not(feature = "disabled"))]
+
+use std::rc::Rc;
+use std::sync::Arc;
+
+use intertrait::cast::*;
+use intertrait::*;
+
+trait Source: CastFromSync {}
+
+trait Observer {
+    fn id(&self) -> u32;
+}
+
+#[cast_to([sync] Observer)]
+struct Data(u32);
+
+impl Observer for Data {
+    fn id(&self) -> u32 {
+        self.0
+    }
+}
+
+impl Source for Data {}
+
+struct Unregistered;
+
+impl Source for Unregistered {}
+
+#[test]
+fn test_cast_rc_weak() {
+    let source: Rc<dyn Source> = Rc::new(Data(1));
+    let weak = Rc::downgrade(&source);
+    let observer = weak.cast_weak::<dyn Observer>().unwrap();
+    assert_eq!(observer.upgrade().unwrap().id(), 1);
+    assert_eq!(Rc::strong_count(&source), 1);
+    assert_eq!(Rc::weak_count(&source), 2);
+
+    drop(source);
+    assert!(observer.upgrade().is_none());
+    assert!(weak.cast_weak::<dyn Observer>().is_none());
+}
+
+#[test]
+fn test_cast_arc_weak() {
+    let source: Arc<dyn Source> = Arc::new(Data(2));
+    let weak = Arc::downgrade(&source);
+    let observer = weak.cast_weak::<dyn Observer>().unwrap();
+    assert_eq!(observer.upgrade().unwrap().id(), 2);
+    assert_eq!(Arc::strong_count(&source), 1);
+
+    drop(source);
+    assert!(observer.upgrade().is_none());
+}
+
+#[test]
+fn test_cast_weak_unregistered() {
+    let source: Rc<dyn Source> = Rc::new(Unregistered);
+    assert!(Rc::downgrade(&source).cast_weak::<dyn Observer>().is_none());
+
+    let source: Arc<dyn Source> = Arc::new(Unregistered);
+    assert!(Arc::downgrade(&source)
+        .cast_weak::<dyn Observer>()
+        .is_none());
+}
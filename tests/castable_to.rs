@@ -1,3 +1,7 @@
+#![cfg(not(feature = "disabled"))]
+// The registrations are accepted by a crate forbidding unsafe code.
+#![forbid(unsafe_code)]
+
 use intertrait::cast::*;
 use intertrait::*;
 
@@ -0,0 +1,119 @@
+#![cfg(not(feature = "disabled"))]
+
+use std::any::TypeId;
+
+use intertrait::cast::*;
+use intertrait::*;
+
+trait Source: CastFrom {}
+
+trait Count {
+    fn count(&self) -> u32;
+
+    fn increment(&mut self);
+}
+
+#[cast_to(Count)]
+struct Data(u32);
+
+#[cast_to(Count)]
+struct Other(u32);
+
+struct Unregistered;
+
+impl Count for Data {
+    fn count(&self) -> u32 {
+        self.0
+    }
+
+    fn increment(&mut self) {
+        self.0 += 1;
+    }
+}
+
+impl Count for Other {
+    fn count(&self) -> u32 {
+        self.0 + 100
+    }
+
+    fn increment(&mut self) {
+        self.0 += 1;
+    }
+}
+
+impl Source for Data {}
+impl Source for Other {}
+impl Source for Unregistered {}
+
+#[test]
+fn test_cast_handled() {
+    let mut sources: Vec<Box<dyn Source>> = vec![Box::new(Data(1)), Box::new(Data(2))];
+    let (count, handle) = (*sources[0]).cast_handled::<dyn Count>().unwrap();
+    assert_eq!(count.count(), 1);
+    assert_eq!(handle.type_id(), TypeId::of::<Data>());
+
+    assert_eq!(handle.cast_ref(&*sources[1]).unwrap().count(), 2);
+    handle.cast_mut(&mut *sources[1]).unwrap().increment();
+    assert_eq!(handle.cast_ref(&*sources[1]).unwrap().count(), 3);
+}
+
+#[test]
+fn test_handle_rejects_other_types() {
+    let data: &dyn Source = &Data(1);
+    let (_, handle) = data.cast_handled::<dyn Count>().unwrap();
+
+    let mut other = Other(1);
+    assert!(handle.cast_ref::<dyn Source>(&other).is_none());
+    assert!(handle.cast_mut::<dyn Source>(&mut other).is_none());
+    assert!(handle.cast_ref::<dyn Source>(&Unregistered).is_none());
+}
+
+#[test]
+fn test_cast_handled_fails() {
+    let unregistered: &dyn Source = &Unregistered;
+    assert!(unregistered.cast_handled::<dyn Count>().is_none());
+}
+
+#[test]
+fn test_cast_slice() {
+    let (a, b, c) = (Data(1), Data(2), Data(3));
+    let sources: Vec<&dyn Source> = vec![&a, &b, &c];
+    let counts: Vec<u32> = cast_slice::<_, dyn Count>(&sources)
+        .unwrap()
+        .iter()
+        .map(|count| count.count())
+        .collect();
+    assert_eq!(counts, vec![1, 2, 3]);
+
+    let empty: Vec<&dyn Source> = Vec::new();
+    assert!(cast_slice::<_, dyn Count>(&empty).unwrap().is_empty());
+}
+
+#[test]
+fn test_cast_slice_of_mixed_types() {
+    let (data, other) = (Data(1), Other(2));
+    // Both are castable, but not with the caster for the first.
+    let sources: Vec<&dyn Source> = vec![&data, &other];
+    assert!(cast_slice::<_, dyn Count>(&sources).is_none());
+
+    let sources: Vec<&dyn Source> = vec![&Unregistered, &data];
+    assert!(cast_slice::<_, dyn Count>(&sources).is_none());
+}
+
+#[test]
+fn test_caster_of() {
+    let sources: Vec<Box<dyn Source>> = vec![Box::new(Data(1)), Box::new(Data(2))];
+    let handle = caster_of::<dyn Count, _>(&*sources[0]).unwrap();
+    assert_eq!(handle.type_id(), TypeId::of::<Data>());
+    let counts: Vec<u32> = sources
+        .into_iter()
+        .map(|source| handle.cast_box(source).ok().unwrap().count())
+        .collect();
+    assert_eq!(counts, vec![1, 2]);
+
+    let other: Box<dyn Source> = Box::new(Other(1));
+    let other = handle.cast_box(other).map(|_| ()).unwrap_err();
+    assert!((*other).impls::<dyn Count>());
+
+    assert!(caster_of::<dyn Count, _>(&Unregistered as &dyn Source).is_none());
+}
@@ -0,0 +1,42 @@
+#![cfg(not(feature = "disabled"))]
+
+use std::panic::catch_unwind;
+
+use intertrait::*;
+use linkme::distributed_slice;
+
+trait Greet {}
+trait Wave {}
+trait Dance {}
+
+#[cast_to(Greet, Wave)]
+struct Data;
+
+impl Greet for Data {}
+impl Wave for Data {}
+
+#[cast_to]
+impl Dance for Data {}
+
+struct Other;
+
+impl Greet for Other {}
+
+castable_to!(Other => Greet);
+
+// Simulates a caster stripped by the linker, whose marker is left alone.
+#[distributed_slice(LINK_MARKERS)]
+fn stripped() -> LinkMarker {
+    LinkMarker::new::<Other, dyn Dance>()
+}
+
+#[test]
+fn test_assert_all_linked() {
+    let err = catch_unwind(assert_all_linked).unwrap_err();
+    let msg = err.downcast_ref::<String>().unwrap();
+    assert_eq!(
+        msg,
+        "1 of 5 casters are missing from the registry: \
+         check_linking::Other => dyn check_linking::Dance"
+    );
+}
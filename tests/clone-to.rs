@@ -0,0 +1,48 @@
+use dyn_clone::DynClone;
+use intertrait::cast::*;
+use intertrait::*;
+
+trait Template: CastFrom {}
+
+trait Shape: DynClone {
+    fn area(&self) -> u32;
+
+    fn scale(&mut self, factor: u32);
+}
+
+trait Unknown: DynClone {}
+
+#[cast_to(Shape)]
+#[derive(Clone)]
+struct Square(u32);
+
+impl Template for Square {}
+
+impl Shape for Square {
+    fn area(&self) -> u32 {
+        self.0 * self.0
+    }
+
+    fn scale(&mut self, factor: u32) {
+        self.0 *= factor;
+    }
+}
+
+#[test]
+fn test_clone_to() {
+    let template: Box<dyn Template> = Box::new(Square(2));
+    let mut first = (*template).clone_to::<dyn Shape>().unwrap();
+    let second = (*template).clone_to::<dyn Shape>().unwrap();
+    first.scale(3);
+
+    // The clones are independent of each other and of the template.
+    assert_eq!(first.area(), 36);
+    assert_eq!(second.area(), 4);
+    assert_eq!((*template).cast::<dyn Shape>().unwrap().area(), 4);
+}
+
+#[test]
+fn test_clone_to_unregistered() {
+    let template: &dyn Template = &Square(2);
+    assert!(template.clone_to::<dyn Unknown>().is_none());
+}
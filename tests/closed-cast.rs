@@ -0,0 +1,62 @@
+use intertrait::cast::*;
+use intertrait::*;
+
+mod shapes {
+    pub struct Circle(pub f64);
+    pub struct Square(pub f64);
+}
+
+use shapes::{Circle, Square};
+
+trait Draw {
+    fn draw(&self) -> String;
+}
+
+trait Scale {
+    fn scale(&mut self, factor: f64);
+}
+
+impl Draw for Circle {
+    fn draw(&self) -> String {
+        format!("circle {}", self.0)
+    }
+}
+
+impl Scale for Circle {
+    fn scale(&mut self, factor: f64) {
+        self.0 *= factor;
+    }
+}
+
+impl Draw for Square {
+    fn draw(&self) -> String {
+        format!("square {}", self.0)
+    }
+}
+
+impl Scale for Square {
+    fn scale(&mut self, factor: f64) {
+        self.0 *= factor;
+    }
+}
+
+closed_cast! {
+    enum Node: shapes::Circle, shapes::Square => dyn Draw, dyn Scale + 'static,
+}
+
+#[test]
+fn test_closed_cast() {
+    let nodes = [Node::from(Circle(1.0)), Node::from(Square(2.0))];
+    let drawn: Vec<_> = nodes
+        .iter()
+        .map(|node| node.closed_cast::<dyn Draw>().unwrap().draw())
+        .collect();
+    assert_eq!(drawn, ["circle 1", "square 2"]);
+}
+
+#[test]
+fn test_closed_cast_mut() {
+    let mut node = Node::Square(Square(2.0));
+    node.closed_cast_mut::<dyn Scale>().unwrap().scale(1.5);
+    assert_eq!(node.closed_cast::<dyn Draw>().unwrap().draw(), "square 3");
+}
@@ -0,0 +1,90 @@
+#![cfg(not(feature = "disabled"))]
+
+use intertrait::cast::*;
+use intertrait::*;
+
+struct Matrix<const N: usize>([[i64; N]; N]);
+
+trait Source: CastFrom {}
+
+impl<const N: usize> Source for Matrix<N> {}
+
+trait Determinant {
+    fn determinant(&self) -> i64;
+}
+
+trait Size {
+    fn size(&self) -> usize;
+}
+
+impl Determinant for Matrix<1> {
+    fn determinant(&self) -> i64 {
+        self.0[0][0]
+    }
+}
+
+impl Determinant for Matrix<2> {
+    fn determinant(&self) -> i64 {
+        let m = &self.0;
+        m[0][0] * m[1][1] - m[0][1] * m[1][0]
+    }
+}
+
+impl<const N: usize> Size for Matrix<N> {
+    fn size(&self) -> usize {
+        N
+    }
+}
+
+castable_to!(Matrix<1> => Determinant, Size);
+castable_to!(Matrix<{ 1 + 1 }> => Determinant, Size);
+
+castable_to!(Matrix<3> => Size);
+
+#[cast_to]
+impl Determinant for Matrix<3> {
+    fn determinant(&self) -> i64 {
+        let m = &self.0;
+        (0..3)
+            .map(|i| {
+                m[0][i]
+                    * (m[1][(i + 1) % 3] * m[2][(i + 2) % 3]
+                        - m[1][(i + 2) % 3] * m[2][(i + 1) % 3])
+            })
+            .sum()
+    }
+}
+
+#[test]
+fn test_cast_const_generic_instantiations() {
+    let one: Box<dyn Source> = Box::new(Matrix([[7]]));
+    let two: Box<dyn Source> = Box::new(Matrix([[1, 2], [3, 4]]));
+    let three: Box<dyn Source> = Box::new(Matrix([[2, 0, 0], [0, 3, 0], [0, 0, 4]]));
+    let four: Box<dyn Source> = Box::new(Matrix([[0; 4]; 4]));
+
+    assert_eq!((*one).cast::<dyn Determinant>().unwrap().determinant(), 7);
+    assert_eq!((*two).cast::<dyn Determinant>().unwrap().determinant(), -2);
+    assert_eq!(
+        (*three).cast::<dyn Determinant>().unwrap().determinant(),
+        24
+    );
+    assert!((*four).cast::<dyn Determinant>().is_none());
+
+    assert_eq!((*one).cast::<dyn Size>().unwrap().size(), 1);
+    assert_eq!((*two).cast::<dyn Size>().unwrap().size(), 2);
+    assert_eq!((*three).cast::<dyn Size>().unwrap().size(), 3);
+    assert!((*four).cast::<dyn Size>().is_none());
+}
+
+#[test]
+fn test_const_generic_instantiations_register_distinctly() {
+    let mut sizes: Vec<_> = impls_of::<dyn Size>().collect();
+    sizes.sort();
+    let mut expected = vec![
+        std::any::TypeId::of::<Matrix<1>>(),
+        std::any::TypeId::of::<Matrix<2>>(),
+        std::any::TypeId::of::<Matrix<3>>(),
+    ];
+    expected.sort();
+    assert_eq!(sizes, expected);
+}
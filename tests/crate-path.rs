@@ -0,0 +1,83 @@
+#![cfg(not(feature = "disabled"))]
+
+use intertrait::cast::*;
+use intertrait::{cast_to, castable_to, CastFrom};
+
+mod reexport {
+    // The registrations reach `linkme` through the crate path as well.
+    pub mod intertrait {
+        pub use ::intertrait::*;
+
+        pub mod linkme {
+            pub use ::intertrait::linkme::*;
+        }
+    }
+}
+
+trait Source: CastFrom {}
+
+trait Greet {
+    fn greet(&self) -> &'static str;
+}
+
+#[cast_to(crate = crate::reexport::intertrait, Greet)]
+struct Inline;
+
+impl Greet for Inline {
+    fn greet(&self) -> &'static str {
+        "inline"
+    }
+}
+
+impl Source for Inline {}
+
+#[cast_to(Greet)]
+#[intertrait(crate = crate::reexport::intertrait)]
+struct Separate;
+
+impl Greet for Separate {
+    fn greet(&self) -> &'static str {
+        "separate"
+    }
+}
+
+impl Source for Separate {}
+
+struct OnImpl;
+
+#[cast_to(crate = reexport::intertrait, [sync])]
+impl Greet for OnImpl {
+    fn greet(&self) -> &'static str {
+        "on impl"
+    }
+}
+
+impl Source for OnImpl {}
+
+struct OnMacro;
+
+impl Greet for OnMacro {
+    fn greet(&self) -> &'static str {
+        "on macro"
+    }
+}
+
+impl Source for OnMacro {}
+
+castable_to! { OnMacro => crate = reexport::intertrait, Greet }
+
+fn greet(source: &dyn Source) -> &'static str {
+    source.cast::<dyn Greet>().unwrap().greet()
+}
+
+#[test]
+fn test_inline_crate_path() {
+    assert_eq!(greet(&Inline), "inline");
+    assert_eq!(greet(&OnImpl), "on impl");
+    assert_eq!(greet(&OnMacro), "on macro");
+}
+
+#[test]
+fn test_separate_crate_path() {
+    assert_eq!(greet(&Separate), "separate");
+}
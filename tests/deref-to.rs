@@ -0,0 +1,127 @@
+#![cfg(not(feature = "disabled"))]
+
+use std::ops::{Deref, DerefMut};
+
+use intertrait::cast::*;
+use intertrait::*;
+
+trait Source: CastFrom {}
+
+trait Greet {
+    fn greet(&self) -> String;
+}
+
+trait Count {
+    fn count(&mut self) -> u32;
+}
+
+#[cast_to(Greet, Count)]
+struct Inner(u32);
+
+impl Greet for Inner {
+    fn greet(&self) -> String {
+        format!("Inner {}", self.0)
+    }
+}
+
+impl Count for Inner {
+    fn count(&mut self) -> u32 {
+        self.0 += 1;
+        self.0
+    }
+}
+
+#[cast_to(deref_mut_to = Inner)]
+struct Outer(Inner);
+
+impl Deref for Outer {
+    type Target = Inner;
+
+    fn deref(&self) -> &Inner {
+        &self.0
+    }
+}
+
+impl DerefMut for Outer {
+    fn deref_mut(&mut self) -> &mut Inner {
+        &mut self.0
+    }
+}
+
+impl Source for Outer {}
+
+// Dereferences to `Outer`, which in turn dereferences to `Inner`, only immutably.
+struct Shared(Outer);
+
+impl Deref for Shared {
+    type Target = Outer;
+
+    fn deref(&self) -> &Outer {
+        &self.0
+    }
+}
+
+impl Source for Shared {}
+
+castable_to!(Shared => deref_to = Outer);
+
+// Registered for `Greet` itself, which takes precedence over the `Deref` relationship.
+#[cast_to(deref_to = Inner, Greet)]
+struct Own(Inner);
+
+impl Deref for Own {
+    type Target = Inner;
+
+    fn deref(&self) -> &Inner {
+        &self.0
+    }
+}
+
+impl Greet for Own {
+    fn greet(&self) -> String {
+        "Own".to_owned()
+    }
+}
+
+impl Source for Own {}
+
+#[test]
+fn test_cast_ref_through_deref() {
+    let outer = Outer(Inner(1));
+    let source: &dyn Source = &outer;
+    assert!(source.impls::<dyn Greet>());
+    assert_eq!(source.cast::<dyn Greet>().unwrap().greet(), "Inner 1");
+}
+
+#[test]
+fn test_cast_mut_through_deref_mut() {
+    let mut outer = Outer(Inner(1));
+    let source: &mut dyn Source = &mut outer;
+    assert_eq!(source.cast::<dyn Count>().unwrap().count(), 2);
+    assert_eq!(outer.0 .0, 2);
+}
+
+#[test]
+fn test_cast_through_deref_chain() {
+    let mut shared = Shared(Outer(Inner(3)));
+    let source: &mut dyn Source = &mut shared;
+    assert!((*source).impls::<dyn Count>());
+    assert_eq!((*source).cast::<dyn Greet>().unwrap().greet(), "Inner 3");
+    // `Shared` doesn't implement `DerefMut`.
+    assert!(source.cast::<dyn Count>().is_none());
+}
+
+#[test]
+fn test_registered_cast_precedes_deref() {
+    let own = Own(Inner(4));
+    let source: &dyn Source = &own;
+    assert_eq!(source.cast::<dyn Greet>().unwrap().greet(), "Own");
+    assert!(source.impls::<dyn Count>());
+}
+
+#[test]
+fn test_cast_box_not_through_deref() {
+    let source: Box<dyn Source> = Box::new(Outer(Inner(5)));
+    assert!(!source.impls::<dyn std::fmt::Debug>());
+    assert!(source.cast::<dyn Greet>().is_err());
+}
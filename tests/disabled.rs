@@ -0,0 +1,53 @@
+use intertrait::cast::*;
+use intertrait::*;
+
+#[cast_to(Greet)]
+#[derive(Debug)]
+struct Data;
+
+struct Wrapper(Data);
+
+trait Source: CastFrom {}
+
+trait Greet {
+    fn greet(&self) -> &'static str;
+}
+
+#[cast_to]
+impl Greet for Data {
+    fn greet(&self) -> &'static str {
+        "Hello"
+    }
+}
+
+impl std::ops::Deref for Wrapper {
+    type Target = Data;
+
+    fn deref(&self) -> &Data {
+        &self.0
+    }
+}
+
+castable_to!(Data => std::fmt::Debug);
+castable_to!(Wrapper => deref_to = Data);
+
+impl Source for Data {}
+impl Source for Wrapper {}
+
+#[test]
+fn test_casts_fail_when_disabled() {
+    let data = Data;
+    assert_eq!(data.greet(), "Hello");
+
+    let source: &dyn Source = &data;
+    assert!(source.cast::<dyn Greet>().is_none());
+    assert!(!source.impls::<dyn Greet>());
+    let wrapper: &dyn Source = &Wrapper(Data);
+    assert!(wrapper.cast::<dyn Greet>().is_none());
+    let source: Box<dyn Source> = Box::new(Data);
+    assert!(source.cast::<dyn std::fmt::Debug>().is_err());
+
+    assert_eq!(impls_of::<dyn Greet>().count(), 0);
+    assert!(registry_snapshot().is_empty());
+    assert!(validate_registry().is_empty());
+}
@@ -0,0 +1,55 @@
+#![cfg(not(feature = "disabled"))]
+
+use intertrait::cast::*;
+use intertrait::*;
+
+trait Source: CastFrom {}
+
+trait Greet {
+    fn greet(&self) -> &'static str;
+}
+
+#[cast_to(Greet)]
+struct Data(u32);
+
+struct Other;
+
+impl Source for Data {}
+
+impl Source for Other {}
+
+impl Greet for Data {
+    fn greet(&self) -> &'static str {
+        "hello"
+    }
+}
+
+#[test]
+fn test_downcast_ref() {
+    let source: &dyn Source = &Data(1);
+    assert_eq!(source.cast::<Data>().unwrap().0, 1);
+    assert!(source.cast::<Other>().is_none());
+    assert!(source.impls::<Data>());
+    assert!(!source.impls::<Other>());
+    assert_eq!(source.expect_cast::<Data>().0, 1);
+
+    // Casting into a trait is unaffected.
+    assert_eq!(source.cast::<dyn Greet>().unwrap().greet(), "hello");
+}
+
+#[test]
+fn test_downcast_mut() {
+    let mut data = Data(1);
+    let source: &mut dyn Source = &mut data;
+    source.cast::<Data>().unwrap().0 += 1;
+    assert!(source.cast::<Other>().is_none());
+    assert_eq!(data.0, 2);
+}
+
+#[test]
+fn test_downcast_box() {
+    let source: Box<dyn Source> = Box::new(Data(1));
+    let source = source.cast::<Other>().map(|_| ()).unwrap_err();
+    let data: Box<Data> = source.cast::<Data>().ok().unwrap();
+    assert_eq!(data.0, 1);
+}
@@ -0,0 +1,30 @@
+use std::any::type_name;
+
+use intertrait::*;
+
+trait Greet {}
+
+trait Wave {}
+
+#[cast_to(Greet, Wave)]
+struct Data;
+
+impl Greet for Data {}
+
+impl Wave for Data {}
+
+struct Other;
+
+#[cast_to]
+impl Greet for Other {}
+
+#[test]
+fn test_dump() {
+    let mut expected = vec![
+        (type_name::<Data>(), type_name::<dyn Greet>()),
+        (type_name::<Data>(), type_name::<dyn Wave>()),
+        (type_name::<Other>(), type_name::<dyn Greet>()),
+    ];
+    expected.sort();
+    assert_eq!(dump(), expected);
+}
@@ -0,0 +1,90 @@
+#![cfg(not(feature = "disabled"))]
+
+use std::any::TypeId;
+use std::collections::HashMap;
+
+use intertrait::cast::*;
+use intertrait::*;
+
+trait Greet {
+    fn greet(&self) -> String;
+}
+
+trait Rename {
+    fn rename(&mut self, name: &str);
+}
+
+#[cast_to(Greet, Rename)]
+struct Person(String);
+
+impl Greet for Person {
+    fn greet(&self) -> String {
+        format!("Hello, {}", self.0)
+    }
+}
+
+impl Rename for Person {
+    fn rename(&mut self, name: &str) {
+        self.0 = name.to_string();
+    }
+}
+
+#[cast_to(Greet)]
+struct Robot;
+
+impl Greet for Robot {
+    fn greet(&self) -> String {
+        "Beep".to_string()
+    }
+}
+
+#[test]
+fn test_impls_type_id() {
+    let person: Box<dyn DynCast> = Box::new(Person("Alice".to_string()));
+    assert!((*person).impls_type_id(TypeId::of::<dyn Greet>()));
+    assert!((*person).impls_type_id(TypeId::of::<dyn Rename>()));
+    assert!((*person).impls_type_id(TypeId::of::<Person>()));
+    assert!(!(*person).impls_type_id(TypeId::of::<Robot>()));
+
+    // The generic casts work on `dyn DynCast` as well.
+    assert_eq!(
+        (*person).cast::<dyn Greet>().unwrap().greet(),
+        "Hello, Alice"
+    );
+}
+
+#[test]
+fn test_query_by_type_id() {
+    let mut components: HashMap<&str, Box<dyn DynCast>> = HashMap::new();
+    components.insert("person", Box::new(Person("Alice".to_string())));
+    components.insert("robot", Box::new(Robot));
+
+    let mut renamable: Vec<_> = components
+        .iter()
+        .filter(|(_, component)| (***component).impls_type_id(TypeId::of::<dyn Rename>()))
+        .map(|(name, _)| *name)
+        .collect();
+    renamable.sort();
+    assert_eq!(renamable, vec!["person"]);
+
+    let robot = (*components["robot"])
+        .cast_erased(TypeId::of::<dyn Greet>())
+        .unwrap();
+    assert_eq!(robot.trait_id(), TypeId::of::<dyn Greet>());
+    assert!(robot.downcast::<dyn Rename>().is_none());
+    assert_eq!(robot.downcast::<dyn Greet>().unwrap().greet(), "Beep");
+    assert!((*components["robot"])
+        .cast_erased(TypeId::of::<dyn Rename>())
+        .is_none());
+
+    let person = components.get_mut("person").unwrap();
+    let erased = (**person)
+        .cast_erased_mut(TypeId::of::<dyn Rename>())
+        .unwrap();
+    let erased = erased.downcast::<dyn Greet>().map(|_| ()).unwrap_err();
+    erased.downcast::<dyn Rename>().ok().unwrap().rename("Bob");
+    assert_eq!(
+        (**person).cast::<dyn Greet>().unwrap().greet(),
+        "Hello, Bob"
+    );
+}
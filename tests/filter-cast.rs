@@ -0,0 +1,72 @@
+#![cfg(not(feature = "disabled"))]
+
+use intertrait::cast::*;
+use intertrait::*;
+
+trait Source: CastFrom {}
+
+trait Count {
+    fn count(&self) -> usize;
+
+    fn increment(&mut self);
+}
+
+#[cast_to(Count)]
+struct Counter(usize);
+
+struct Other;
+
+impl Source for Counter {}
+
+impl Source for Other {}
+
+impl Count for Counter {
+    fn count(&self) -> usize {
+        self.0
+    }
+
+    fn increment(&mut self) {
+        self.0 += 1;
+    }
+}
+
+fn sources() -> Vec<Box<dyn Source>> {
+    vec![Box::new(Counter(1)), Box::new(Other), Box::new(Counter(2))]
+}
+
+#[test]
+fn test_filter_cast_refs() {
+    let (a, b, c) = (Counter(1), Other, Counter(2));
+    let sources: Vec<&dyn Source> = vec![&a, &b, &c];
+    let counts: Vec<usize> = sources
+        .into_iter()
+        .filter_cast::<dyn Count>()
+        .map(|count| count.count())
+        .collect();
+    assert_eq!(counts, vec![1, 2]);
+}
+
+#[test]
+fn test_filter_cast_muts() {
+    let mut sources = sources();
+    sources
+        .iter_mut()
+        .map(|source| &mut **source)
+        .filter_cast::<dyn Count>()
+        .for_each(|count| count.increment());
+
+    let counts: Vec<usize> = sources
+        .iter()
+        .map(|source| &**source)
+        .filter_cast::<dyn Count>()
+        .map(|count| count.count())
+        .collect();
+    assert_eq!(counts, vec![2, 3]);
+}
+
+#[test]
+fn test_filter_cast_boxes() {
+    let counts: Vec<Box<dyn Count>> = sources().into_iter().filter_cast::<dyn Count>().collect();
+    assert_eq!(counts.len(), 2);
+    assert_eq!(counts[1].count(), 2);
+}
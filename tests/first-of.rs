@@ -0,0 +1,79 @@
+#![cfg(not(feature = "disabled"))]
+
+use intertrait::cast::*;
+use intertrait::*;
+
+trait Source: CastFrom {}
+
+trait GpuDraw {
+    fn draw_gpu(&self) -> &'static str;
+}
+
+trait CpuDraw {
+    fn draw_cpu(&self) -> &'static str;
+}
+
+trait Print {}
+
+#[cast_to(GpuDraw, CpuDraw)]
+struct Accelerated;
+
+impl GpuDraw for Accelerated {
+    fn draw_gpu(&self) -> &'static str {
+        "gpu"
+    }
+}
+
+impl CpuDraw for Accelerated {
+    fn draw_cpu(&self) -> &'static str {
+        "cpu"
+    }
+}
+
+impl Source for Accelerated {}
+
+#[cast_to(CpuDraw)]
+struct Software;
+
+impl CpuDraw for Software {
+    fn draw_cpu(&self) -> &'static str {
+        "cpu"
+    }
+}
+
+impl Source for Software {}
+
+struct Headless;
+
+impl Source for Headless {}
+
+fn draw(source: &dyn Source) -> Option<&'static str> {
+    source
+        .first_of::<(&dyn GpuDraw, &dyn CpuDraw)>()
+        .map(|found| match found {
+            OneOf2::First(gpu) => gpu.draw_gpu(),
+            OneOf2::Second(cpu) => cpu.draw_cpu(),
+        })
+}
+
+#[test]
+fn test_first_of_in_order() {
+    assert_eq!(draw(&Accelerated), Some("gpu"));
+    assert_eq!(draw(&Software), Some("cpu"));
+    assert_eq!(draw(&Headless), None);
+}
+
+#[test]
+fn test_first_of_more() {
+    let source: &dyn Source = &Software;
+    match source.first_of::<(&dyn Print, &dyn GpuDraw, &Software)>() {
+        Some(OneOf3::Third(_)) => {}
+        _ => panic!("unexpected result"),
+    }
+
+    let found = source.first_of::<(&dyn Print, &dyn GpuDraw, &Headless, &dyn CpuDraw)>();
+    match found {
+        Some(OneOf4::Fourth(cpu)) => assert_eq!(cpu.draw_cpu(), "cpu"),
+        _ => panic!("unexpected result"),
+    }
+}
@@ -0,0 +1,61 @@
+#![cfg(not(feature = "disabled"))]
+
+use std::fmt;
+
+use intertrait::cast::*;
+use intertrait::*;
+
+// Simulates a crate providing a trait blanket-implemented for the types of another trait.
+mod x {
+    pub trait Serialize {
+        fn serialize(&self) -> String;
+    }
+
+    pub trait MySerialize {
+        fn my_serialize(&self) -> String;
+    }
+
+    impl<T: Serialize> MySerialize for T {
+        fn my_serialize(&self) -> String {
+            format!("<{}>", self.serialize())
+        }
+    }
+}
+
+struct Data;
+
+impl x::Serialize for Data {
+    fn serialize(&self) -> String {
+        "data".to_owned()
+    }
+}
+
+impl fmt::Display for Data {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "displayed")
+    }
+}
+
+trait Source: CastFrom {}
+
+impl Source for Data {}
+
+// Neither `x::MySerialize` nor `x::Serialize` is in scope here.
+castable_to!(Data => x::MySerialize);
+
+// `ToString` is blanket-implemented for `Display` types in `std`.
+castable_to!(Data => ToString);
+
+#[test]
+fn test_cast_to_trait_of_blanket_impl() {
+    let source: &dyn Source = &Data;
+    let serialize = source.cast::<dyn x::MySerialize>().unwrap();
+    assert_eq!(serialize.my_serialize(), "<data>");
+}
+
+#[test]
+fn test_cast_to_trait_of_foreign_blanket_impl() {
+    let source: &dyn Source = &Data;
+    let to_string = source.cast::<dyn ToString>().unwrap();
+    assert_eq!(to_string.to_string(), "displayed");
+}
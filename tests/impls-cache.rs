@@ -0,0 +1,66 @@
+use std::any::{Any, TypeId};
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::thread;
+
+use intertrait::cast::*;
+use intertrait::dynamic::{register_caster, set_cast_fallback};
+use intertrait::*;
+
+trait Source: CastFrom {}
+
+trait Greet {
+    fn greet(&self) -> &'static str;
+}
+
+struct Data;
+
+impl Greet for Data {
+    fn greet(&self) -> &'static str {
+        "Hello"
+    }
+}
+
+impl Source for Data {}
+
+trait Unknown {}
+
+static FALLBACK_CALLS: AtomicUsize = AtomicUsize::new(0);
+
+fn fallback(_: TypeId, trait_id: TypeId) -> Option<&'static (dyn Any + Send + Sync)> {
+    if trait_id == TypeId::of::<dyn Unknown>() {
+        FALLBACK_CALLS.fetch_add(1, Ordering::SeqCst);
+    }
+    None
+}
+
+// The cache is invalidated by any change of the casts allowed at runtime, so the cases are
+// run in sequence in a single test.
+#[test]
+fn test_cached_impls() {
+    set_cast_fallback(fallback);
+    let source: &dyn Source = &Data;
+    assert!(!source.impls::<dyn Unknown>());
+    assert!(!source.impls::<dyn Unknown>());
+    assert_eq!(FALLBACK_CALLS.load(Ordering::SeqCst), 1);
+
+    // The result cached by a thread is shared by the others.
+    thread::spawn(|| {
+        let source: &dyn Source = &Data;
+        assert!(!source.impls::<dyn Unknown>());
+    })
+    .join()
+    .unwrap();
+    assert_eq!(FALLBACK_CALLS.load(Ordering::SeqCst), 1);
+
+    assert!(!source.impls::<dyn Greet>());
+    assert!(!source.impls::<dyn Greet>());
+
+    register_caster::<Data, dyn Greet>(Caster::new(
+        |from| from.downcast_ref::<Data>().unwrap(),
+        |from| from.downcast_mut::<Data>().unwrap(),
+        |from| from.downcast::<Data>().unwrap(),
+        |from| from.downcast::<Data>().unwrap(),
+    ));
+    assert!(source.impls::<dyn Greet>());
+    assert_eq!(source.cast::<dyn Greet>().unwrap().greet(), "Hello");
+}
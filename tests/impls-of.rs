@@ -0,0 +1,67 @@
+#![cfg(not(feature = "disabled"))]
+
+use std::any::TypeId;
+
+use intertrait::*;
+
+trait Render {}
+
+trait Update {}
+
+#[cast_to(Render, Update)]
+struct Sprite;
+
+impl Render for Sprite {}
+
+impl Update for Sprite {}
+
+#[cast_to(Render)]
+struct Text;
+
+impl Render for Text {}
+
+struct Timer;
+
+#[cast_to]
+impl Update for Timer {}
+
+#[test]
+fn test_impls_of() {
+    let mut renderers: Vec<TypeId> = impls_of::<dyn Render>().collect();
+    renderers.sort();
+    let mut expected = vec![TypeId::of::<Sprite>(), TypeId::of::<Text>()];
+    expected.sort();
+    assert_eq!(renderers, expected);
+
+    let mut updaters: Vec<TypeId> = impls_of::<dyn Update>().collect();
+    updaters.sort();
+    let mut expected = vec![TypeId::of::<Sprite>(), TypeId::of::<Timer>()];
+    expected.sort();
+    assert_eq!(updaters, expected);
+
+    assert_eq!(impls_of::<dyn std::fmt::Debug>().count(), 0);
+}
+
+#[test]
+fn test_traits_of() {
+    let mut traits: Vec<(TypeId, &str)> = traits_of(&Sprite).collect();
+    traits.sort();
+    let mut expected = vec![
+        (
+            TypeId::of::<dyn Render>(),
+            std::any::type_name::<dyn Render>(),
+        ),
+        (
+            TypeId::of::<dyn Update>(),
+            std::any::type_name::<dyn Update>(),
+        ),
+    ];
+    expected.sort();
+    assert_eq!(traits, expected);
+
+    let timer: &dyn std::any::Any = &Timer;
+    let traits: Vec<TypeId> = traits_of(timer).map(|(trait_id, _)| trait_id).collect();
+    assert_eq!(traits, vec![TypeId::of::<dyn Update>()]);
+
+    assert_eq!(traits_of(&0u8).count(), 0);
+}
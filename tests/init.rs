@@ -0,0 +1,29 @@
+#![cfg(not(feature = "disabled"))]
+
+use intertrait::cast::*;
+use intertrait::*;
+
+#[cast_to(Greet)]
+struct Data;
+
+trait Source: CastFrom {}
+
+trait Greet {
+    fn greet(&self) -> &'static str;
+}
+
+impl Greet for Data {
+    fn greet(&self) -> &'static str {
+        "hello"
+    }
+}
+
+impl Source for Data {}
+
+#[test]
+fn test_cast_after_init() {
+    init();
+    init();
+    let source: &dyn Source = &Data;
+    assert_eq!(source.cast::<dyn Greet>().unwrap().greet(), "hello");
+}
@@ -0,0 +1,90 @@
+#![cfg(not(feature = "disabled"))]
+
+use intertrait::cast::*;
+use intertrait::*;
+
+trait Source: CastFrom {}
+
+// Object-safe as the associated functions are excluded from trait objects.
+trait Construct {
+    fn construct() -> Self
+    where
+        Self: Sized;
+
+    fn name() -> &'static str
+    where
+        Self: Sized;
+}
+
+// Not object-safe, so it can only be registered as a marker.
+#[cast_marker]
+trait Spawn {
+    fn spawn() -> Self;
+}
+
+#[cast_marker]
+pub trait Persist {
+    const TABLE: &'static str;
+}
+
+#[cast_to(Construct, marker Spawn)]
+struct Data;
+
+struct Other;
+
+struct Unregistered;
+
+impl Construct for Data {
+    fn construct() -> Self {
+        Data
+    }
+
+    fn name() -> &'static str {
+        "data"
+    }
+}
+
+impl Spawn for Data {
+    fn spawn() -> Self {
+        Data
+    }
+}
+
+impl Spawn for Other {
+    fn spawn() -> Self {
+        Other
+    }
+}
+
+impl Persist for Other {
+    const TABLE: &'static str = "others";
+}
+
+castable_to!(Other => marker Spawn, marker Persist);
+
+impl Source for Data {}
+impl Source for Other {}
+impl Source for Unregistered {}
+
+#[test]
+fn test_cast_to_object_safe_trait_of_associated_functions() {
+    let data = Data::construct();
+    assert_eq!(Data::name(), "data");
+    let source: &dyn Source = &data;
+    assert!(source.impls::<dyn Construct>());
+    assert!(source.cast::<dyn Construct>().is_some());
+}
+
+#[test]
+fn test_impls_marker() {
+    let data: &dyn Source = &Data::spawn();
+    let other: &dyn Source = &Other::spawn();
+    let unregistered: &dyn Source = &Unregistered;
+    assert_eq!(Other::TABLE, "others");
+
+    assert!(data.impls_marker::<SpawnMarker>());
+    assert!(!data.impls_marker::<PersistMarker>());
+    assert!(other.impls_marker::<SpawnMarker>());
+    assert!(other.impls_marker::<PersistMarker>());
+    assert!(!unregistered.impls_marker::<SpawnMarker>());
+}
@@ -0,0 +1,99 @@
+#![cfg(not(feature = "disabled"))]
+
+use intertrait::*;
+
+trait Source: CastFrom {}
+
+trait GpuDraw {
+    fn draw_gpu(&self) -> String;
+}
+
+trait CpuDraw {
+    fn draw_cpu(&self) -> String;
+}
+
+trait Resize {
+    fn resize(&mut self, size: u32);
+}
+
+#[cast_to(GpuDraw, CpuDraw)]
+struct Accelerated;
+
+impl GpuDraw for Accelerated {
+    fn draw_gpu(&self) -> String {
+        "gpu".to_string()
+    }
+}
+
+impl CpuDraw for Accelerated {
+    fn draw_cpu(&self) -> String {
+        "cpu".to_string()
+    }
+}
+
+impl Source for Accelerated {}
+
+#[cast_to(CpuDraw, Resize)]
+struct Software(u32);
+
+impl CpuDraw for Software {
+    fn draw_cpu(&self) -> String {
+        format!("cpu {}", self.0)
+    }
+}
+
+impl Resize for Software {
+    fn resize(&mut self, size: u32) {
+        self.0 = size;
+    }
+}
+
+impl Source for Software {}
+
+struct Headless;
+
+impl Source for Headless {}
+
+fn draw(source: &dyn Source) -> String {
+    match_cast!(source => {
+        gpu: dyn GpuDraw => gpu.draw_gpu(),
+        cpu: dyn CpuDraw => {
+            let drawn = cpu.draw_cpu();
+            drawn + "!"
+        },
+        _ => "nothing".to_string(),
+    })
+}
+
+#[test]
+fn test_match_cast_first_arm() {
+    assert_eq!(draw(&Accelerated), "gpu");
+    assert_eq!(draw(&Software(1)), "cpu 1!");
+    assert_eq!(draw(&Headless), "nothing");
+}
+
+#[test]
+fn test_match_cast_without_default() {
+    let source: Box<dyn Source> = Box::new(Software(1));
+    let mut drawn = Vec::new();
+    match_cast!(source => {
+        gpu: dyn GpuDraw => drawn.push(gpu.draw_gpu()),
+        cpu: dyn CpuDraw => drawn.push(cpu.draw_cpu())
+    });
+    assert_eq!(drawn, vec!["cpu 1"]);
+}
+
+#[test]
+fn test_match_cast_mut() {
+    let mut source: Box<dyn Source> = Box::new(Software(1));
+    let resized = match_cast!(mut source => {
+        gpu: dyn GpuDraw => gpu.draw_gpu(),
+        resize: dyn Resize => {
+            resize.resize(2);
+            "resized".to_string()
+        },
+        _ => unreachable!(),
+    });
+    assert_eq!(resized, "resized");
+    assert_eq!(draw(&*source), "cpu 2!");
+}
@@ -0,0 +1,239 @@
+use std::any::TypeId;
+
+use intertrait::cast::*;
+use intertrait::dynamic::{merge_slice, merge_slice_revocable, try_merge_slice, MergeError};
+use intertrait::{CastFrom, Caster, CasterEntry, StableKey, CASTER_ABI_VERSION};
+use linkme::distributed_slice;
+
+trait Source: CastFrom {}
+
+trait Greet {
+    fn greet(&self) -> &'static str;
+}
+
+struct PluginAData;
+
+impl Greet for PluginAData {
+    fn greet(&self) -> &'static str {
+        "plugin a"
+    }
+}
+
+impl Source for PluginAData {}
+
+struct PluginBData;
+
+impl Greet for PluginBData {
+    fn greet(&self) -> &'static str {
+        "plugin b"
+    }
+}
+
+impl Source for PluginBData {}
+
+struct PluginCData;
+
+impl Greet for PluginCData {
+    fn greet(&self) -> &'static str {
+        "plugin c"
+    }
+}
+
+impl Source for PluginCData {}
+
+struct PluginDData;
+
+impl Greet for PluginDData {
+    fn greet(&self) -> &'static str {
+        "plugin d"
+    }
+}
+
+impl Source for PluginDData {}
+
+// Simulates the slices that would be exposed by three plugins respectively.
+#[distributed_slice]
+static PLUGIN_A_CASTERS: [CasterEntry] = [..];
+
+#[distributed_slice]
+static PLUGIN_B_CASTERS: [CasterEntry] = [..];
+
+#[distributed_slice]
+static PLUGIN_C_CASTERS: [CasterEntry] = [..];
+
+#[distributed_slice]
+static PLUGIN_D_CASTERS: [CasterEntry] = [..];
+
+#[distributed_slice(PLUGIN_A_CASTERS)]
+static PLUGIN_A_GREET: CasterEntry =
+    unsafe { CasterEntry::new(TypeId::of::<PluginAData>, &A_GREET) };
+
+static A_GREET: Caster<dyn Greet> = Caster::new(
+    |from| from.downcast_ref::<PluginAData>().unwrap(),
+    |from| from.downcast_mut::<PluginAData>().unwrap(),
+    |from| from.downcast::<PluginAData>().unwrap(),
+    |from| from.downcast::<PluginAData>().unwrap(),
+);
+
+#[distributed_slice(PLUGIN_B_CASTERS)]
+static PLUGIN_B_GREET: CasterEntry =
+    unsafe { CasterEntry::new(TypeId::of::<PluginBData>, &B_GREET) };
+
+static B_GREET: Caster<dyn Greet> = Caster::new(
+    |from| from.downcast_ref::<PluginBData>().unwrap(),
+    |from| from.downcast_mut::<PluginBData>().unwrap(),
+    |from| from.downcast::<PluginBData>().unwrap(),
+    |from| from.downcast::<PluginBData>().unwrap(),
+);
+
+static PLUGIN_C_DATA: PluginCData = PluginCData;
+
+// Overlaps with plugin a, which greets as plugin c.
+#[distributed_slice(PLUGIN_C_CASTERS)]
+static PLUGIN_C_GREET_A: CasterEntry =
+    unsafe { CasterEntry::new(TypeId::of::<PluginAData>, &C_GREET_A) };
+
+static C_GREET_A: Caster<dyn Greet> = Caster::<dyn Greet> {
+    type_name: "merge_slice::PluginAData",
+    ..Caster::new(
+        |_| &PLUGIN_C_DATA,
+        |_| unimplemented!(),
+        |_| Box::new(PluginCData),
+        |_| std::rc::Rc::new(PluginCData),
+    )
+};
+
+#[distributed_slice(PLUGIN_C_CASTERS)]
+static PLUGIN_C_GREET: CasterEntry =
+    unsafe { CasterEntry::new(TypeId::of::<PluginCData>, &C_GREET) };
+
+static C_GREET: Caster<dyn Greet> = Caster::new(
+    |from| from.downcast_ref::<PluginCData>().unwrap(),
+    |from| from.downcast_mut::<PluginCData>().unwrap(),
+    |from| from.downcast::<PluginCData>().unwrap(),
+    |from| from.downcast::<PluginCData>().unwrap(),
+);
+
+#[distributed_slice(PLUGIN_D_CASTERS)]
+static PLUGIN_D_GREET: CasterEntry =
+    unsafe { CasterEntry::new(TypeId::of::<PluginDData>, &D_GREET) };
+
+static D_GREET: Caster<dyn Greet> = Caster::new(
+    |from| from.downcast_ref::<PluginDData>().unwrap(),
+    |from| from.downcast_mut::<PluginDData>().unwrap(),
+    |from| from.downcast::<PluginDData>().unwrap(),
+    |from| from.downcast::<PluginDData>().unwrap(),
+);
+
+// Simulates an entry built against another version of intertrait.
+#[distributed_slice(PLUGIN_D_CASTERS)]
+static PLUGIN_D_FUTURE: CasterEntry = unsafe {
+    CasterEntry::with_version(CASTER_ABI_VERSION + 1, TypeId::of::<PluginDData>, &D_SOURCE)
+};
+
+static D_SOURCE: Caster<dyn Source> = Caster::new(
+    |from| from.downcast_ref::<PluginDData>().unwrap(),
+    |from| from.downcast_mut::<PluginDData>().unwrap(),
+    |from| from.downcast::<PluginDData>().unwrap(),
+    |from| from.downcast::<PluginDData>().unwrap(),
+);
+
+struct PluginEData;
+
+impl Greet for PluginEData {
+    fn greet(&self) -> &'static str {
+        "plugin e"
+    }
+}
+
+impl Source for PluginEData {}
+
+#[distributed_slice]
+static PLUGIN_E_CASTERS: [CasterEntry] = [..];
+
+#[distributed_slice(PLUGIN_E_CASTERS)]
+static PLUGIN_E_GREET: CasterEntry =
+    unsafe { CasterEntry::new(TypeId::of::<PluginEData>, &E_GREET) };
+
+static E_GREET: Caster<dyn Greet> = Caster::new(
+    |from| from.downcast_ref::<PluginEData>().unwrap(),
+    |from| from.downcast_mut::<PluginEData>().unwrap(),
+    |from| from.downcast::<PluginEData>().unwrap(),
+    |from| from.downcast::<PluginEData>().unwrap(),
+);
+
+// The registry is global, so the cases are run in sequence in a single test.
+#[test]
+fn test_merge_slices() {
+    let a: &dyn Source = &PluginAData;
+    let b: &dyn Source = &PluginBData;
+    assert!(a.cast::<dyn Greet>().is_none());
+    assert!(b.cast::<dyn Greet>().is_none());
+
+    merge_slice(&PLUGIN_A_CASTERS);
+    assert_eq!(a.cast::<dyn Greet>().unwrap().greet(), "plugin a");
+    assert!(!b.impls::<dyn Greet>());
+
+    assert_eq!(try_merge_slice(&PLUGIN_B_CASTERS), Ok(()));
+    assert_eq!(b.cast::<dyn Greet>().unwrap().greet(), "plugin b");
+    assert!(a.impls::<dyn Greet>());
+
+    // Nothing is merged on a conflict.
+    let c: &dyn Source = &PluginCData;
+    let conflict = StableKey::new("merge_slice::PluginAData", "dyn merge_slice::Greet");
+    assert_eq!(
+        try_merge_slice(&PLUGIN_C_CASTERS),
+        Err(MergeError::Conflicts(vec![conflict]))
+    );
+    assert_eq!(a.cast::<dyn Greet>().unwrap().greet(), "plugin a");
+    assert!(!c.impls::<dyn Greet>());
+
+    // The conflicting caster is overridden.
+    merge_slice(&PLUGIN_C_CASTERS);
+    assert_eq!(a.cast::<dyn Greet>().unwrap().greet(), "plugin c");
+    assert_eq!(c.cast::<dyn Greet>().unwrap().greet(), "plugin c");
+}
+
+#[test]
+fn test_refuse_version_mismatch() {
+    let d: &dyn Source = &PluginDData;
+
+    match try_merge_slice(&PLUGIN_D_CASTERS) {
+        Err(MergeError::VersionMismatch(err)) => {
+            assert_eq!(err.found(), CASTER_ABI_VERSION + 1);
+            assert_eq!(err.expected(), CASTER_ABI_VERSION);
+        }
+        other => panic!("unexpected result: {:?}", other),
+    }
+    // The entries of the current version in the slice are refused as well.
+    assert!(!d.impls::<dyn Greet>());
+
+    let result = std::panic::catch_unwind(|| merge_slice(&PLUGIN_D_CASTERS));
+    let message = *result.unwrap_err().downcast::<String>().unwrap();
+    assert!(message.contains("version"), "{}", message);
+    assert!(!d.impls::<dyn Greet>());
+}
+
+#[test]
+fn test_revoke_merged_slice() {
+    let e: &dyn Source = &PluginEData;
+
+    let token = merge_slice_revocable(&PLUGIN_E_CASTERS).unwrap();
+    assert_eq!(e.cast::<dyn Greet>().unwrap().greet(), "plugin e");
+    match merge_slice_revocable(&PLUGIN_E_CASTERS) {
+        Err(MergeError::Conflicts(conflicts)) => assert_eq!(conflicts.len(), 1),
+        other => panic!("unexpected result: {:?}", other),
+    }
+
+    token.revoke();
+    assert!(!e.impls::<dyn Greet>());
+
+    // Reloaded, and dropped.
+    let token = merge_slice_revocable(&PLUGIN_E_CASTERS).unwrap();
+    assert!(e.impls::<dyn Greet>());
+    drop(token);
+    assert!(!e.impls::<dyn Greet>());
+
+    merge_slice_revocable(&PLUGIN_E_CASTERS).unwrap().keep();
+    assert_eq!(e.cast::<dyn Greet>().unwrap().greet(), "plugin e");
+}
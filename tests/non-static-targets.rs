@@ -0,0 +1,62 @@
+#![cfg(not(feature = "disabled"))]
+
+use intertrait::cast::*;
+use intertrait::*;
+
+trait Source: CastFrom {}
+
+trait Greet {
+    fn greet(&self) -> String;
+}
+
+#[cast_to(Greet)]
+struct Data;
+
+impl Source for Data {}
+
+impl Greet for Data {
+    fn greet(&self) -> String {
+        "Hello".to_string()
+    }
+}
+
+struct Borrowed<'a>(&'a str);
+
+impl Greet for Borrowed<'_> {
+    fn greet(&self) -> String {
+        self.0.to_string()
+    }
+}
+
+/// Holds trait objects which may borrow data for `'a`.
+struct Greeters<'a> {
+    greets: Vec<&'a (dyn Greet + 'a)>,
+    boxed: Vec<Box<dyn Greet + 'a>>,
+}
+
+fn cast_into<'a>(source: &'a dyn Source) -> Option<&'a (dyn Greet + 'a)> {
+    source.cast::<dyn Greet>()
+}
+
+#[test]
+fn test_cast_into_non_static_target() {
+    let name = String::from("World");
+    let borrowed = Borrowed(&name);
+    let data = Data;
+
+    let mut greeters = Greeters {
+        greets: vec![&borrowed],
+        boxed: vec![Box::new(Borrowed(&name))],
+    };
+    greeters.greets.push(cast_into(&data).unwrap());
+
+    let source: Box<dyn Source> = Box::new(Data);
+    greeters
+        .boxed
+        .push(source.cast::<dyn Greet>().ok().unwrap());
+
+    let greets: Vec<String> = greeters.greets.iter().map(|g| g.greet()).collect();
+    assert_eq!(greets, vec!["World", "Hello"]);
+    let boxed: Vec<String> = greeters.boxed.iter().map(|g| g.greet()).collect();
+    assert_eq!(boxed, vec!["World", "Hello"]);
+}
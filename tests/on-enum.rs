@@ -1,3 +1,5 @@
+#![cfg(not(feature = "disabled"))]
+
 use intertrait::cast::*;
 use intertrait::*;
 
@@ -0,0 +1,71 @@
+#![cfg(not(feature = "disabled"))]
+
+use std::mem::{align_of, size_of};
+
+use intertrait::cast::*;
+use intertrait::*;
+
+#[cast_to(Greet)]
+#[repr(C)]
+#[derive(Clone, Debug, PartialEq)]
+struct Header {
+    tag: u8,
+    len: u32,
+    flags: u8,
+}
+
+#[derive(Debug)]
+#[cast_to(Greet)]
+#[repr(C, align(16))]
+struct Aligned(u8);
+
+trait Source: CastFrom {}
+
+trait Greet {
+    fn greet(&self) -> String;
+}
+
+impl Greet for Header {
+    fn greet(&self) -> String {
+        format!("header {}", self.tag)
+    }
+}
+
+impl Greet for Aligned {
+    fn greet(&self) -> String {
+        format!("aligned {}", self.0)
+    }
+}
+
+impl Source for Header {}
+impl Source for Aligned {}
+
+#[test]
+fn test_cast_to_on_repr_c_struct() {
+    let header = Header {
+        tag: 1,
+        len: 2,
+        flags: 3,
+    };
+    let source: &dyn Source = &header;
+    assert_eq!(source.cast::<dyn Greet>().unwrap().greet(), "header 1");
+
+    let aligned = Aligned(4);
+    let source: &dyn Source = &aligned;
+    assert_eq!(source.cast::<dyn Greet>().unwrap().greet(), "aligned 4");
+}
+
+#[test]
+fn test_attributes_preserved() {
+    // The fields would be reordered into 8 bytes without `#[repr(C)]`.
+    assert_eq!(size_of::<Header>(), 12);
+    assert_eq!(align_of::<Aligned>(), 16);
+
+    let header = Header {
+        tag: 1,
+        len: 2,
+        flags: 3,
+    };
+    assert_eq!(header.clone(), header);
+    assert_eq!(format!("{:?}", Aligned(4)), "Aligned(4)");
+}
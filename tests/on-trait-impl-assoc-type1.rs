@@ -1,3 +1,5 @@
+#![cfg(not(feature = "disabled"))]
+
 use std::fmt::Debug;
 
 use intertrait::cast::*;
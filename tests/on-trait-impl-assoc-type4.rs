@@ -0,0 +1,34 @@
+#![cfg(not(feature = "disabled"))]
+
+use intertrait::cast::*;
+use intertrait::*;
+
+struct Data(String);
+
+trait Source: CastFrom {}
+
+trait Container {
+    type Item: ?Sized;
+
+    fn get(&self) -> &Self::Item;
+}
+
+#[cast_to]
+impl Container for Data {
+    type Item = str;
+
+    fn get(&self) -> &Self::Item {
+        &self.0
+    }
+}
+
+impl Source for Data {}
+
+#[test]
+fn test_cast_to_on_trait_impl_with_unsized_assoc_type() {
+    let data = Data("hello".to_owned());
+    let source: &dyn Source = &data;
+    let container = source.cast::<dyn Container<Item = str>>();
+    assert_eq!(container.unwrap().get(), "hello");
+    assert!(!source.impls::<dyn Container<Item = [u8]>>());
+}
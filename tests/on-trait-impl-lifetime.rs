@@ -0,0 +1,58 @@
+#![cfg(not(feature = "disabled"))]
+
+use intertrait::cast::*;
+use intertrait::*;
+
+struct Data;
+
+struct Named<'a> {
+    name: &'a str,
+}
+
+trait Source: CastFrom {}
+
+trait Greet {
+    fn greet(&self) -> &str;
+}
+
+// The lifetime is what the impl is tested for.
+#[allow(clippy::extra_unused_lifetimes)]
+#[cast_to]
+impl<'a> Greet for Data
+where
+    Self: 'a,
+{
+    fn greet(&self) -> &str {
+        "Hello"
+    }
+}
+
+#[cast_to]
+impl<'a> Greet for Named<'a>
+where
+    'a: 'static,
+{
+    fn greet(&self) -> &str {
+        self.name
+    }
+}
+
+impl Source for Data {}
+impl Source for Named<'static> {}
+
+#[test]
+fn test_cast_to_on_trait_impl_with_lifetime() {
+    let data = Data;
+    let source: &dyn Source = &data;
+    assert!(source.impls::<dyn Greet>());
+    let greet = source.cast::<dyn Greet>();
+    assert_eq!(greet.unwrap().greet(), "Hello");
+}
+
+#[test]
+fn test_cast_to_on_trait_impl_with_static_lifetime() {
+    let named = Named { name: "Hi" };
+    let source: &dyn Source = &named;
+    let greet = source.cast::<dyn Greet>();
+    assert_eq!(greet.unwrap().greet(), "Hi");
+}
@@ -0,0 +1,54 @@
+#![cfg(not(feature = "disabled"))]
+
+use intertrait::cast::*;
+use intertrait::*;
+
+struct Data(u32);
+
+trait Source: CastFrom {}
+
+trait Counter {
+    fn count(&self) -> u32;
+
+    fn increment(&mut self);
+
+    fn with_count(count: u32) -> Self
+    where
+        Self: Sized;
+
+    fn into_count(self) -> u32
+    where
+        Self: Sized,
+    {
+        self.count()
+    }
+}
+
+#[cast_to]
+impl Counter for Data {
+    fn count(&self) -> u32 {
+        self.0
+    }
+
+    fn increment(&mut self) {
+        self.0 += 1;
+    }
+
+    fn with_count(count: u32) -> Self {
+        Data(count)
+    }
+}
+
+impl Source for Data {}
+
+#[test]
+fn test_cast_to_trait_with_sized_methods() {
+    let mut data = Data::with_count(1);
+    {
+        let source: &mut dyn Source = &mut data;
+        let counter = source.cast::<dyn Counter>().unwrap();
+        counter.increment();
+        assert_eq!(counter.count(), 2);
+    }
+    assert_eq!(data.into_count(), 2);
+}
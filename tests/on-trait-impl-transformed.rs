@@ -0,0 +1,63 @@
+#![cfg(not(feature = "disabled"))]
+
+use intertrait::cast::*;
+use intertrait::*;
+
+struct Data;
+
+trait Source: CastFrom {}
+
+trait Greet {
+    type Name;
+
+    fn greet(&self) -> String;
+
+    fn name(&self) -> Self::Name;
+
+    fn shout(&self) -> String {
+        self.greet().to_uppercase()
+    }
+}
+
+// Stands for an attribute macro placed above `#[cast_to]`, which receives the impl
+// first and hands a transformed one with an injected method down to `#[cast_to]`.
+macro_rules! with_shout {
+    ($(#[$attr:meta])* impl $trait_:ident for $ty:ty { $($item:item)* }) => {
+        $(#[$attr])*
+        impl $trait_ for $ty {
+            $($item)*
+
+            #[inline]
+            fn shout(&self) -> String {
+                format!("{}!", self.greet().to_uppercase())
+            }
+        }
+    };
+}
+
+with_shout! {
+    #[cast_to]
+    #[allow(clippy::all)]
+    impl Greet for Data {
+        type Name = &'static str;
+
+        fn greet(&self) -> String {
+            "Hello".to_owned()
+        }
+
+        fn name(&self) -> &'static str {
+            "Data"
+        }
+    }
+}
+
+impl Source for Data {}
+
+#[test]
+fn test_cast_to_transformed_impl() {
+    let data = Data;
+    let source: &dyn Source = &data;
+    let greet = source.cast::<dyn Greet<Name = &'static str>>().unwrap();
+    assert_eq!(greet.shout(), "HELLO!");
+    assert_eq!(greet.name(), "Data");
+}
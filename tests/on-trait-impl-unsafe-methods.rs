@@ -0,0 +1,51 @@
+#![cfg(not(feature = "disabled"))]
+
+use intertrait::cast::*;
+use intertrait::*;
+
+struct Data(Vec<u32>);
+
+trait Source: CastFrom {}
+
+trait Buffer {
+    fn len(&self) -> usize;
+
+    /// # Safety
+    ///
+    /// `index` must be less than `len()`.
+    unsafe fn get_unchecked(&self, index: usize) -> u32;
+
+    /// # Safety
+    ///
+    /// `index` must be less than `len()`.
+    unsafe fn set_unchecked(&mut self, index: usize, value: u32);
+}
+
+#[cast_to]
+impl Buffer for Data {
+    fn len(&self) -> usize {
+        self.0.len()
+    }
+
+    unsafe fn get_unchecked(&self, index: usize) -> u32 {
+        *self.0.get_unchecked(index)
+    }
+
+    unsafe fn set_unchecked(&mut self, index: usize, value: u32) {
+        *self.0.get_unchecked_mut(index) = value;
+    }
+}
+
+impl Source for Data {}
+
+#[test]
+fn test_cast_to_trait_with_unsafe_methods() {
+    let mut data = Data(vec![1, 2, 3]);
+    let source: &mut dyn Source = &mut data;
+    let buffer = source.cast::<dyn Buffer>().unwrap();
+    assert_eq!(buffer.len(), 3);
+    unsafe {
+        buffer.set_unchecked(1, 5);
+        assert_eq!(buffer.get_unchecked(1), 5);
+    }
+}
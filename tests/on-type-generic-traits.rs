@@ -0,0 +1,71 @@
+#![cfg(not(feature = "disabled"))]
+
+use intertrait::cast::*;
+use intertrait::*;
+
+struct EventA(u32);
+struct EventB(&'static str);
+struct EventC;
+
+trait Handler<E> {
+    fn handle(&self, event: E) -> String;
+}
+
+trait Source: CastFrom {}
+
+#[cast_to(Handler<EventA>, crate::Handler<EventB>)]
+struct Bus;
+
+impl Handler<EventA> for Bus {
+    fn handle(&self, event: EventA) -> String {
+        format!("A {}", event.0)
+    }
+}
+
+impl Handler<EventB> for Bus {
+    fn handle(&self, event: EventB) -> String {
+        format!("B {}", event.0.len())
+    }
+}
+
+#[cast_to]
+impl Handler<EventC> for Bus {
+    fn handle(&self, _: EventC) -> String {
+        "C".to_owned()
+    }
+}
+
+impl Handler<u8> for Bus {
+    fn handle(&self, event: u8) -> String {
+        event.to_string()
+    }
+}
+
+impl Source for Bus {}
+
+#[test]
+fn test_cast_to_generic_trait_instantiations() {
+    let bus = Bus;
+    let source: &dyn Source = &bus;
+    assert_eq!(
+        source
+            .cast::<dyn Handler<EventA>>()
+            .unwrap()
+            .handle(EventA(1)),
+        "A 1"
+    );
+    assert_eq!(
+        source
+            .cast::<dyn Handler<EventB>>()
+            .unwrap()
+            .handle(EventB("ab")),
+        "B 2"
+    );
+    assert_eq!(
+        source.cast::<dyn Handler<EventC>>().unwrap().handle(EventC),
+        "C"
+    );
+    assert!(!source.impls::<dyn Handler<u8>>());
+    assert_eq!(impls_of::<dyn Handler<EventA>>().count(), 1);
+    assert_eq!(impls_of::<dyn Handler<EventB>>().count(), 1);
+}
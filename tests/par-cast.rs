@@ -0,0 +1,57 @@
+use intertrait::cast::*;
+use intertrait::*;
+use rayon::prelude::*;
+
+trait Component: CastFrom {}
+
+trait Health: Sync {
+    fn health(&self) -> u64;
+}
+
+#[cast_to(Health)]
+struct Player(u64);
+
+#[cast_to(Health)]
+struct Monster(u64);
+
+struct Scenery;
+
+impl Health for Player {
+    fn health(&self) -> u64 {
+        self.0
+    }
+}
+
+impl Health for Monster {
+    fn health(&self) -> u64 {
+        self.0 * 2
+    }
+}
+
+impl Component for Player {}
+impl Component for Monster {}
+impl Component for Scenery {}
+
+#[test]
+fn test_par_cast_refs() {
+    let components: Vec<Box<dyn Component + Sync>> = (0..100_000u64)
+        .map(|i| -> Box<dyn Component + Sync> {
+            match i % 3 {
+                0 => Box::new(Player(i)),
+                1 => Box::new(Monster(i)),
+                _ => Box::new(Scenery),
+            }
+        })
+        .collect();
+
+    let sequential: Vec<u64> = components
+        .iter()
+        .filter_map(|component| (**component).cast::<dyn Health>())
+        .map(|health| health.health())
+        .collect();
+    let parallel: Vec<u64> = par_cast_refs::<_, dyn Health>(&components)
+        .map(|health| health.health())
+        .collect();
+    assert_eq!(sequential.len(), 66_667);
+    assert_eq!(parallel, sequential);
+}
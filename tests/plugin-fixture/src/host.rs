@@ -0,0 +1,37 @@
+//! A host loading the plugin built next to it, which is run by `tests/plugin.rs`.
+use std::env;
+use std::fmt::Display;
+
+use intertrait::cast::*;
+use intertrait::plugin::load;
+use intertrait::{impls_of, CastFrom};
+
+fn main() {
+    let path = env::current_exe().unwrap().with_file_name(format!(
+        "{}intertrait_plugin_fixture{}",
+        env::consts::DLL_PREFIX,
+        env::consts::DLL_SUFFIX
+    ));
+    for _ in 0..2 {
+        let plugin = unsafe { load(&path) }.unwrap();
+        let type_id = {
+            let create_greeting = unsafe {
+                plugin
+                    .library()
+                    .get::<fn() -> Box<dyn CastFrom>>(b"create_greeting")
+            }
+            .unwrap();
+            let greeting = create_greeting();
+            assert_eq!(
+                (*greeting).cast::<dyn Display>().unwrap().to_string(),
+                "Hello from the plugin"
+            );
+            (*greeting).type_id()
+        };
+        assert!(impls_of::<dyn Display>().any(|id| id == type_id));
+
+        // The casters are revoked, after which the plugin can be loaded again.
+        unsafe { plugin.unload() };
+        assert!(impls_of::<dyn Display>().all(|id| id != type_id));
+    }
+}
@@ -0,0 +1,22 @@
+//! A plugin loaded by `src/host.rs`, which registers a cast from a type only it knows.
+use std::fmt;
+
+use intertrait::*;
+
+#[cast_to(fmt::Display)]
+struct Greeting;
+
+impl fmt::Display for Greeting {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "Hello from the plugin")
+    }
+}
+
+/// Creates a value of the type unknown to the host, which casts it with the casters merged
+/// from this plugin.
+#[no_mangle]
+pub fn create_greeting() -> Box<dyn CastFrom> {
+    Box::new(Greeting)
+}
+
+intertrait::export_casters!();
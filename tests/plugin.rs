@@ -0,0 +1,55 @@
+#![cfg(not(feature = "disabled"))]
+
+use std::env;
+use std::path::Path;
+use std::process::Command;
+
+use intertrait::plugin::{load, PluginError, CASTERS_SYMBOL};
+
+intertrait::export_casters!();
+
+#[test]
+fn test_load_missing_library() {
+    let result = unsafe { load("libintertrait-missing-plugin.so") };
+    match result {
+        Err(PluginError::Load(_)) => {}
+        other => panic!("unexpected result: {:?}", other),
+    }
+}
+
+#[cfg(target_os = "linux")]
+#[test]
+fn test_load_library_without_casters() {
+    let result = unsafe { load("libc.so.6") };
+    match result {
+        Err(err @ PluginError::Load(_)) => {
+            assert!(err.to_string().contains(CASTERS_SYMBOL), "{}", err);
+        }
+        other => panic!("unexpected result: {:?}", other),
+    }
+}
+
+#[test]
+fn test_export_casters() {
+    assert_eq!(__intertrait_casters().len(), intertrait::CASTERS.len());
+}
+
+/// Runs the host in `tests/plugin-fixture`, which loads the plugin there, casts a value of
+/// the plugin through the casters merged from it, and checks they are revoked on unloading it.
+///
+/// The host is built along with the plugin instead of being this test, so that both link
+/// the same intertrait.
+#[test]
+fn test_load_and_unload_plugin() {
+    let manifest_dir = Path::new(env!("CARGO_MANIFEST_DIR"));
+    let status = Command::new(env::var("CARGO").unwrap_or_else(|_| "cargo".to_owned()))
+        .arg("run")
+        .arg("--manifest-path")
+        .arg(manifest_dir.join("tests/plugin-fixture/Cargo.toml"))
+        .arg("--target-dir")
+        .arg(Path::new(env!("CARGO_TARGET_TMPDIR")).join("plugin-fixture"))
+        .args(["--features", "host"])
+        .status()
+        .unwrap();
+    assert!(status.success(), "the plugin host failed: {}", status);
+}
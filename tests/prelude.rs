@@ -0,0 +1,54 @@
+#![cfg(not(feature = "disabled"))]
+
+use std::fmt::Debug;
+
+trait Greet {
+    fn greet(&self) -> &'static str;
+}
+
+mod with_prelude {
+    use intertrait::prelude::*;
+
+    use super::Greet;
+
+    #[cast_to(Greet)]
+    pub struct Data;
+
+    impl Greet for Data {
+        fn greet(&self) -> &'static str {
+            "hello"
+        }
+    }
+
+    pub trait Source: CastFrom {}
+
+    impl Source for Data {}
+
+    #[test]
+    fn test_cast_with_prelude() {
+        let source: &dyn Source = &Data;
+        assert_eq!(source.cast::<dyn Greet>().unwrap().greet(), "hello");
+        let source: Box<dyn Source> = Box::new(Data);
+        assert!(source.cast::<dyn Greet>().is_ok());
+    }
+}
+
+// Nothing is imported from intertrait but the free functions.
+#[test]
+fn test_free_functions() {
+    use with_prelude::{Data, Source};
+
+    let source: &dyn Source = &Data;
+    let greet = intertrait::cast_ref::<dyn Greet, _>(source).unwrap();
+    assert_eq!(greet.greet(), "hello");
+    assert!(intertrait::cast_ref::<dyn Debug, _>(source).is_none());
+
+    let mut data = Data;
+    let source: &mut dyn Source = &mut data;
+    assert!(intertrait::cast_mut::<dyn Greet, _>(source).is_some());
+
+    let source: Box<dyn Source> = Box::new(Data);
+    let source = intertrait::cast_box::<dyn Debug, _>(source).unwrap_err();
+    let greet = intertrait::cast_box::<dyn Greet, _>(source).ok().unwrap();
+    assert_eq!(greet.greet(), "hello");
+}
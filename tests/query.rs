@@ -0,0 +1,58 @@
+#![cfg(not(feature = "disabled"))]
+
+use std::sync::Arc;
+
+use intertrait::*;
+
+trait Source: CastFromSync {}
+
+trait Greet {
+    fn greet(&self) -> &'static str;
+}
+
+trait Render {
+    fn render(&self) -> String;
+}
+
+trait Shutdown {}
+
+#[cast_to(Greet, Render)]
+struct Plugin;
+
+impl Greet for Plugin {
+    fn greet(&self) -> &'static str {
+        "hello"
+    }
+}
+
+impl Render for Plugin {
+    fn render(&self) -> String {
+        "<plugin>".to_string()
+    }
+}
+
+impl Source for Plugin {}
+
+#[test]
+fn test_query_named() {
+    let plugin: Arc<dyn Source> = Arc::new(Plugin);
+    let caps = query!(plugin => greet: dyn Greet, render: dyn Render, shutdown: dyn Shutdown,);
+    assert_eq!(caps.greet.unwrap().greet(), "hello");
+    assert_eq!(caps.render.unwrap().render(), "<plugin>");
+    assert!(caps.shutdown.is_none());
+
+    // Only some of the fields may be used.
+    let caps = query!(&Plugin => plugin: Plugin, shutdown: dyn Shutdown);
+    assert!(caps.plugin.is_some());
+}
+
+#[test]
+fn test_query_tuple() {
+    let plugin: &dyn Source = &Plugin;
+    let (greet, shutdown) = query!(plugin => dyn Greet, dyn Shutdown);
+    assert_eq!(greet.unwrap().greet(), "hello");
+    assert!(shutdown.is_none());
+
+    let (render,) = query!(plugin => dyn Render);
+    assert_eq!(render.unwrap().render(), "<plugin>");
+}
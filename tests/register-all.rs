@@ -0,0 +1,53 @@
+#![cfg(not(feature = "disabled"))]
+
+use intertrait::cast::*;
+use intertrait::*;
+
+mod traits {
+    pub trait Greet {
+        fn greet(&self) -> &str;
+    }
+
+    pub trait Wave {
+        fn wave(&self) -> u32;
+    }
+}
+
+use traits::{Greet, Wave};
+
+#[derive(std::fmt::Debug)]
+struct Data;
+
+trait Source: CastFromSync {}
+
+impl Source for Data {}
+
+impl Greet for Data {
+    fn greet(&self) -> &str {
+        "Hello"
+    }
+}
+
+impl Wave for Data {
+    fn wave(&self) -> u32 {
+        2
+    }
+}
+
+register_all!(Data => [sync] [
+    traits::Greet,
+    crate::traits::Wave,
+    std::fmt::Debug,
+]);
+
+#[test]
+fn test_register_all() {
+    let data = Data;
+    let source: &dyn Source = &data;
+    assert_eq!(source.cast::<dyn Greet>().unwrap().greet(), "Hello");
+    assert_eq!(source.cast::<dyn Wave>().unwrap().wave(), 2);
+    assert!(source.impls::<dyn std::fmt::Debug>());
+
+    let source: std::sync::Arc<dyn Source> = std::sync::Arc::new(Data);
+    assert!(source.cast::<dyn Wave>().is_ok());
+}
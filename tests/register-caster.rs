@@ -0,0 +1,172 @@
+use intertrait::cast::*;
+use intertrait::dynamic::{register_caster, register_if, unregister_caster};
+use intertrait::{impls_of, register, CastFrom, CastFromSync, Caster};
+use std::any::TypeId;
+use std::pin::Pin;
+use std::sync::Arc;
+
+trait Source: CastFrom {}
+
+trait Greet {
+    fn greet(&self) -> &'static str;
+}
+
+struct Licensed;
+
+impl Greet for Licensed {
+    fn greet(&self) -> &'static str {
+        "licensed"
+    }
+}
+
+impl Source for Licensed {}
+
+struct Unlicensed;
+
+impl Greet for Unlicensed {
+    fn greet(&self) -> &'static str {
+        "unlicensed"
+    }
+}
+
+impl Source for Unlicensed {}
+
+fn register_greet<S: Greet + 'static>(condition: bool) {
+    register_if(condition, || {
+        register_caster::<S, dyn Greet>(Caster::new(
+            |from| from.downcast_ref::<S>().unwrap(),
+            |from| from.downcast_mut::<S>().unwrap(),
+            |from| from.downcast::<S>().unwrap(),
+            |from| from.downcast::<S>().unwrap(),
+        ))
+    });
+}
+
+#[test]
+fn test_register_if() {
+    register_greet::<Licensed>(true);
+    register_greet::<Unlicensed>(false);
+
+    let licensed: &dyn Source = &Licensed;
+    assert_eq!(licensed.cast::<dyn Greet>().unwrap().greet(), "licensed");
+
+    let unlicensed: &dyn Source = &Unlicensed;
+    assert!(!unlicensed.impls::<dyn Greet>());
+    assert!(unlicensed.cast::<dyn Greet>().is_none());
+
+    let greets: Vec<TypeId> = impls_of::<dyn Greet>().collect();
+    assert_eq!(greets, vec![TypeId::of::<Licensed>()]);
+}
+
+trait Render {
+    fn render(&self) -> &'static str;
+}
+
+trait Resize {
+    fn resize(&mut self, size: usize);
+}
+
+struct Canvas(usize);
+
+impl Render for Canvas {
+    fn render(&self) -> &'static str {
+        "canvas"
+    }
+}
+
+impl Resize for Canvas {
+    fn resize(&mut self, size: usize) {
+        self.0 = size;
+    }
+}
+
+impl Source for Canvas {}
+
+trait SyncSource: CastFromSync {}
+
+impl SyncSource for Canvas {}
+
+#[test]
+fn test_register_macro() {
+    let mut canvas: Box<dyn Source> = Box::new(Canvas(0));
+    assert!(!canvas.impls::<dyn Render>());
+
+    register!(Canvas => dyn Render, dyn Resize);
+    assert_eq!((*canvas).cast::<dyn Render>().unwrap().render(), "canvas");
+    CastMut::cast::<dyn Resize>(&mut *canvas).unwrap().resize(2);
+    assert_eq!((*canvas).cast::<Canvas>().unwrap().0, 2);
+
+    register!(Canvas => [sync] dyn Render + Send + Sync);
+    let canvas: Arc<dyn SyncSource> = Arc::new(Canvas(0));
+    let render = canvas.cast::<dyn Render + Send + Sync>().ok().unwrap();
+    assert_eq!(render.render(), "canvas");
+}
+
+trait Scale {
+    fn scale(&self) -> usize;
+}
+
+struct Image(usize);
+
+impl Scale for Image {
+    fn scale(&self) -> usize {
+        self.0
+    }
+}
+
+impl Source for Image {}
+
+#[test]
+fn test_unregister_caster() {
+    let image: &dyn Source = &Image(3);
+    assert!(!unregister_caster::<Image, dyn Scale>());
+    let mut cursor = CastCursor::<dyn Scale>::new();
+    assert!(cursor.cast_ref(image).is_none());
+
+    register!(Image => dyn Scale);
+    assert_eq!(image.cast::<dyn Scale>().unwrap().scale(), 3);
+    assert_eq!(cursor.cast_ref(image).unwrap().scale(), 3);
+    assert_eq!(
+        impls_of::<dyn Scale>().collect::<Vec<_>>(),
+        vec![TypeId::of::<Image>()]
+    );
+    let handle = caster_of::<dyn Scale, _>(image).unwrap();
+
+    assert!(unregister_caster::<Image, dyn Scale>());
+    assert!(!image.impls::<dyn Scale>());
+    assert!(handle.cast_ref(image).is_none());
+    assert!(cursor.cast_ref(image).is_none());
+    assert!(image.cast::<dyn Scale>().is_none());
+    assert_eq!(impls_of::<dyn Scale>().count(), 0);
+    assert!(!unregister_caster::<Image, dyn Scale>());
+
+    // It can be registered again.
+    register!(Image => dyn Scale);
+    assert_eq!(image.cast::<dyn Scale>().unwrap().scale(), 3);
+}
+
+trait Label {
+    fn label(&self) -> &'static str;
+}
+
+struct Pinned;
+
+impl Label for Pinned {
+    fn label(&self) -> &'static str {
+        "pinned"
+    }
+}
+
+impl Source for Pinned {}
+
+#[test]
+fn test_registered_caster_not_for_pin() {
+    register!(Pinned => dyn Label);
+    let mut source: Pin<Box<dyn Source>> = Box::pin(Pinned);
+    assert_eq!((*source).cast::<dyn Label>().unwrap().label(), "pinned");
+
+    // A caster registered at runtime isn't trusted not to move a pinned value.
+    assert!(source.as_mut().cast_pin::<dyn Label>().is_none());
+    let source = source.cast_pin::<dyn Label>().err().unwrap();
+    assert!(source.cast_pin::<Pinned>().is_ok());
+}
@@ -0,0 +1,152 @@
+#![cfg(not(feature = "disabled"))]
+
+use std::any::{Any, TypeId};
+
+use intertrait::cast::*;
+use intertrait::*;
+
+mod subsystem {
+    intertrait::registry!(pub static WIDGETS);
+}
+
+registry! {
+    /// The casts of the plugins.
+    static PLUGINS;
+}
+
+trait Source: CastFrom {}
+
+trait Greet {
+    fn greet(&self) -> &'static str;
+}
+
+trait Wave {
+    fn wave(&self) -> u32;
+}
+
+struct Data;
+
+impl Source for Data {}
+
+#[cast_to(registry = PLUGINS)]
+impl Greet for Data {
+    fn greet(&self) -> &'static str {
+        "Hello"
+    }
+}
+
+impl Wave for Data {
+    fn wave(&self) -> u32 {
+        3
+    }
+}
+
+castable_to! { Data => registry = subsystem::WIDGETS, Wave }
+
+#[cast_to(registry = subsystem::WIDGETS, std::fmt::Debug)]
+#[derive(Debug)]
+struct Widget;
+
+impl Source for Widget {}
+
+#[test]
+fn test_cast_in_registry() {
+    let mut data = Data;
+    assert_eq!(
+        PLUGINS
+            .cast_ref::<dyn Greet, _>(&data as &dyn Source)
+            .unwrap()
+            .greet(),
+        "Hello"
+    );
+    assert!(PLUGINS
+        .cast_mut::<dyn Greet, _>(&mut data as &mut dyn Source)
+        .is_some());
+    assert!(PLUGINS.impls::<dyn Greet, _>(&data as &dyn Source));
+    assert!(!PLUGINS.impls::<dyn Wave, _>(&data as &dyn Source));
+
+    let source: Box<dyn Source> = Box::new(Data);
+    let source = subsystem::WIDGETS
+        .cast_box::<dyn Greet, _>(source)
+        .err()
+        .unwrap();
+    let wave = subsystem::WIDGETS
+        .cast_box::<dyn Wave, _>(source)
+        .ok()
+        .unwrap();
+    assert_eq!(wave.wave(), 3);
+
+    let widget: &dyn Source = &Widget;
+    assert!(subsystem::WIDGETS
+        .cast_ref::<dyn std::fmt::Debug, _>(widget)
+        .is_some());
+    assert!(subsystem::WIDGETS.cast_ref::<Widget, _>(widget).is_some());
+}
+
+#[test]
+fn test_registry_isolated_from_global() {
+    let data: &dyn Source = &Data;
+    assert!(!data.impls::<dyn Greet>());
+    assert!(data.cast::<dyn Wave>().is_none());
+    assert!(registry_snapshot().is_empty());
+}
+
+#[test]
+fn test_owned_registry() {
+    let mut registry = Registry::new();
+    let data: &dyn Source = &Data;
+    assert!(!registry.impls::<dyn Wave, _>(data));
+
+    registry.register_caster::<Data, dyn Wave>(Caster::new(
+        |from| from.downcast_ref::<Data>().unwrap(),
+        |from| from.downcast_mut::<Data>().unwrap(),
+        |from| from.downcast::<Data>().unwrap(),
+        |from| from.downcast::<Data>().unwrap(),
+    ));
+    assert_eq!(registry.cast_ref::<dyn Wave, _>(data).unwrap().wave(), 3);
+    assert!(!PLUGINS.impls::<dyn Wave, _>(data));
+}
+
+static WAVE: Caster<dyn Wave> = Caster::new(
+    |from| from.downcast_ref::<Data>().unwrap(),
+    |from| from.downcast_mut::<Data>().unwrap(),
+    |from| from.downcast::<Data>().unwrap(),
+    |from| from.downcast::<Data>().unwrap(),
+);
+
+fn fallback(type_id: TypeId, trait_id: TypeId) -> Option<&'static (dyn Any + Send + Sync)> {
+    if type_id == TypeId::of::<Data>() && trait_id == TypeId::of::<dyn Wave>() {
+        Some(&WAVE)
+    } else if trait_id == TypeId::of::<dyn Greet>() {
+        // Not a `Caster<dyn Greet>`, which is ignored.
+        Some(&WAVE)
+    } else {
+        None
+    }
+}
+
+#[test]
+fn test_registry_fallback() {
+    let registry = Registry::new();
+    let mut data = Data;
+    registry.set_cast_fallback(fallback);
+    assert_eq!(
+        registry
+            .cast_ref::<dyn Wave, _>(&data as &dyn Source)
+            .unwrap()
+            .wave(),
+        3
+    );
+    assert!(registry
+        .cast_mut::<dyn Wave, _>(&mut data as &mut dyn Source)
+        .is_some());
+    assert!(registry.impls::<dyn Wave, _>(&data as &dyn Source));
+    assert!(!registry.impls::<dyn Greet, _>(&data as &dyn Source));
+    assert!(registry
+        .cast_ref::<dyn Greet, _>(&data as &dyn Source)
+        .is_none());
+    assert!(!(&data as &dyn Source).impls::<dyn Wave>());
+
+    registry.clear_cast_fallback();
+    assert!(!registry.impls::<dyn Wave, _>(&data as &dyn Source));
+}
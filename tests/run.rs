@@ -1,3 +1,5 @@
+#![cfg(not(feature = "disabled"))]
+
 #[test]
 fn tests() {
     let t = trybuild::TestCases::new();
@@ -0,0 +1,59 @@
+#![cfg(not(feature = "disabled"))]
+
+use intertrait::cast::*;
+use intertrait::*;
+
+trait Source: CastFrom {
+    fn id(&self) -> u32;
+}
+
+trait Source2: CastFrom {
+    fn name(&self) -> &str;
+}
+
+// A source trait can be cast into only if it is registered as a target as well.
+#[cast_to(Source, Source2)]
+struct Data(u32);
+
+impl Source for Data {
+    fn id(&self) -> u32 {
+        self.0
+    }
+}
+
+impl Source2 for Data {
+    fn name(&self) -> &str {
+        "Data"
+    }
+}
+
+struct Unregistered;
+
+impl Source for Unregistered {
+    fn id(&self) -> u32 {
+        0
+    }
+}
+
+impl Source2 for Unregistered {
+    fn name(&self) -> &str {
+        "Unregistered"
+    }
+}
+
+#[test]
+fn test_cast_box_between_sibling_source_traits() {
+    let source: Box<dyn Source> = Box::new(Data(7));
+    let source2 = source.cast::<dyn Source2>().ok().unwrap();
+    assert_eq!(source2.name(), "Data");
+    let source = source2.cast::<dyn Source>().ok().unwrap();
+    assert_eq!(source.id(), 7);
+}
+
+#[test]
+fn test_cast_box_between_unregistered_sibling_source_traits() {
+    let source: Box<dyn Source> = Box::new(Unregistered);
+    let source = source.cast::<dyn Source2>().err().unwrap();
+    assert_eq!(source.id(), 0);
+    assert_eq!((*source).cast::<dyn Source2>().map(|s| s.name()), None);
+}
@@ -0,0 +1,96 @@
+use std::thread;
+
+use intertrait::cast::*;
+use intertrait::testing::scoped_registry;
+use intertrait::*;
+
+trait Source: CastFrom {}
+
+trait Greet {
+    fn greet(&self) -> &'static str;
+}
+
+#[cast_to]
+impl Greet for Data {
+    fn greet(&self) -> &'static str {
+        "hello"
+    }
+}
+
+struct Data;
+
+impl Source for Data {}
+
+struct Polite;
+
+impl Greet for Polite {
+    fn greet(&self) -> &'static str {
+        "good morning"
+    }
+}
+
+impl Source for Polite {}
+
+fn polite_caster() -> Caster<dyn Greet> {
+    Caster::new(
+        |from| from.downcast_ref::<Polite>().unwrap(),
+        |from| from.downcast_mut::<Polite>().unwrap(),
+        |from| from.downcast::<Polite>().unwrap(),
+        |from| from.downcast::<Polite>().unwrap(),
+    )
+}
+
+#[test]
+fn test_hide_global_caster() {
+    let data: &dyn Source = &Data;
+    assert!(data.impls::<dyn Greet>());
+    {
+        let registry = scoped_registry();
+        registry.hide::<Data, dyn Greet>();
+        assert!(data.cast::<dyn Greet>().is_none());
+        assert!(!data.impls::<dyn Greet>());
+    }
+    assert_eq!(data.cast::<dyn Greet>().unwrap().greet(), "hello");
+    assert!(data.impls::<dyn Greet>());
+}
+
+#[test]
+fn test_scoped_registration_is_thread_local() {
+    let registry = scoped_registry();
+    registry.register_caster::<Polite, dyn Greet>(polite_caster());
+
+    let polite: &dyn Source = &Polite;
+    assert_eq!(polite.cast::<dyn Greet>().unwrap().greet(), "good morning");
+    thread::spawn(|| {
+        let polite: &dyn Source = &Polite;
+        assert!(polite.cast::<dyn Greet>().is_none());
+        assert!(!polite.impls::<dyn Greet>());
+    })
+    .join()
+    .unwrap();
+
+    drop(registry);
+    assert!(polite.cast::<dyn Greet>().is_none());
+}
+
+#[test]
+fn test_nested_scopes() {
+    let polite: &dyn Source = &Polite;
+    let outer = scoped_registry();
+    outer.register_caster::<Polite, dyn Greet>(polite_caster());
+    {
+        let inner = scoped_registry();
+        inner.hide::<Polite, dyn Greet>();
+        assert!(!polite.impls::<dyn Greet>());
+        inner.register_caster::<Polite, dyn Greet>(polite_caster());
+        assert!(polite.impls::<dyn Greet>());
+    }
+    {
+        let inner = scoped_registry();
+        inner.hide::<Polite, dyn Greet>();
+        assert!(polite.cast::<dyn Greet>().is_none());
+    }
+    assert_eq!(polite.cast::<dyn Greet>().unwrap().greet(), "good morning");
+    drop(outer);
+    assert!(!polite.impls::<dyn Greet>());
+}
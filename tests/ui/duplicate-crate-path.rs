@@ -0,0 +1,19 @@
+use intertrait::*;
+
+#[cast_to(crate = intertrait, Greet)]
+#[intertrait(crate = intertrait)]
+struct Data;
+
+trait Greet {
+    fn greet(&self);
+}
+
+impl Greet for Data {
+    fn greet(&self) {
+        println!("Hello");
+    }
+}
+
+fn main() {
+    let _ = Data;
+}
@@ -0,0 +1,10 @@
+use intertrait::*;
+
+trait Greet {}
+
+#[cast_to(label = "a", label = "b", Greet)]
+struct Data;
+
+impl Greet for Data {}
+
+fn main() {}
@@ -0,0 +1,27 @@
+use std::any::Any;
+use std::rc::Rc;
+
+use intertrait::*;
+
+trait Source {}
+
+// It would move the value out of a pinned `Box` into another.
+impl CastFrom for dyn Source {
+    fn ref_any(&self) -> &dyn Any {
+        &()
+    }
+
+    fn mut_any(&mut self) -> &mut dyn Any {
+        Box::leak(Box::new(()))
+    }
+
+    fn box_any(self: Box<Self>) -> Box<dyn Any> {
+        Box::new(())
+    }
+
+    fn rc_any(self: Rc<Self>) -> Rc<dyn Any> {
+        Rc::new(())
+    }
+}
+
+fn main() {}
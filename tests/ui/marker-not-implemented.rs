@@ -0,0 +1,11 @@
+use intertrait::*;
+
+#[cast_marker]
+trait Spawn {
+    fn spawn() -> Self;
+}
+
+#[cast_to(marker Spawn)]
+struct Data;
+
+fn main() {}
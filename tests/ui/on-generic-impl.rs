@@ -0,0 +1,34 @@
+use intertrait::*;
+
+struct Data;
+
+struct Wrapper<T>(T);
+
+trait Greet {
+    fn greet(&self);
+}
+
+trait Handle<T> {
+    fn handle(&self, t: T);
+}
+
+#[cast_to]
+impl<T: 'static> Greet for Wrapper<T> {
+    fn greet(&self) {
+        println!("Hello");
+    }
+}
+
+#[cast_to]
+impl<T> Handle<T> for Data {
+    fn handle(&self, _: T) {}
+}
+
+#[cast_to]
+impl<const N: usize> Greet for [u8; N] {
+    fn greet(&self) {
+        println!("Hello");
+    }
+}
+
+fn main() {}
@@ -0,0 +1,21 @@
+use intertrait::*;
+
+struct Data<'a> {
+    name: &'a str,
+}
+
+trait Greet {
+    fn greet(&self);
+}
+
+#[cast_to]
+impl<'a> Greet for Data<'a> {
+    fn greet(&self) {
+        println!("Hello, {}", self.name);
+    }
+}
+
+fn main() {
+    let data = Data { name: "world" };
+    data.greet();
+}
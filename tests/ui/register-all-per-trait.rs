@@ -0,0 +1,31 @@
+use intertrait::*;
+
+struct Data;
+
+trait Greet {}
+
+trait Clone2 {
+    fn clone2(&self) -> Self
+    where
+        Self: Sized;
+
+    fn make() -> Self;
+}
+
+trait Wave {}
+
+impl Greet for Data {}
+
+impl Clone2 for Data {
+    fn clone2(&self) -> Self {
+        Data
+    }
+
+    fn make() -> Self {
+        Data
+    }
+}
+
+register_all!(Data => [Greet, Clone2, Wave]);
+
+fn main() {}
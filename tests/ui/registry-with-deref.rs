@@ -0,0 +1,22 @@
+use intertrait::*;
+
+registry!(static PLUGINS);
+
+trait Greet {}
+
+struct Inner;
+
+#[cast_to(registry = PLUGINS, deref_to = Inner, Greet)]
+struct Data(Inner);
+
+impl Greet for Data {}
+
+impl std::ops::Deref for Data {
+    type Target = Inner;
+
+    fn deref(&self) -> &Inner {
+        &self.0
+    }
+}
+
+fn main() {}
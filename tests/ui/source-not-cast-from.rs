@@ -0,0 +1,21 @@
+use intertrait::cast::*;
+use intertrait::*;
+
+#[cast_to(Greet)]
+struct Data;
+
+trait Source {}
+
+trait Greet {}
+
+impl Source for Data {}
+impl Greet for Data {}
+
+fn greet_all<S: ?Sized + CastFrom>(sources: &[&S]) -> usize {
+    cast_borrowed_refs::<S, dyn Greet>(sources).count()
+}
+
+fn main() {
+    let source: &dyn Source = &Data;
+    greet_all(&[source]);
+}
@@ -2,7 +2,7 @@ use intertrait::cast::*;
 use intertrait::*;
 use std::sync::Arc;
 
-#[cast_to([sync, send] Greet)]
+#[cast_to([sync, unpin] Greet)]
 struct Data;
 
 trait Source: CastFromSync {}
@@ -0,0 +1,10 @@
+use intertrait::*;
+
+trait Greet {}
+
+#[cast_to(name = "a", Greet)]
+struct Data;
+
+impl Greet for Data {}
+
+fn main() {}
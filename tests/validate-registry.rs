@@ -0,0 +1,42 @@
+#![cfg(not(feature = "disabled"))]
+
+use intertrait::*;
+
+struct Data;
+
+trait Greet {
+    fn greet(&self) -> &str;
+}
+
+#[cast_to]
+impl Greet for Data {
+    fn greet(&self) -> &str {
+        "Hello"
+    }
+}
+
+castable_to!(Data => Greet, std::fmt::Debug, std::any::Any);
+
+impl std::fmt::Debug for Data {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str("Data")
+    }
+}
+
+#[test]
+fn test_validate_registry() {
+    let greet = StableKey::new("validate_registry::Data", "dyn validate_registry::Greet");
+    let any = StableKey::new("validate_registry::Data", "dyn core::any::Any");
+    let mut expected = vec![
+        RegistryWarning::Duplicate(greet),
+        RegistryWarning::AnyTarget(any),
+    ];
+    expected.sort();
+    assert_eq!(validate_registry(), expected);
+    assert_eq!(Data.greet(), "Hello");
+    assert_eq!(
+        RegistryWarning::Duplicate(greet).to_string(),
+        "the cast from validate_registry::Data to dyn validate_registry::Greet \
+         is registered more than once"
+    );
+}